@@ -1,11 +1,16 @@
-use std::time::Duration;
+use std::{path::PathBuf, time::Duration};
 
+use encoding_rs::{Encoding, UTF_8};
 use parser::SyntaxConfig;
 use regex::Regex;
+use syntax::ProjectConfig;
+use url::Url;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Config {
     pub root_dir: Option<String>,
+    pub root_document: Option<Url>,
+    pub default_encoding: &'static Encoding,
     pub build: BuildConfig,
     pub diagnostics: DiagnosticsConfig,
     pub formatting: FormattingConfig,
@@ -13,9 +18,12 @@ pub struct Config {
     pub symbols: SymbolConfig,
     pub syntax: SyntaxConfig,
     pub completion: CompletionConfig,
+    pub indexing: IndexingConfig,
+    pub citation: CitationConfig,
+    pub import: ImportConfig,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct BuildConfig {
     pub program: String,
     pub args: Vec<String>,
@@ -24,7 +32,7 @@ pub struct BuildConfig {
     pub output_dir: String,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct DiagnosticsConfig {
     pub allowed_patterns: Vec<Regex>,
     pub ignored_patterns: Vec<Regex>,
@@ -32,20 +40,20 @@ pub struct DiagnosticsConfig {
     pub delay: Duration,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ChktexConfig {
     pub on_open: bool,
     pub on_save: bool,
     pub on_edit: bool,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct SynctexConfig {
     pub program: String,
     pub args: Vec<String>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct FormattingConfig {
     pub tex_formatter: Formatter,
     pub bib_formatter: Formatter,
@@ -53,31 +61,50 @@ pub struct FormattingConfig {
     pub line_length: usize,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum Formatter {
     Null,
     Server,
     LatexIndent,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct LatexIndentConfig {
     pub local: Option<String>,
     pub modify_line_breaks: bool,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct SymbolConfig {
     pub allowed_patterns: Vec<Regex>,
     pub ignored_patterns: Vec<Regex>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct CompletionConfig {
     pub matcher: MatchingAlgo,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexingConfig {
+    pub enabled: bool,
+    pub exclude: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CitationConfig {
+    pub style: Option<PathBuf>,
+    pub locales: Option<PathBuf>,
+    pub key_pattern: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ImportConfig {
+    pub endpoint: Option<String>,
+    pub database: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone)]
 pub enum MatchingAlgo {
     Skim,
     SkimIgnoreCase,
@@ -85,10 +112,92 @@ pub enum MatchingAlgo {
     PrefixIgnoreCase,
 }
 
+impl Config {
+    /// Applies the settings of a project configuration file located in `dir`.
+    ///
+    /// Scalar settings of the project file take precedence over the settings of the client.
+    /// Environments, citation commands and diagnostic filters are added to the ones
+    /// of the client instead of replacing them.
+    pub fn merge(&self, project: &ProjectConfig, dir: &Url) -> Self {
+        let mut config = self.clone();
+        if let Some(root_document) = &project.root_document {
+            config.root_document = dir.join(root_document).ok();
+        }
+
+        if let Some(aux_directory) = &project.aux_directory {
+            config.build.output_dir = aux_directory.clone();
+        }
+
+        if let Some(program) = &project.build_executable {
+            config.build.program = program.clone();
+        }
+
+        if let Some(args) = &project.build_args {
+            config.build.args = args.clone();
+        }
+
+        let syntax = &mut config.syntax;
+        syntax
+            .math_environments
+            .extend(project.math_environments.iter().cloned());
+
+        syntax
+            .enum_environments
+            .extend(project.enum_environments.iter().cloned());
+
+        syntax
+            .verbatim_environments
+            .extend(project.verbatim_environments.iter().cloned());
+
+        syntax
+            .citation_commands
+            .extend(project.citation_commands.iter().cloned());
+
+        let diagnostics = &mut config.diagnostics;
+        diagnostics
+            .allowed_patterns
+            .extend(compile_patterns(&project.allowed_patterns));
+
+        diagnostics
+            .ignored_patterns
+            .extend(compile_patterns(&project.ignored_patterns));
+
+        if let Some(key_pattern) = &project.citation_key_pattern {
+            config.citation.key_pattern = Some(key_pattern.clone());
+        }
+
+        if let Ok(dir) = dir.to_file_path() {
+            if let Some(style) = &project.citation_style {
+                config.citation.style = Some(dir.join(style));
+            }
+
+            if let Some(locales) = &project.citation_locales {
+                config.citation.locales = Some(dir.join(locales));
+            }
+        }
+
+        config
+    }
+}
+
+fn compile_patterns(patterns: &[String]) -> impl Iterator<Item = Regex> + '_ {
+    patterns
+        .iter()
+        .filter_map(|pattern| match Regex::new(pattern) {
+            Ok(regex) => Some(regex),
+            Err(why) => {
+                log::warn!("Invalid pattern in project configuration: {why}");
+                None
+            }
+        })
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
             root_dir: None,
+            root_document: None,
+            default_encoding: UTF_8,
             build: BuildConfig::default(),
             diagnostics: DiagnosticsConfig::default(),
             formatting: FormattingConfig::default(),
@@ -96,6 +205,9 @@ impl Default for Config {
             symbols: SymbolConfig::default(),
             syntax: SyntaxConfig::default(),
             completion: CompletionConfig::default(),
+            indexing: IndexingConfig::default(),
+            citation: CitationConfig::default(),
+            import: ImportConfig::default(),
         }
     }
 }
@@ -172,3 +284,12 @@ impl Default for CompletionConfig {
         }
     }
 }
+
+impl Default for IndexingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            exclude: Vec::new(),
+        }
+    }
+}
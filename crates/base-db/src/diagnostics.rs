@@ -21,5 +21,14 @@ pub enum ErrorCode {
     ExpectingRCurly,
     ExpectingEq,
     ExpectingFieldValue,
+    MissingCrossref(String),
+    UndefinedCitation(String),
+    InvalidDate,
+    InvalidMonth(Option<u32>),
+    InvalidPageRange(String),
+    InvalidIsbn,
+    DoiWithPrefix(String),
+    InvalidUrl,
+    UndefinedString(String),
     Build(BuildError),
 }
@@ -1,4 +1,6 @@
-use rowan::{ast::AstNode, TextRange};
+use citeproc::field::{date::month_macro, FieldErrorKind};
+use rowan::{ast::AstNode, TextLen, TextRange, TextSize};
+use rustc_hash::FxHashSet;
 use syntax::bibtex::{self, HasDelims, HasEq, HasName, HasType, HasValue};
 
 use crate::{Document, DocumentData};
@@ -61,5 +63,143 @@ fn analyze_field(document: &mut Document, field: bibtex::Field) {
             range: field.name_token().unwrap().text_range(),
             code,
         });
+
+        return;
+    }
+
+    let Err(error) = citeproc::check_field(&field) else { return };
+    let code = match error.kind {
+        FieldErrorKind::InvalidDate => ErrorCode::InvalidDate,
+        FieldErrorKind::InvalidMonth(number) => ErrorCode::InvalidMonth(number),
+        FieldErrorKind::InvalidPageRange(pages) => ErrorCode::InvalidPageRange(pages),
+        FieldErrorKind::InvalidIsbn => ErrorCode::InvalidIsbn,
+        FieldErrorKind::DoiWithPrefix(doi) => ErrorCode::DoiWithPrefix(doi),
+        FieldErrorKind::InvalidUrl => ErrorCode::InvalidUrl,
+    };
+
+    document.diagnostics.push(Diagnostic {
+        range: error.range,
+        code,
+    });
+}
+
+/// The entry keys and `@string` names that are defined in the BibTeX files of a project
+/// together with the keys of its CSL-JSON and Hayagriva bibliographies.
+/// The names of the strings are stored in lowercase because BibTeX ignores their case.
+#[derive(Debug, Default)]
+pub struct BibDefinitions {
+    keys: FxHashSet<String>,
+    strings: FxHashSet<String>,
+    csl_keys: FxHashSet<String>,
+    has_bibliography: bool,
+}
+
+impl BibDefinitions {
+    pub fn new<'a>(documents: impl IntoIterator<Item = &'a Document>) -> Self {
+        let mut definitions = Self::default();
+        for document in documents {
+            match &document.data {
+                DocumentData::Bib(data) => {
+                    definitions.has_bibliography = true;
+                    let Some(root) = bibtex::Root::cast(data.root_node()) else { continue };
+                    definitions.keys.extend(
+                        root.entries()
+                            .filter_map(|entry| entry.name_token())
+                            .map(|token| token.text().to_string()),
+                    );
+
+                    definitions.strings.extend(
+                        root.strings()
+                            .filter_map(|string| string.name_token())
+                            .map(|token| token.text().to_lowercase()),
+                    );
+                }
+                DocumentData::Csl(data) => {
+                    definitions.has_bibliography = true;
+                    definitions
+                        .csl_keys
+                        .extend(data.bibliography.items.iter().map(|item| item.key.clone()));
+                }
+                DocumentData::Tex(_)
+                | DocumentData::Aux(_)
+                | DocumentData::Log(_)
+                | DocumentData::Fls(_)
+                | DocumentData::Root(_)
+                | DocumentData::Tectonic(_) => {}
+            }
+        }
+
+        definitions
+    }
+
+    /// Checks if the project contains at least one bibliography.
+    pub fn has_bibliography(&self) -> bool {
+        self.has_bibliography
     }
+
+    /// Checks if `key` can be cited, i.e. if it is defined by a BibTeX entry or a CSL item.
+    pub fn contains_citation(&self, key: &str) -> bool {
+        self.keys.contains(key) || self.csl_keys.contains(key)
+    }
+}
+
+/// Reports the keys of `crossref`, `xref`, `xdata` and `entryset` fields that refer to entries
+/// which are not defined in any BibTeX file of the project.
+pub fn analyze_crossrefs(document: &Document, definitions: &BibDefinitions) -> Vec<Diagnostic> {
+    let DocumentData::Bib(data) = &document.data else { return Vec::new() };
+
+    let mut diagnostics = Vec::new();
+    let root = bibtex::SyntaxNode::new_root(data.green.clone());
+    for field in root.descendants().filter_map(bibtex::Field::cast) {
+        let Some(name) = field.name_token() else { continue };
+        if !["crossref", "xref", "xdata", "entryset"]
+            .iter()
+            .any(|kind| name.text().eq_ignore_ascii_case(kind))
+        {
+            continue;
+        }
+
+        let Some(value) = field.value() else { continue };
+        let value_range = value.syntax().text_range();
+        let text = value.syntax().text().to_string();
+        let mut offset = 0;
+        for key in citeproc::field::text::parse_keys(&value) {
+            // Keys that come from a string reference do not appear in the source text.
+            let range = text[offset..].find(&key).map(|start| {
+                let start = offset + start;
+                offset = start + key.len();
+                let start = value_range.start() + TextSize::try_from(start).unwrap();
+                TextRange::at(start, key.as_str().text_len())
+            });
+
+            if !definitions.keys.contains(&key) {
+                diagnostics.push(Diagnostic {
+                    range: range.unwrap_or(value_range),
+                    code: ErrorCode::MissingCrossref(key),
+                });
+            }
+        }
+    }
+
+    diagnostics
+}
+
+/// Reports references to `@string` macros that are neither defined in any BibTeX file
+/// of the project nor one of the standard month macros.
+pub fn analyze_strings(document: &Document, definitions: &BibDefinitions) -> Vec<Diagnostic> {
+    let DocumentData::Bib(data) = &document.data else { return Vec::new() };
+
+    bibtex::SyntaxNode::new_root(data.green.clone())
+        .descendants()
+        .filter_map(bibtex::Literal::cast)
+        .filter_map(|literal| literal.name_token())
+        .filter(|name| {
+            !definitions.strings.contains(&name.text().to_lowercase())
+                && month_macro(name.text()).is_none()
+        })
+        .map(|name| Diagnostic {
+            range: name.text_range(),
+            code: ErrorCode::UndefinedString(name.text().into()),
+        })
+        .collect()
 }
@@ -11,18 +11,26 @@ pub fn analyze<'a>(
     workspace: &'a Workspace,
     log_document: &'a Document,
 ) -> FxHashMap<&'a Document, Vec<Diagnostic>> {
-    let mut results = FxHashMap::default();
-
-    let DocumentData::Log(data) = &log_document.data else { return results };
+    let DocumentData::Log(data) = &log_document.data else { return FxHashMap::default() };
 
     let parents = workspace.parents(log_document);
-    let Some(root_document) = parents.iter().next() else { return results };
+    let Some(root_document) = parents.first() else { return FxHashMap::default() };
+
+    analyze_errors(workspace, root_document, &data.errors)
+}
+
+pub fn analyze_errors<'a>(
+    workspace: &'a Workspace,
+    root_document: &'a Document,
+    errors: &[BuildError],
+) -> FxHashMap<&'a Document, Vec<Diagnostic>> {
+    let mut results = FxHashMap::default();
 
     let Some(base_path) = root_document.path
         .as_deref()
         .and_then(|path| path.parent()) else { return results };
 
-    for error in &data.errors {
+    for error in errors {
         let full_path = base_path.join(&error.relative_path);
         let Ok(full_path_uri) = Url::from_file_path(&full_path) else { continue };
         let tex_document = workspace.lookup(&full_path_uri).unwrap_or(root_document);
@@ -3,7 +3,7 @@ use syntax::latex;
 
 use crate::{Config, Document, DocumentData};
 
-use super::{Diagnostic, ErrorCode};
+use super::{bib::BibDefinitions, Diagnostic, ErrorCode};
 
 pub fn analyze(document: &mut Document, config: &Config) {
     if !document.uri.as_str().ends_with(".tex") {
@@ -114,3 +114,30 @@ fn analyze_curly_braces(document: &mut Document, node: latex::SyntaxNode) -> Opt
         None
     }
 }
+
+/// Reports citations whose key is not defined by any BibTeX entry or CSL-JSON/Hayagriva item
+/// of the project. Projects without a bibliography are not checked.
+pub fn analyze_citations(document: &Document, definitions: &BibDefinitions) -> Vec<Diagnostic> {
+    let DocumentData::Tex(data) = &document.data else { return Vec::new() };
+    if !definitions.has_bibliography() {
+        return Vec::new();
+    }
+
+    latex::SyntaxNode::new_root(data.green.clone())
+        .descendants()
+        .filter_map(latex::Citation::cast)
+        .filter_map(|citation| citation.key_list())
+        .flat_map(|list| list.keys())
+        .filter_map(|key| {
+            let text = key.to_string();
+            if text == "*" || definitions.contains_citation(&text) {
+                return None;
+            }
+
+            Some(Diagnostic {
+                range: latex::small_range(&key),
+                code: ErrorCode::UndefinedCitation(text),
+            })
+        })
+        .collect()
+}
@@ -1,7 +1,8 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use distro::Language;
-use syntax::{bibtex, latex, BuildError};
+use rowan::{GreenNode, TextRange};
+use syntax::{bibtex, csl, latex, BuildError, ProjectConfig, Recorder, TectonicManifest};
 use url::Url;
 
 use crate::{
@@ -39,6 +40,63 @@ impl Document {
         owner: Owner,
         cursor: LineCol,
         config: &Config,
+    ) -> Self {
+        let reuse = Reuse::default();
+        Self::parse_with_reuse(uri, text, language, owner, cursor, config, reuse)
+    }
+
+    /// Parses a TeX document like [`Document::parse`] but takes the semantics from a cache.
+    pub fn parse_with_semantics(
+        uri: Url,
+        text: String,
+        owner: Owner,
+        cursor: LineCol,
+        config: &Config,
+        semantics: semantics::tex::Semantics,
+    ) -> Self {
+        let reuse = Reuse {
+            green: None,
+            semantics: Some(semantics),
+        };
+
+        Self::parse_with_reuse(uri, text, Language::Tex, owner, cursor, config, reuse)
+    }
+
+    /// Replaces `delete` with `insert` and parses the resulting text.
+    /// The syntax tree of a LaTeX document is updated incrementally.
+    pub fn edit(
+        &self,
+        delete: TextRange,
+        insert: &str,
+        owner: Owner,
+        cursor: LineCol,
+        config: &Config,
+    ) -> Self {
+        let mut text = self.text.clone();
+        text.replace_range(std::ops::Range::<usize>::from(delete), insert);
+
+        let green = self
+            .data
+            .as_tex()
+            .map(|data| parser::reparse_latex(&data.green, &text, delete, insert, &config.syntax));
+
+        let reuse = Reuse {
+            green,
+            semantics: None,
+        };
+
+        let uri = self.uri.clone();
+        Self::parse_with_reuse(uri, text, self.language, owner, cursor, config, reuse)
+    }
+
+    fn parse_with_reuse(
+        uri: Url,
+        text: String,
+        language: Language,
+        owner: Owner,
+        cursor: LineCol,
+        config: &Config,
+        reuse: Reuse,
     ) -> Self {
         let dir = uri.join(".").unwrap();
 
@@ -53,15 +111,30 @@ impl Document {
         let diagnostics = Vec::new();
         let data = match language {
             Language::Tex => {
-                let green = parser::parse_latex(&text, &config.syntax);
-                let mut semantics = semantics::tex::Semantics::default();
-                semantics.process_root(&latex::SyntaxNode::new_root(green.clone()));
+                let green = reuse
+                    .green
+                    .unwrap_or_else(|| parser::parse_latex(&text, &config.syntax));
+
+                let semantics = reuse.semantics.unwrap_or_else(|| {
+                    let mut semantics = semantics::tex::Semantics::default();
+                    semantics.process_root(&latex::SyntaxNode::new_root(green.clone()));
+                    semantics
+                });
+
                 DocumentData::Tex(TexDocumentData { green, semantics })
             }
             Language::Bib => {
                 let green = parser::parse_bibtex(&text);
                 DocumentData::Bib(BibDocumentData { green })
             }
+            Language::CslJson => {
+                let bibliography = parser::parse_csl_json(&text);
+                DocumentData::Csl(CslDocumentData { bibliography })
+            }
+            Language::Hayagriva => {
+                let bibliography = parser::parse_hayagriva(&text);
+                DocumentData::Csl(CslDocumentData { bibliography })
+            }
             Language::Aux => {
                 let green = parser::parse_latex(&text, &config.syntax);
                 let mut semantics = semantics::auxiliary::Semantics::default();
@@ -72,8 +145,18 @@ impl Document {
                 let errors = parser::parse_build_log(&text).errors;
                 DocumentData::Log(LogDocumentData { errors })
             }
-            Language::Root => DocumentData::Root,
-            Language::Tectonic => DocumentData::Tectonic,
+            Language::Fls => {
+                let recorder = parser::parse_recorder(&text);
+                DocumentData::Fls(FlsDocumentData { recorder })
+            }
+            Language::Root => {
+                let config = parser::parse_project_config(&text);
+                DocumentData::Root(RootDocumentData { config })
+            }
+            Language::Tectonic => {
+                let manifest = parser::parse_tectonic(&text);
+                DocumentData::Tectonic(TectonicDocumentData { manifest })
+            }
         };
 
         let mut document = Self {
@@ -92,13 +175,22 @@ impl Document {
         match language {
             Language::Tex => diagnostics::tex::analyze(&mut document, config),
             Language::Bib => diagnostics::bib::analyze(&mut document),
-            Language::Aux | Language::Log | Language::Root | Language::Tectonic => (),
+            Language::CslJson | Language::Hayagriva => (),
+            Language::Aux | Language::Log | Language::Fls => (),
+            Language::Root | Language::Tectonic => (),
         };
 
         document
     }
 }
 
+/// The results of a previous analysis of a TeX document that do not need to be recomputed.
+#[derive(Default)]
+struct Reuse {
+    green: Option<GreenNode>,
+    semantics: Option<semantics::tex::Semantics>,
+}
+
 impl std::fmt::Debug for Document {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_tuple("Document").field(&self.uri.as_str()).finish()
@@ -135,10 +227,12 @@ impl std::hash::Hash for Document {
 pub enum DocumentData {
     Tex(TexDocumentData),
     Bib(BibDocumentData),
+    Csl(CslDocumentData),
     Aux(AuxDocumentData),
     Log(LogDocumentData),
-    Root,
-    Tectonic,
+    Fls(FlsDocumentData),
+    Root(RootDocumentData),
+    Tectonic(TectonicDocumentData),
 }
 
 impl DocumentData {
@@ -158,6 +252,14 @@ impl DocumentData {
         }
     }
 
+    pub fn as_csl(&self) -> Option<&CslDocumentData> {
+        if let DocumentData::Csl(data) = self {
+            Some(data)
+        } else {
+            None
+        }
+    }
+
     pub fn as_aux(&self) -> Option<&AuxDocumentData> {
         if let DocumentData::Aux(data) = self {
             Some(data)
@@ -165,6 +267,22 @@ impl DocumentData {
             None
         }
     }
+
+    pub fn as_root(&self) -> Option<&RootDocumentData> {
+        if let DocumentData::Root(data) = self {
+            Some(data)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_tectonic(&self) -> Option<&TectonicDocumentData> {
+        if let DocumentData::Tectonic(data) = self {
+            Some(data)
+        } else {
+            None
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -190,13 +308,64 @@ impl BibDocumentData {
     }
 }
 
+/// A bibliography in CSL-JSON or Hayagriva format.
+#[derive(Debug, Clone)]
+pub struct CslDocumentData {
+    pub bibliography: csl::Bibliography,
+}
+
 #[derive(Debug, Clone)]
 pub struct LogDocumentData {
     pub errors: Vec<BuildError>,
 }
 
+#[derive(Debug, Clone)]
+pub struct FlsDocumentData {
+    pub recorder: Recorder,
+}
+
+impl FlsDocumentData {
+    /// Returns the recorded input files that are located in the working directory of the engine.
+    pub fn project_inputs(&self) -> impl Iterator<Item = &Path> {
+        self.recorder
+            .inputs
+            .iter()
+            .filter(|path| !self.is_distro_input(path))
+            .map(PathBuf::as_path)
+    }
+
+    /// Returns the recorded input files that come from the TeX distribution
+    /// or from other locations outside of the project.
+    pub fn distro_inputs(&self) -> impl Iterator<Item = &Path> {
+        self.recorder
+            .inputs
+            .iter()
+            .filter(|path| self.is_distro_input(path))
+            .map(PathBuf::as_path)
+    }
+
+    fn is_distro_input(&self, path: &Path) -> bool {
+        match &self.recorder.working_dir {
+            Some(dir) => !path.starts_with(dir),
+            None => path.is_absolute(),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct AuxDocumentData {
     pub green: rowan::GreenNode,
     pub semantics: semantics::auxiliary::Semantics,
 }
+
+#[derive(Debug, Clone)]
+pub struct RootDocumentData {
+    /// The parsed project configuration or `None` if the file is not valid TOML.
+    pub config: Option<ProjectConfig>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TectonicDocumentData {
+    /// The parsed manifest or `None` if `Tectonic.toml` is not valid.
+    pub manifest: Option<TectonicManifest>,
+}
@@ -31,6 +31,7 @@ pub struct Graph<'a> {
     pub start: &'a Document,
     pub edges: Vec<Edge<'a>>,
     pub missing: Vec<Url>,
+    pub bibliographies: Vec<Url>,
 }
 
 impl<'a> Graph<'a> {
@@ -40,6 +41,7 @@ impl<'a> Graph<'a> {
             start,
             edges: Vec::new(),
             missing: Vec::new(),
+            bibliographies: Vec::new(),
         };
 
         let base_dir = workspace.current_dir(&start.dir);
@@ -56,7 +58,32 @@ impl<'a> Graph<'a> {
                 }
             }
 
+            let index = graph.edges.len();
+            graph.tectonic_edges(source);
+            for edge in &graph.edges[index..] {
+                if visited.insert(&edge.target.uri) {
+                    let base_dir = workspace.current_dir(&edge.target.dir);
+                    stack.push((edge.target, base_dir));
+                }
+            }
+
+            let index = graph.edges.len();
             graph.implicit_edges(source, &base_dir);
+            let recorders = graph.edges[index..]
+                .iter()
+                .map(|edge| edge.target)
+                .filter(|target| target.language == Language::Fls)
+                .collect::<Vec<_>>();
+
+            for recorder in recorders {
+                let index = graph.edges.len();
+                graph.recorded_edges(recorder);
+                for edge in &graph.edges[index..] {
+                    if visited.insert(&edge.target.uri) {
+                        stack.push((edge.target, base_dir.clone()));
+                    }
+                }
+            }
         }
 
         graph
@@ -103,6 +130,10 @@ impl<'a> Graph<'a> {
             .flat_map(|file_name| base_dir.join(file_name))
             .chain(distro_files)
         {
+            if link.kind == semantics::tex::LinkKind::Bib {
+                self.bibliographies.push(target_uri.clone());
+            }
+
             match self.workspace.lookup(&target_uri) {
                 Some(target) => {
                     let new_base_dir = link
@@ -130,11 +161,73 @@ impl<'a> Graph<'a> {
         }
     }
 
+    fn tectonic_edges(&mut self, source: &'a Document) {
+        let Some(data) = source.data.as_tectonic() else { return };
+        let Some(manifest) = &data.manifest else { return };
+        let Ok(src_dir) = source.uri.join("src/") else { return };
+        let inputs = manifest
+            .outputs
+            .iter()
+            .flat_map(|output| &output.inputs)
+            .flat_map(|input| src_dir.join(input));
+
+        // Tectonic writes the log of an output next to the output file itself.
+        let logs = manifest.outputs.iter().flat_map(|output| {
+            let name = format!("{}{}.log", output.build_dir(), output.name);
+            source.uri.join(&name)
+        });
+
+        for target_uri in inputs.chain(logs) {
+            match self.workspace.lookup(&target_uri) {
+                Some(target) => {
+                    self.edges.push(Edge {
+                        source,
+                        target,
+                        weight: None,
+                    });
+                }
+                None => {
+                    self.missing.push(target_uri);
+                }
+            };
+        }
+    }
+
     fn implicit_edges(&mut self, source: &'a Document, base_dir: &Url) {
         let uri = source.uri.as_str();
         if source.language == Language::Tex && !uri.ends_with(".aux") {
             self.implicit_edge(source, base_dir, "log");
             self.implicit_edge(source, base_dir, "aux");
+            self.implicit_edge(source, base_dir, "fls");
+        }
+    }
+
+    fn recorded_edges(&mut self, source: &'a Document) {
+        let DocumentData::Fls(data) = &source.data else { return };
+        for path in data.project_inputs() {
+            if !matches!(Language::from_path(path), Some(Language::Tex | Language::Bib)) {
+                continue;
+            }
+
+            let target_uri = if path.is_absolute() {
+                Url::from_file_path(path).ok()
+            } else {
+                path.to_str().and_then(|path| source.dir.join(path).ok())
+            };
+
+            let Some(target_uri) = target_uri else { continue };
+            match self.workspace.lookup(&target_uri) {
+                Some(target) => {
+                    self.edges.push(Edge {
+                        source,
+                        target,
+                        weight: None,
+                    });
+                }
+                None => {
+                    self.missing.push(target_uri);
+                }
+            };
         }
     }
 
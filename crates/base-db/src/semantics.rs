@@ -1,7 +1,12 @@
 pub mod auxiliary;
+mod cache;
 pub mod tex;
 
-#[derive(PartialEq, Eq, Clone, Hash)]
+use serde::{Deserialize, Serialize};
+
+pub use self::cache::SemanticsCache;
+
+#[derive(PartialEq, Eq, Clone, Hash, Serialize, Deserialize)]
 pub struct Span {
     pub text: String,
     pub range: rowan::TextRange,
@@ -0,0 +1,186 @@
+use std::{
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+};
+
+use parser::SyntaxConfig;
+use rustc_hash::{FxHashMap, FxHasher};
+use serde::{Deserialize, Serialize};
+
+use super::tex;
+
+/// Needs to be incremented whenever the format of the cache file
+/// or the semantic analysis of TeX documents changes.
+const CACHE_VERSION: u32 = 1;
+
+const CACHE_FILE_NAME: &str = "semantics.json";
+
+/// Persists the semantics of the TeX documents that are loaded from disk.
+/// An entry is only used if neither the text of the document
+/// nor the syntax configuration have changed since it was written.
+#[derive(Debug, Default)]
+pub struct SemanticsCache {
+    dir: Option<PathBuf>,
+    entries: FxHashMap<PathBuf, CacheEntry>,
+    changed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    path: PathBuf,
+    key: u64,
+    semantics: tex::Semantics,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheFile {
+    version: u32,
+    entries: Vec<CacheEntry>,
+}
+
+impl SemanticsCache {
+    /// Loads the cache from `dir`. A missing cache or a cache that has been written
+    /// by another version of the server results in an empty cache.
+    pub fn load(dir: &Path) -> Self {
+        let entries = std::fs::read(dir.join(CACHE_FILE_NAME))
+            .ok()
+            .and_then(|data| serde_json::from_slice::<CacheFile>(&data).ok())
+            .filter(|cache| cache.version == CACHE_VERSION)
+            .map(|cache| cache.entries)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|entry| (entry.path.clone(), entry))
+            .collect();
+
+        Self {
+            dir: Some(dir.to_path_buf()),
+            entries,
+            changed: false,
+        }
+    }
+
+    /// Computes the key of a TeX document that is compared with the cached entry.
+    pub fn key(text: &str, config: &SyntaxConfig) -> u64 {
+        let mut hasher = FxHasher::default();
+        text.hash(&mut hasher);
+        for names in [
+            &config.math_environments,
+            &config.enum_environments,
+            &config.verbatim_environments,
+            &config.citation_commands,
+        ] {
+            let mut names: Vec<_> = names.iter().collect();
+            names.sort();
+            names.hash(&mut hasher);
+        }
+
+        hasher.finish()
+    }
+
+    pub fn get(&self, path: &Path, key: u64) -> Option<&tex::Semantics> {
+        self.entries
+            .get(path)
+            .filter(|entry| entry.key == key)
+            .map(|entry| &entry.semantics)
+    }
+
+    pub fn insert(&mut self, path: PathBuf, key: u64, semantics: tex::Semantics) {
+        if self.dir.is_none() {
+            return;
+        }
+
+        let entry = CacheEntry {
+            path: path.clone(),
+            key,
+            semantics,
+        };
+
+        self.entries.insert(path, entry);
+        self.changed = true;
+    }
+
+    /// Writes the cache back to disk if it has changed. Entries of deleted files are dropped.
+    pub fn store(&mut self) -> std::io::Result<()> {
+        let Some(dir) = &self.dir else { return Ok(()) };
+        if !self.changed {
+            return Ok(());
+        }
+
+        self.entries.retain(|path, _| path.is_file());
+        let cache = CacheFile {
+            version: CACHE_VERSION,
+            entries: self.entries.values().cloned().collect(),
+        };
+
+        std::fs::create_dir_all(dir)?;
+        std::fs::write(dir.join(CACHE_FILE_NAME), serde_json::to_vec(&cache)?)?;
+        self.changed = false;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use parser::SyntaxConfig;
+    use rowan::TextRange;
+
+    use crate::semantics::{tex, Span};
+
+    use super::SemanticsCache;
+
+    fn semantics() -> tex::Semantics {
+        let mut semantics = tex::Semantics::default();
+        semantics.labels.push(tex::Label {
+            kind: tex::LabelKind::Definition,
+            name: Span {
+                text: "foo".into(),
+                range: TextRange::new(7.into(), 10.into()),
+            },
+            targets: Vec::new(),
+            full_range: TextRange::new(0.into(), 11.into()),
+        });
+
+        semantics.can_be_root = true;
+        semantics
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("main.tex");
+        std::fs::write(&path, "\\label{foo}").unwrap();
+
+        let key = SemanticsCache::key("\\label{foo}", &SyntaxConfig::default());
+        let mut cache = SemanticsCache::load(&dir.path().join("cache"));
+        cache.insert(path.clone(), key, semantics());
+        cache.store().unwrap();
+
+        let cache = SemanticsCache::load(&dir.path().join("cache"));
+        let semantics = cache.get(&path, key).unwrap();
+        assert!(semantics.can_be_root);
+        assert_eq!(semantics.labels[0].name.text, "foo");
+        assert!(cache.get(&path, key + 1).is_none());
+    }
+
+    #[test]
+    fn test_deleted_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("main.tex");
+
+        let key = SemanticsCache::key("\\label{foo}", &SyntaxConfig::default());
+        let mut cache = SemanticsCache::load(&dir.path().join("cache"));
+        cache.insert(path.clone(), key, semantics());
+        cache.store().unwrap();
+
+        let cache = SemanticsCache::load(&dir.path().join("cache"));
+        assert!(cache.get(&path, key).is_none());
+    }
+
+    #[test]
+    fn test_config_changed() {
+        let mut config = SyntaxConfig::default();
+        let key = SemanticsCache::key("\\label{foo}", &config);
+        config.verbatim_environments.insert("foo".into());
+        assert_ne!(SemanticsCache::key("\\label{foo}", &config), key);
+    }
+}
@@ -1,11 +1,14 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
 use rowan::{ast::AstNode, TextLen};
 use rustc_hash::FxHashSet;
+use serde::{Deserialize, Serialize};
 use syntax::latex::{self, HasBrack, HasCurly};
-use text_size::TextRange;
+use text_size::{TextRange, TextSize};
 
 use super::Span;
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Semantics {
     pub links: Vec<Link>,
     pub labels: Vec<Label>,
@@ -15,6 +18,7 @@ pub struct Semantics {
     pub graphics_paths: FxHashSet<String>,
     pub can_be_root: bool,
     pub can_be_compiled: bool,
+    pub magic_comments: MagicComments,
 }
 
 impl Semantics {
@@ -25,7 +29,9 @@ impl Semantics {
                     self.process_node(&node);
                 }
                 latex::SyntaxElement::Token(token) => {
-                    if token.kind() == latex::COMMAND_NAME {
+                    if token.kind() == latex::COMMENT {
+                        self.process_comment(&token);
+                    } else if token.kind() == latex::COMMAND_NAME {
                         let range = token.text_range();
                         let range = TextRange::new(range.start() + "\\".text_len(), range.end());
                         let text = String::from(&token.text()[1..]);
@@ -60,6 +66,33 @@ impl Semantics {
         }
     }
 
+    fn process_comment(&mut self, token: &latex::SyntaxToken) {
+        let Some(captures) = MAGIC_COMMENT_REGEX.captures(token.text()) else { return };
+        let value = captures.get(3).unwrap();
+        if value.as_str().is_empty() {
+            return;
+        }
+
+        let start = token.text_range().start() + TextSize::from(value.start() as u32);
+        let span = Span {
+            text: value.as_str().into(),
+            range: TextRange::at(start, value.as_str().text_len()),
+        };
+
+        let magic = &mut self.magic_comments;
+        let slot = match (
+            captures[1].to_ascii_lowercase().as_str(),
+            captures[2].to_ascii_lowercase().as_str(),
+        ) {
+            ("tex", "root") => &mut magic.root,
+            ("tex", "program") => &mut magic.program,
+            ("bib", "program") => &mut magic.bib_program,
+            _ => return,
+        };
+
+        slot.get_or_insert(span);
+    }
+
     fn process_include(&mut self, include: latex::Include) {
         let Some(list) = include.path_list() else { return };
 
@@ -234,7 +267,23 @@ impl Semantics {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+/// Matches magic comments like `% !TEX root = main.tex` or `% !BIB program = biber`.
+static MAGIC_COMMENT_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)^%\s*!\s*(TEX|BIB)\s+(?:TS-)?(root|program)\s*=\s*(.*?)\s*$").unwrap()
+});
+
+/// The magic comments of a document. If a comment occurs multiple times, the first one wins.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MagicComments {
+    /// The root document relative to the directory of the document.
+    pub root: Option<Span>,
+    /// The TeX engine that compiles the project.
+    pub program: Option<Span>,
+    /// The program that processes the bibliography.
+    pub bib_program: Option<Span>,
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash, Serialize, Deserialize)]
 pub enum LinkKind {
     Sty,
     Cls,
@@ -253,21 +302,21 @@ impl LinkKind {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Serialize, Deserialize)]
 pub struct Link {
     pub kind: LinkKind,
     pub path: Span,
     pub base_dir: Option<String>,
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash, Serialize, Deserialize)]
 pub enum LabelKind {
     Definition,
     Reference,
     ReferenceRange,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Label {
     pub kind: LabelKind,
     pub name: Span,
@@ -275,13 +324,13 @@ pub struct Label {
     pub full_range: TextRange,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LabelTarget {
     pub object: LabelObject,
     pub range: TextRange,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum LabelObject {
     Section {
         prefix: String,
@@ -295,7 +344,7 @@ pub enum LabelObject {
     },
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TheoremDefinition {
     pub name: Span,
     pub heading: String,
@@ -1,8 +1,10 @@
+mod encoding;
 mod label;
 mod line_index;
 mod regex_filter;
 
 pub use self::{
+    encoding::{declared_encoding, decode},
     label::{render_label, FloatKind, RenderedLabel, RenderedObject},
     line_index::{LineCol, LineColUtf16, LineIndex},
     regex_filter::filter_regex_patterns,
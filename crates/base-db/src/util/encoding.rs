@@ -0,0 +1,148 @@
+use encoding_rs::{Encoding, UTF_8};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// Decodes the contents of a file and returns the text together with the encoding that was used.
+///
+/// A `% !TEX encoding` comment always takes precedence. Otherwise, valid UTF-8 is kept as is
+/// and the encoding of other files is taken from `\usepackage[...]{inputenc}`
+/// or the configured default.
+pub fn decode(data: &[u8], default: &'static Encoding) -> (String, &'static Encoding) {
+    let text = String::from_utf8_lossy(data);
+    let encoding = magic_encoding(&text).or_else(|| {
+        if std::str::from_utf8(data).is_ok() {
+            Some(UTF_8)
+        } else {
+            inputenc_encoding(&text)
+        }
+    });
+
+    let encoding = encoding.unwrap_or(default);
+    if encoding == UTF_8 {
+        return (text.into_owned(), UTF_8);
+    }
+
+    let (text, _) = encoding.decode_without_bom_handling(data);
+    (text.into_owned(), encoding)
+}
+
+/// Returns the encoding that is declared by the document itself, if any.
+pub fn declared_encoding(text: &str) -> Option<&'static Encoding> {
+    magic_encoding(text).or_else(|| inputenc_encoding(text))
+}
+
+fn magic_encoding(text: &str) -> Option<&'static Encoding> {
+    let captures = MAGIC_ENCODING_REGEX.captures(text)?;
+    let label = captures[1].to_lowercase();
+    let label = match label.as_str() {
+        "utf-8 unicode" => "utf-8",
+        "isolatin" | "windowslatin1" => "windows-1252",
+        "isolatin2" => "iso-8859-2",
+        "isolatin5" => "iso-8859-9",
+        "isolatin9" => "iso-8859-15",
+        "windowslatin2" => "windows-1250",
+        "macosroman" => "macintosh",
+        label => label,
+    };
+
+    Encoding::for_label(label.as_bytes())
+}
+
+fn inputenc_encoding(text: &str) -> Option<&'static Encoding> {
+    let captures = INPUTENC_REGEX.captures(text)?;
+    captures[1].split(',').rev().find_map(|option| {
+        let label = match option.trim() {
+            "ansinew" => "windows-1252",
+            "applemac" => "macintosh",
+            "latin9" => "iso-8859-15",
+            "latin10" => "iso-8859-16",
+            "utf8x" => "utf-8",
+            label => label,
+        };
+
+        Encoding::for_label(label.as_bytes())
+    })
+}
+
+static MAGIC_ENCODING_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?im)^\s*%\s*!\s*TEX\s+(?:TS-)?encoding\s*=\s*(.*?)\s*$").unwrap());
+
+static INPUTENC_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?m)^[^%\n]*\\usepackage\s*\[([^\]]*)\]\s*\{\s*inputenc\s*\}").unwrap()
+});
+
+#[cfg(test)]
+mod tests {
+    use encoding_rs::{ISO_8859_2, UTF_8, WINDOWS_1252};
+
+    use super::{declared_encoding, decode};
+
+    #[test]
+    fn test_inputenc() {
+        let text = "\\documentclass{article}\n\\usepackage[latin1]{inputenc}\n";
+        assert_eq!(declared_encoding(text), Some(WINDOWS_1252));
+    }
+
+    #[test]
+    fn test_inputenc_last_option() {
+        let text = "\\usepackage[utf8,latin2]{inputenc}\n";
+        assert_eq!(declared_encoding(text), Some(ISO_8859_2));
+    }
+
+    #[test]
+    fn test_inputenc_commented() {
+        let text = "% \\usepackage[latin1]{inputenc}\n";
+        assert_eq!(declared_encoding(text), None);
+    }
+
+    #[test]
+    fn test_magic_comment() {
+        let text = "% !TEX encoding = IsoLatin2\n\\usepackage[latin1]{inputenc}\n";
+        assert_eq!(declared_encoding(text), Some(ISO_8859_2));
+    }
+
+    #[test]
+    fn test_magic_comment_ts() {
+        let text = "%!TEX TS-encoding = UTF-8 Unicode\n";
+        assert_eq!(declared_encoding(text), Some(UTF_8));
+    }
+
+    #[test]
+    fn test_none() {
+        assert_eq!(declared_encoding("\\documentclass{article}\n"), None);
+    }
+
+    #[test]
+    fn test_decode_utf8() {
+        let (text, encoding) = decode("Grüße".as_bytes(), WINDOWS_1252);
+        assert_eq!(text, "Grüße");
+        assert_eq!(encoding, UTF_8);
+    }
+
+    #[test]
+    fn test_decode_default() {
+        let (text, encoding) = decode(b"Gr\xfc\xdfe", WINDOWS_1252);
+        assert_eq!(text, "Grüße");
+        assert_eq!(encoding, WINDOWS_1252);
+    }
+
+    #[test]
+    fn test_decode_inputenc() {
+        let data = b"\\usepackage[latin2]{inputenc}\n\xb1";
+        let (text, encoding) = decode(data, WINDOWS_1252);
+        assert_eq!(text, "\\usepackage[latin2]{inputenc}\ną");
+        assert_eq!(encoding, ISO_8859_2);
+    }
+
+    #[test]
+    fn test_round_trip_latin1() {
+        let data = b"\\usepackage[latin1]{inputenc}\nCaf\xe9 \x80 \xe6\xf8\xe5\n";
+        let (text, encoding) = decode(data, UTF_8);
+        assert_eq!(encoding, WINDOWS_1252);
+        assert_eq!(text, "\\usepackage[latin1]{inputenc}\nCafé € æøå\n");
+
+        let (encoded, _, had_errors) = encoding.encode(&text);
+        assert!(!had_errors);
+        assert_eq!(&encoded[..], &data[..]);
+    }
+}
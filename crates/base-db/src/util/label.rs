@@ -6,7 +6,7 @@ use self::RenderedObject::*;
 
 use crate::{
     semantics::tex::{Label, LabelObject},
-    Project, Workspace,
+    Document, Project, Workspace,
 };
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
@@ -120,6 +120,7 @@ impl<'a> RenderedLabel<'a> {
 pub fn render_label<'a>(
     workspace: &'a Workspace,
     project: &Project<'a>,
+    document: &Document,
     label: &'a Label,
 ) -> Option<RenderedLabel<'a>> {
     let number = project
@@ -150,7 +151,7 @@ pub fn render_label<'a>(
                 options,
                 caption,
             } => {
-                let config = &workspace.config().syntax;
+                let config = &workspace.config_for(&document.uri).syntax;
                 if config.math_environments.contains(name.as_str()) {
                     return Some(RenderedLabel {
                         range: target.range,
@@ -1,16 +1,25 @@
 use std::{
-    borrow::{Borrow, Cow},
+    borrow::Borrow,
+    cmp::Reverse,
     path::{Path, PathBuf},
 };
 
 use distro::{Distro, Language};
-use itertools::Itertools;
+use encoding_rs::UTF_8;
+use once_cell::sync::OnceCell;
+use parking_lot::Mutex;
 use rowan::TextRange;
-use rustc_hash::FxHashSet;
+use rustc_hash::{FxHashMap, FxHashSet};
+use syntax::{ProjectConfig, TectonicManifest};
 use text_size::TextLen;
 use url::Url;
 
-use crate::{graph, util::LineCol, Config, Document, DocumentData, Owner};
+use crate::{
+    graph,
+    semantics::SemanticsCache,
+    util::{self, LineCol},
+    Config, Document, DocumentData, Owner,
+};
 
 #[derive(Debug, Default)]
 pub struct Workspace {
@@ -18,6 +27,11 @@ pub struct Workspace {
     config: Config,
     distro: Distro,
     folders: Vec<PathBuf>,
+    index: OnceCell<ProjectIndex>,
+    partial_index: Mutex<ProjectIndex>,
+    scopes: OnceCell<Vec<ConfigScope>>,
+    pinned_roots: FxHashMap<Url, Url>,
+    semantics_cache: SemanticsCache,
 }
 
 impl Workspace {
@@ -34,6 +48,16 @@ impl Workspace {
             .find(|document| document.path.as_deref() == Some(path))
     }
 
+    /// Returns the language of `uri` if it is the target of a bibliography link
+    /// like `\addbibresource{references.json}`.
+    pub fn bibliography_language(&self, uri: &Url) -> Option<Language> {
+        if !self.index().bibliographies.contains(uri) {
+            return None;
+        }
+
+        Language::from_bibliography_path(Path::new(uri.path()))
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = &Document> + '_ {
         self.documents.iter()
     }
@@ -42,6 +66,19 @@ impl Workspace {
         &self.config
     }
 
+    /// Returns the configuration that applies to the document `uri`.
+    ///
+    /// The settings of the client are merged with the project configuration files
+    /// (`texlab.toml` or `.texlabroot`) in the ancestor directories of the document,
+    /// starting with the outermost one. Hence, the innermost file has the highest precedence.
+    pub fn config_for(&self, uri: &Url) -> &Config {
+        self.scopes()
+            .iter()
+            .rev()
+            .find(|scope| uri.as_str().starts_with(scope.dir.as_str()))
+            .map_or(&self.config, |scope| &scope.config)
+    }
+
     pub fn distro(&self) -> &Distro {
         &self.distro
     }
@@ -55,33 +92,182 @@ impl Workspace {
         cursor: LineCol,
     ) {
         log::debug!("Opening document {uri}...");
-        self.documents.remove(&uri);
-        self.documents.insert(Document::parse(
-            uri,
-            text,
-            language,
-            owner,
-            cursor,
-            &self.config,
-        ));
+        if self.reopen(&uri, &text, language, owner, cursor) {
+            return;
+        }
+
+        let config = self.config_for(&uri);
+        let document = Document::parse(uri, text, language, owner, cursor, config);
+        self.insert(document);
+    }
+
+    /// Updates the owner and the cursor of `uri` without parsing the document again
+    /// if it has already been opened with the same text.
+    fn reopen(
+        &mut self,
+        uri: &Url,
+        text: &str,
+        language: Language,
+        owner: Owner,
+        cursor: LineCol,
+    ) -> bool {
+        let Some(document) = self
+            .lookup(uri)
+            .filter(|document| document.language == language && document.text == text)
+        else {
+            return false;
+        };
+
+        let mut document = document.clone();
+        document.owner = owner;
+        document.cursor = cursor;
+        self.documents.replace(document);
+        true
+    }
+
+    fn insert(&mut self, document: Document) {
+        let old = self.lookup(&document.uri);
+        let old_config = old.and_then(project_config).cloned();
+        let marker_changed = match old {
+            Some(old) => is_marker(old) != is_marker(&document),
+            None => is_marker(&document),
+        };
+
+        if marker_changed {
+            self.invalidate_all();
+        } else if old.map_or(true, |old| !has_same_edges(old, &document)) {
+            let uri = document.uri.clone();
+            self.invalidate_index(&uri);
+        }
+
+        let new_config = project_config(&document).cloned();
+        self.documents.replace(document);
+        self.update_scopes(old_config, new_config);
+    }
+
+    fn update_scopes(&mut self, old: Option<ProjectConfig>, new: Option<ProjectConfig>) {
+        let old = old.unwrap_or_default();
+        let new = new.unwrap_or_default();
+        if old == new {
+            return;
+        }
+
+        self.scopes.take();
+        self.invalidate_all();
+        if old.math_environments != new.math_environments
+            || old.enum_environments != new.enum_environments
+            || old.verbatim_environments != new.verbatim_environments
+            || old.citation_commands != new.citation_commands
+        {
+            self.reload();
+        }
     }
 
     pub fn load(&mut self, path: &Path, language: Language, owner: Owner) -> std::io::Result<()> {
         log::debug!("Loading document {} from disk...", path.display());
         let uri = Url::from_file_path(path).unwrap();
-        let data = std::fs::read(path)?;
-        let text = match String::from_utf8_lossy(&data) {
-            Cow::Borrowed(_) => unsafe { String::from_utf8_unchecked(data) },
-            Cow::Owned(text) => text,
-        };
+        let text = self.read(path, &uri)?;
+        let cursor = LineCol { line: 0, col: 0 };
+        if language != Language::Tex {
+            self.open(uri, text, language, owner, cursor);
+            return Ok(());
+        }
 
-        self.open(uri, text, language, owner, LineCol { line: 0, col: 0 });
+        log::debug!("Opening document {uri}...");
+        if self.reopen(&uri, &text, language, owner, cursor) {
+            return Ok(());
+        }
+
+        let document = self.parse_tex(path, uri, text, owner);
+        self.insert_loaded(document);
         Ok(())
     }
 
+    /// Reads and parses a file like [`Workspace::load`] without adding it to the workspace.
+    /// This allows parsing many files while only holding a read lock.
+    pub fn parse_file(
+        &self,
+        path: &Path,
+        language: Language,
+        owner: Owner,
+    ) -> std::io::Result<Document> {
+        log::debug!("Loading document {} from disk...", path.display());
+        let uri = Url::from_file_path(path).unwrap();
+        let text = self.read(path, &uri)?;
+        if language == Language::Tex {
+            return Ok(self.parse_tex(path, uri, text, owner));
+        }
+
+        let cursor = LineCol { line: 0, col: 0 };
+        let config = self.config_for(&uri);
+        Ok(Document::parse(uri, text, language, owner, cursor, config))
+    }
+
+    /// Adds the documents that have been parsed with [`Workspace::parse_file`].
+    /// Documents that have been opened in the meantime are kept.
+    pub fn extend(&mut self, documents: impl IntoIterator<Item = Document>) {
+        for document in documents {
+            if self.lookup(&document.uri).is_none() {
+                self.insert_loaded(document);
+            }
+        }
+    }
+
+    fn read(&self, path: &Path, uri: &Url) -> std::io::Result<String> {
+        let data = std::fs::read(path)?;
+        let (text, encoding) = util::decode(&data, self.config_for(uri).default_encoding);
+        if encoding != UTF_8 {
+            log::debug!("Decoded {} as {}", path.display(), encoding.name());
+        }
+
+        Ok(text)
+    }
+
+    /// Parses a TeX document that has been read from `path`. The semantics are taken
+    /// from the cache if neither the text nor the syntax configuration have changed.
+    fn parse_tex(&self, path: &Path, uri: Url, text: String, owner: Owner) -> Document {
+        let cursor = LineCol { line: 0, col: 0 };
+        let config = self.config_for(&uri);
+        let key = SemanticsCache::key(&text, &config.syntax);
+        match self.semantics_cache.get(path, key) {
+            Some(semantics) => {
+                let semantics = semantics.clone();
+                Document::parse_with_semantics(uri, text, owner, cursor, config, semantics)
+            }
+            None => Document::parse(uri, text, Language::Tex, owner, cursor, config),
+        }
+    }
+
+    /// Inserts a document that has been loaded from disk and caches its semantics.
+    fn insert_loaded(&mut self, document: Document) {
+        if let (Some(path), Some(data)) = (&document.path, document.data.as_tex()) {
+            let config = self.config_for(&document.uri);
+            let key = SemanticsCache::key(&document.text, &config.syntax);
+            if self.semantics_cache.get(path, key).is_none() {
+                self.semantics_cache
+                    .insert(path.clone(), key, data.semantics.clone());
+            }
+        }
+
+        self.insert(document);
+    }
+
+    /// Reads the semantics of TeX documents that have been cached in `dir`
+    /// by a previous session. Without a cache, the semantics are not persisted.
+    pub fn load_semantics_cache(&mut self, dir: &Path) {
+        self.semantics_cache = SemanticsCache::load(dir);
+    }
+
+    /// Writes the semantics of the TeX documents that have been loaded from disk to the cache.
+    pub fn store_semantics_cache(&mut self) {
+        if let Err(why) = self.semantics_cache.store() {
+            log::warn!("Unable to cache the semantics of the documents: {why}");
+        }
+    }
+
     pub fn edit(&mut self, uri: &Url, delete: TextRange, insert: &str) -> Option<()> {
         let document = self.lookup(uri)?;
-        let mut text = document.text.clone();
+        let text = &document.text;
         let cursor = if delete.len() == text.text_len() {
             let line = document.cursor.line.min(text.lines().count() as u32);
             LineCol { line, col: 0 }
@@ -89,15 +275,9 @@ impl Workspace {
             document.line_index.line_col(delete.start())
         };
 
-        text.replace_range(std::ops::Range::<usize>::from(delete), insert);
-        self.open(
-            document.uri.clone(),
-            text,
-            document.language,
-            Owner::Client,
-            cursor,
-        );
-
+        let config = self.config_for(uri);
+        let document = document.edit(delete, insert, Owner::Client, cursor, config);
+        self.insert(document);
         Some(())
     }
 
@@ -106,6 +286,19 @@ impl Workspace {
         watcher: &mut dyn notify::Watcher,
         watched_dirs: &mut FxHashSet<PathBuf>,
     ) {
+        let tectonic_dirs = self
+            .iter()
+            .filter_map(|document| {
+                Some((document, document.data.as_tectonic()?.manifest.as_ref()?))
+            })
+            .flat_map(|(document, manifest)| {
+                manifest
+                    .outputs
+                    .iter()
+                    .flat_map(|output| document.uri.join(&output.build_dir()))
+            })
+            .map(|dir| dir.to_file_path());
+
         self.iter()
             .filter(|document| document.uri.scheme() == "file")
             .flat_map(|document| {
@@ -113,6 +306,7 @@ impl Workspace {
                 let dir2 = &document.dir;
                 [dir1.to_file_path(), dir2.to_file_path()]
             })
+            .chain(tectonic_dirs)
             .flatten()
             .for_each(|path| {
                 if !watched_dirs.contains(&path) {
@@ -129,14 +323,17 @@ impl Workspace {
         }
 
         self.iter()
-            .filter(|document| matches!(document.data, DocumentData::Root | DocumentData::Tectonic))
-            .flat_map(|document| document.uri.join("."))
+            .filter_map(|document| match document.data {
+                DocumentData::Root(_) => document.uri.join(".").ok(),
+                DocumentData::Tectonic(_) => document.uri.join("src/").ok(),
+                _ => None,
+            })
             .find(|root_dir| base_dir.as_str().starts_with(root_dir.as_str()))
             .unwrap_or_else(|| base_dir.clone())
     }
 
     pub fn output_dir(&self, base_dir: &Url) -> Url {
-        let mut path = self.config.build.output_dir.clone();
+        let mut path = self.config_for(base_dir).build.output_dir.clone();
         if !path.ends_with('/') {
             path.push('/');
         }
@@ -153,40 +350,125 @@ impl Workspace {
     }
 
     pub fn project(&self, child: &Document) -> Project {
-        let mut documents = FxHashSet::default();
-        for graph in self
-            .iter()
-            .map(|start| graph::Graph::new(self, start))
-            .filter(|graph| graph.preorder().contains(&child))
-        {
-            documents.extend(graph.preorder());
-        }
+        let documents = self
+            .index()
+            .graphs
+            .values()
+            .map(|graph| &graph.reachable)
+            .filter(|uris| uris.contains(&child.uri))
+            .flatten()
+            .filter_map(|uri| self.lookup(uri))
+            .collect();
 
         Project { documents }
     }
 
-    pub fn parents(&self, child: &Document) -> FxHashSet<&Document> {
-        self.iter()
+    /// Returns the root documents that can compile `child`, ordered by their rank.
+    ///
+    /// A root that has been selected explicitly is the only candidate. In order of precedence,
+    /// it is either pinned with [`Workspace::pin_root`], declared with a `% !TEX root` magic comment
+    /// or configured as `root_document` in the project configuration. Otherwise, every compilable
+    /// document that includes `child` is a candidate. Candidates are ranked by preferring `child`
+    /// itself, then documents in the closest ancestor directory of `child`,
+    /// then documents that are opened in the editor and finally by their URI.
+    pub fn parents(&self, child: &Document) -> Vec<&Document> {
+        if let Some(root) = self.explicit_root(child) {
+            return vec![root];
+        }
+
+        let index = self.index();
+        let mut parents: Vec<_> = self
+            .iter()
             .filter(|document| {
                 let DocumentData::Tex(data) = &document.data else { return false };
                 data.semantics.can_be_root
             })
             .filter(|parent| {
-                let graph = graph::Graph::new(self, parent);
-                let mut nodes = graph.preorder();
-                nodes.contains(&child)
+                index
+                    .reachable(&parent.uri)
+                    .map_or(false, |uris| uris.contains(&child.uri))
+            })
+            .collect();
+
+        let rank = |parent: &Document| {
+            let is_ancestor = child.dir.as_str().starts_with(parent.dir.as_str());
+            (
+                parent.uri != child.uri,
+                !is_ancestor,
+                Reverse(parent.dir.as_str().len()),
+                parent.owner,
+            )
+        };
+
+        parents.sort_by(|a, b| rank(a).cmp(&rank(b)).then_with(|| a.uri.cmp(&b.uri)));
+        parents
+    }
+
+    fn explicit_root(&self, child: &Document) -> Option<&Document> {
+        if let Some(root) = self
+            .pinned_roots
+            .get(&child.uri)
+            .and_then(|uri| self.lookup(uri))
+        {
+            return Some(root);
+        }
+
+        if let Some(root) = magic_root(child).and_then(|uri| self.lookup(&uri)) {
+            return Some(root);
+        }
+
+        let index = self.index();
+        self.config_for(&child.uri)
+            .root_document
+            .as_ref()
+            .and_then(|uri| self.lookup(uri))
+            .filter(|root| {
+                index
+                    .reachable(&root.uri)
+                    .map_or(false, |uris| uris.contains(&child.uri))
+            })
+    }
+
+    /// Pins the root document of `child` to `root` or removes the pin if `root` is `None`.
+    pub fn pin_root(&mut self, child: Url, root: Option<Url>) {
+        match root {
+            Some(root) => self.pinned_roots.insert(child, root),
+            None => self.pinned_roots.remove(&child),
+        };
+    }
+
+    /// Returns the `Tectonic.toml` manifest of the project that compiles `child`.
+    pub fn tectonic(&self, child: &Document) -> Option<(&Document, &TectonicManifest)> {
+        let index = self.index();
+        self.iter()
+            .filter_map(|document| {
+                let manifest = document.data.as_tectonic()?.manifest.as_ref()?;
+                Some((document, manifest))
+            })
+            .find(|(document, _)| {
+                index
+                    .reachable(&document.uri)
+                    .map_or(false, |uris| uris.contains(&child.uri))
             })
-            .collect()
     }
 
     pub fn set_config(&mut self, config: Config) {
+        let syntax_changed = self.config.syntax != config.syntax;
         self.config = config;
-        self.reload();
+        self.invalidate_all();
+        self.scopes.take();
+        if syntax_changed {
+            self.reload();
+        }
     }
 
     pub fn set_distro(&mut self, distro: Distro) {
         self.distro = distro;
-        self.reload();
+        self.invalidate_all();
+    }
+
+    pub fn folders(&self) -> &[PathBuf] {
+        &self.folders
     }
 
     pub fn set_folders(&mut self, folders: Vec<PathBuf>) {
@@ -211,24 +493,35 @@ impl Workspace {
 
         for uri in uris {
             let document = self.lookup(&uri).unwrap();
-            self.open(
-                uri,
+            let document = Document::parse(
+                uri.clone(),
                 document.text.clone(),
                 document.language,
                 document.owner,
                 document.cursor,
+                self.config_for(&uri),
             );
+
+            self.insert(document);
         }
     }
 
     pub fn remove(&mut self, uri: &Url) {
-        self.documents.remove(uri);
+        if let Some(document) = self.documents.take(uri) {
+            if is_marker(&document) {
+                self.invalidate_all();
+            } else {
+                self.invalidate_index(uri);
+            }
+
+            self.update_scopes(project_config(&document).cloned(), None);
+        }
     }
 
     pub fn close(&mut self, uri: &Url) -> Option<()> {
         let mut document = self.lookup(uri)?.clone();
         document.owner = Owner::Server;
-        self.documents.insert(document);
+        self.documents.replace(document);
         Some(())
     }
 
@@ -286,17 +579,58 @@ impl Workspace {
     }
 
     fn discover_children(&mut self) -> bool {
-        let paths = self
+        let mut root_documents: Vec<Url> = self
+            .scopes()
+            .iter()
+            .filter_map(|scope| scope.config.root_document.clone())
+            .collect();
+
+        root_documents.extend(self.iter().filter_map(magic_root));
+        root_documents.extend(self.pinned_roots.values().cloned());
+
+        let index = self.index();
+        let paths = index
+            .missing
             .iter()
-            .map(|start| graph::Graph::new(self, start))
-            .flat_map(|graph| graph.missing)
+            .chain(&root_documents)
             .filter(|uri| uri.scheme() == "file")
-            .flat_map(|uri| uri.to_file_path())
-            .collect::<FxHashSet<_>>();
+            .filter_map(|uri| {
+                let path = uri.to_file_path().ok()?;
+                let language = if index.bibliographies.contains(uri) {
+                    Language::from_bibliography_path(&path)
+                } else {
+                    Language::from_path(&path)
+                };
+
+                Some((path, language.unwrap_or(Language::Tex)))
+            })
+            .collect::<FxHashMap<_, _>>();
+
+        // A client may open a bibliography before the document that references it.
+        let reclassified: Vec<_> = index
+            .bibliographies
+            .iter()
+            .filter_map(|uri| {
+                let document = self.lookup(uri)?;
+                let language = Language::from_bibliography_path(Path::new(uri.path()))?;
+                (document.language != language).then(|| (document.clone(), language))
+            })
+            .collect();
 
         let mut changed = false;
-        for path in paths {
-            let language = Language::from_path(&path).unwrap_or(Language::Tex);
+        for (document, language) in reclassified {
+            let Document {
+                uri,
+                text,
+                owner,
+                cursor,
+                ..
+            } = document;
+            self.open(uri, text, language, owner, cursor);
+            changed = true;
+        }
+
+        for (path, language) in paths {
             if self.lookup_path(&path).is_none() {
                 changed |= self.load(&path, language, Owner::Server).is_ok();
             }
@@ -304,9 +638,363 @@ impl Workspace {
 
         changed
     }
+
+    fn index(&self) -> &ProjectIndex {
+        self.index.get_or_init(|| {
+            let mut index = std::mem::take(&mut *self.partial_index.lock());
+            index.update(self);
+            index
+        })
+    }
+
+    /// Discards the graphs that may change if the edges of `uri` change or if `uri` is added
+    /// or removed. The remaining graphs are kept until the index is needed again.
+    fn invalidate_index(&mut self, uri: &Url) {
+        let partial_index = self.partial_index.get_mut();
+        if let Some(index) = self.index.take() {
+            *partial_index = index;
+        }
+
+        partial_index.invalidate(uri);
+    }
+
+    /// Discards all graphs, e.g. when the configuration changes.
+    fn invalidate_all(&mut self) {
+        self.index.take();
+        *self.partial_index.get_mut() = ProjectIndex::default();
+    }
+
+    fn scopes(&self) -> &[ConfigScope] {
+        self.scopes.get_or_init(|| ConfigScope::new_all(self))
+    }
+}
+
+/// The configuration of the documents in `dir` and its subdirectories.
+#[derive(Debug)]
+struct ConfigScope {
+    dir: Url,
+    config: Config,
+}
+
+impl ConfigScope {
+    /// Creates a scope for every project configuration file, ordered from the outermost
+    /// to the innermost directory.
+    fn new_all(workspace: &Workspace) -> Vec<Self> {
+        let mut files: Vec<_> = workspace
+            .iter()
+            .filter_map(|document| {
+                let config = project_config(document)?;
+                Some((document.uri.join(".").ok()?, config))
+            })
+            .collect();
+
+        files.sort_by_key(|(dir, _)| dir.as_str().len());
+
+        let mut scopes: Vec<Self> = Vec::new();
+        for (dir, project) in files {
+            let config = scopes
+                .iter()
+                .rev()
+                .find(|scope| dir.as_str().starts_with(scope.dir.as_str()))
+                .map_or(&workspace.config, |scope| &scope.config)
+                .merge(project, &dir);
+
+            scopes.push(Self { dir, config });
+        }
+
+        scopes
+    }
+}
+
+/// Resolves the root document that is declared with a `% !TEX root` magic comment.
+fn magic_root(document: &Document) -> Option<Url> {
+    let root = document
+        .data
+        .as_tex()?
+        .semantics
+        .magic_comments
+        .root
+        .as_ref()?;
+    document.dir.join(&root.text).ok()
+}
+
+/// Checks whether the document changes the working directory of the documents next to it.
+fn is_marker(document: &Document) -> bool {
+    matches!(document.language, Language::Root | Language::Tectonic)
+}
+
+fn project_config(document: &Document) -> Option<&ProjectConfig> {
+    document.data.as_root()?.config.as_ref()
+}
+
+/// Stores the documents that are reachable from every document of the workspace.
+///
+/// Building the dependency graphs is expensive, so the graphs are computed lazily.
+/// A graph is only discarded if it contains a document whose edges have changed
+/// or if it refers to a document that has been added or removed.
+#[derive(Debug, Default)]
+struct ProjectIndex {
+    graphs: FxHashMap<Url, GraphIndex>,
+    missing: FxHashSet<Url>,
+    bibliographies: FxHashSet<Url>,
+}
+
+/// The documents that are reachable from a single document.
+#[derive(Debug)]
+struct GraphIndex {
+    reachable: FxHashSet<Url>,
+    missing: FxHashSet<Url>,
+    bibliographies: FxHashSet<Url>,
+}
+
+impl ProjectIndex {
+    /// Builds the graphs of the documents that are not part of the index yet.
+    fn update(&mut self, workspace: &Workspace) {
+        for start in workspace.iter() {
+            if self.graphs.contains_key(&start.uri) {
+                continue;
+            }
+
+            let graph = graph::Graph::new(workspace, start);
+            let reachable = graph
+                .preorder()
+                .map(|document| document.uri.clone())
+                .collect();
+
+            let graph = GraphIndex {
+                reachable,
+                missing: graph.missing.into_iter().collect(),
+                bibliographies: graph.bibliographies.into_iter().collect(),
+            };
+
+            self.graphs.insert(start.uri.clone(), graph);
+        }
+
+        self.missing = self
+            .graphs
+            .values()
+            .flat_map(|graph| graph.missing.iter().cloned())
+            .collect();
+
+        self.bibliographies = self
+            .graphs
+            .values()
+            .flat_map(|graph| graph.bibliographies.iter().cloned())
+            .collect();
+    }
+
+    fn invalidate(&mut self, uri: &Url) {
+        self.graphs.retain(|start, graph| {
+            start != uri && !graph.reachable.contains(uri) && !graph.missing.contains(uri)
+        });
+    }
+
+    fn reachable(&self, start: &Url) -> Option<&FxHashSet<Url>> {
+        self.graphs.get(start).map(|graph| &graph.reachable)
+    }
+}
+
+/// Checks whether replacing `old` with `new` keeps the dependency graph intact.
+fn has_same_edges(old: &Document, new: &Document) -> bool {
+    if old.language != new.language {
+        return false;
+    }
+
+    match (&old.data, &new.data) {
+        (DocumentData::Tex(old), DocumentData::Tex(new)) => {
+            let old = &old.semantics;
+            let new = &new.semantics;
+            old.can_be_root == new.can_be_root
+                && old.links.len() == new.links.len()
+                && old.links.iter().zip(&new.links).all(|(a, b)| {
+                    a.kind == b.kind && a.path.text == b.path.text && a.base_dir == b.base_dir
+                })
+        }
+        (DocumentData::Fls(old), DocumentData::Fls(new)) => old.recorder == new.recorder,
+        (DocumentData::Tectonic(old), DocumentData::Tectonic(new)) => old.manifest == new.manifest,
+        _ => true,
+    }
 }
 
 #[derive(Debug)]
 pub struct Project<'a> {
     pub documents: FxHashSet<&'a Document>,
 }
+
+#[cfg(test)]
+mod tests {
+    use distro::Language;
+    use url::Url;
+
+    use crate::{util::LineCol, Config, Owner};
+
+    use super::Workspace;
+
+    fn open(workspace: &mut Workspace, uri: &Url, text: &str) {
+        let cursor = LineCol { line: 0, col: 0 };
+        workspace.open(
+            uri.clone(),
+            text.into(),
+            Language::Tex,
+            Owner::Client,
+            cursor,
+        );
+    }
+
+    fn uri(name: &str) -> Url {
+        Url::parse(&format!("file:///texlab/{name}")).unwrap()
+    }
+
+    fn cached_graphs(workspace: &Workspace) -> Vec<String> {
+        let mut starts: Vec<_> = match workspace.index.get() {
+            Some(index) => index.graphs.keys().map(ToString::to_string).collect(),
+            None => workspace
+                .partial_index
+                .lock()
+                .graphs
+                .keys()
+                .map(ToString::to_string)
+                .collect(),
+        };
+
+        starts.sort();
+        starts
+    }
+
+    fn setup() -> Workspace {
+        let mut workspace = Workspace::default();
+        open(&mut workspace, &uri("main.tex"), "\\input{chapter}");
+        open(&mut workspace, &uri("chapter.tex"), "Foo");
+        open(&mut workspace, &uri("other.tex"), "Bar");
+        workspace.index();
+        workspace
+    }
+
+    #[test]
+    fn test_same_edges() {
+        let mut workspace = setup();
+        open(&mut workspace, &uri("chapter.tex"), "Foo Bar");
+        assert!(workspace.index.get().is_some());
+        assert_eq!(cached_graphs(&workspace).len(), 3);
+    }
+
+    #[test]
+    fn test_changed_edges() {
+        let mut workspace = setup();
+        open(&mut workspace, &uri("chapter.tex"), "\\input{section}");
+        assert!(workspace.index.get().is_none());
+        assert_eq!(
+            cached_graphs(&workspace),
+            vec![uri("other.tex").to_string()]
+        );
+
+        let main = workspace.lookup(&uri("main.tex")).unwrap();
+        let mut project: Vec<_> = workspace
+            .project(main)
+            .documents
+            .into_iter()
+            .map(|document| document.uri.to_string())
+            .collect();
+
+        project.sort();
+        assert_eq!(
+            project,
+            vec![uri("chapter.tex").to_string(), uri("main.tex").to_string()]
+        );
+        assert_eq!(cached_graphs(&workspace).len(), 3);
+    }
+
+    #[test]
+    fn test_added_document() {
+        let mut workspace = setup();
+        open(&mut workspace, &uri("main.aux"), "");
+        assert_eq!(
+            cached_graphs(&workspace),
+            vec![uri("chapter.tex").to_string(), uri("other.tex").to_string()]
+        );
+
+        let aux = workspace.lookup(&uri("main.aux")).unwrap();
+        let main = workspace.lookup(&uri("main.tex")).unwrap();
+        assert!(workspace.project(aux).documents.contains(main));
+    }
+
+    #[test]
+    fn test_removed_document() {
+        let mut workspace = setup();
+        workspace.remove(&uri("other.tex"));
+        assert_eq!(
+            cached_graphs(&workspace),
+            vec![uri("chapter.tex").to_string(), uri("main.tex").to_string()]
+        );
+    }
+
+    #[test]
+    fn test_config() {
+        let mut workspace = setup();
+        workspace.set_config(Config::default());
+        assert!(cached_graphs(&workspace).is_empty());
+    }
+
+    #[test]
+    fn test_added_marker() {
+        let mut workspace = setup();
+        let cursor = LineCol { line: 0, col: 0 };
+        workspace.open(
+            uri(".texlabroot"),
+            String::new(),
+            Language::Root,
+            Owner::Server,
+            cursor,
+        );
+
+        assert!(cached_graphs(&workspace).is_empty());
+    }
+
+    #[test]
+    fn test_removed_marker() {
+        let mut workspace = setup();
+        let cursor = LineCol { line: 0, col: 0 };
+        workspace.open(
+            uri("Tectonic.toml"),
+            String::new(),
+            Language::Tectonic,
+            Owner::Server,
+            cursor,
+        );
+
+        workspace.index();
+        workspace.remove(&uri("Tectonic.toml"));
+        assert!(cached_graphs(&workspace).is_empty());
+    }
+
+    #[test]
+    fn test_extend_keeps_opened_documents() {
+        let dir = tempfile::tempdir().unwrap();
+        let main = dir.path().join("main.tex");
+        let chapter = dir.path().join("chapter.tex");
+        std::fs::write(&main, "Disk").unwrap();
+        std::fs::write(&chapter, "Foo").unwrap();
+
+        let mut workspace = Workspace::default();
+        let documents: Vec<_> = [&main, &chapter]
+            .into_iter()
+            .map(|path| {
+                workspace
+                    .parse_file(path, Language::Tex, Owner::Server)
+                    .unwrap()
+            })
+            .collect();
+
+        let main_uri = Url::from_file_path(&main).unwrap();
+        open(&mut workspace, &main_uri, "Editor");
+        workspace.extend(documents);
+
+        let document = workspace.lookup(&main_uri).unwrap();
+        assert_eq!(document.text, "Editor");
+        assert_eq!(document.owner, Owner::Client);
+
+        let document = workspace.lookup_path(&chapter).unwrap();
+        assert_eq!(document.text, "Foo");
+        assert_eq!(document.owner, Owner::Server);
+    }
+}
@@ -0,0 +1,176 @@
+use syntax::bibtex::{self, HasName, HasType, HasValue};
+
+use crate::{
+    entry::{EntryData, EntryKind},
+    field::{
+        date::DateField,
+        text::{parse_keys, TextField},
+    },
+};
+
+/// Parses an entry including the fields that it inherits from other entries of the bibliography.
+///
+/// The fields of `xdata` entries are inherited as they are. The fields of a `crossref` parent
+/// are mapped with the default inheritance rules of biblatex, e.g. the `title` of a `@book`
+/// becomes the `booktitle` of an `@inbook`. Fields of the entry itself always take precedence.
+/// Unlike `crossref`, an `xref` only establishes a relationship and does not inherit any data.
+pub(crate) fn resolve(entry: &bibtex::Entry, bibliography: &[bibtex::Root]) -> EntryData {
+    let mut resolver = Resolver {
+        bibliography,
+        stack: Vec::new(),
+    };
+
+    resolver.resolve(entry)
+}
+
+/// Returns the members of an `@set` entry that are listed in its `entryset` field.
+pub(crate) fn set_members(
+    entry: &bibtex::Entry,
+    bibliography: &[bibtex::Root],
+) -> Vec<bibtex::Entry> {
+    if entry_kind(entry) != EntryKind::Set {
+        return Vec::new();
+    }
+
+    referenced_keys(entry, "entryset")
+        .into_iter()
+        .filter_map(|key| find_entry(bibliography, &key))
+        .filter(|member| entry_kind(member) != EntryKind::Set)
+        .collect()
+}
+
+struct Resolver<'a> {
+    bibliography: &'a [bibtex::Root],
+    stack: Vec<String>,
+}
+
+impl<'a> Resolver<'a> {
+    fn resolve(&mut self, entry: &bibtex::Entry) -> EntryData {
+        let mut data = EntryData::parse(entry, self.bibliography);
+        let key = entry.name_token().map(|token| token.text().to_string());
+        self.stack.extend(key.clone());
+
+        for parent in self.parents(entry, "xdata") {
+            let parent = self.resolve(&parent);
+            inherit(&mut data, parent, None);
+        }
+
+        if let Some(parent) = self.parents(entry, "crossref").into_iter().next() {
+            let parent = self.resolve(&parent);
+            let titles = title_fields(parent.kind, data.kind);
+            inherit(&mut data, parent, titles);
+        }
+
+        if key.is_some() {
+            self.stack.pop();
+        }
+
+        data
+    }
+
+    fn parents(&self, entry: &bibtex::Entry, field: &str) -> Vec<bibtex::Entry> {
+        referenced_keys(entry, field)
+            .into_iter()
+            .filter(|key| !self.stack.contains(key))
+            .filter_map(|key| find_entry(self.bibliography, &key))
+            .collect()
+    }
+}
+
+fn entry_kind(entry: &bibtex::Entry) -> EntryKind {
+    entry.type_token().map_or(EntryKind::Unknown, |token| {
+        EntryKind::parse(&token.text()[1..])
+    })
+}
+
+fn find_entry(bibliography: &[bibtex::Root], key: &str) -> Option<bibtex::Entry> {
+    bibliography.iter().find_map(|root| root.find_entry(key))
+}
+
+/// Returns the comma-separated entry keys of a field like `crossref` or `entryset`.
+fn referenced_keys(entry: &bibtex::Entry, name: &str) -> Vec<String> {
+    entry
+        .fields()
+        .filter(|field| {
+            field
+                .name_token()
+                .map_or(false, |token| token.text().eq_ignore_ascii_case(name))
+        })
+        .filter_map(|field| field.value())
+        .flat_map(|value| parse_keys(&value))
+        .collect()
+}
+
+/// The fields that receive the `title`, `subtitle` and `titleaddon` of a `crossref` parent.
+fn title_fields(parent: EntryKind, child: EntryKind) -> Option<[TextField; 3]> {
+    const MAIN: [TextField; 3] = [
+        TextField::MainTitle,
+        TextField::MainSubtitle,
+        TextField::MainTitleAddon,
+    ];
+
+    const BOOK: [TextField; 3] = [
+        TextField::BookTitle,
+        TextField::BookSubtitle,
+        TextField::BookTitleAddon,
+    ];
+
+    const JOURNAL: [TextField; 3] = [
+        TextField::JournalTitle,
+        TextField::JournalSubtitle,
+        TextField::JournalTitleAddon,
+    ];
+
+    use EntryKind::*;
+    match (parent, child) {
+        (
+            MVBook | MVCollection | MVReference | MVProceedings,
+            Book | InBook | BookInBook | SuppBook | Collection | InCollection | SuppCollection
+            | Reference | InReference | Proceedings | InProceedings | Conference,
+        ) => Some(MAIN),
+        (Book, InBook | BookInBook | SuppBook) => Some(BOOK),
+        (Collection | Reference, InCollection | SuppCollection | InReference) => Some(BOOK),
+        (Proceedings, InProceedings | Conference) => Some(BOOK),
+        (Periodical, Article | SuppPeriodical) => Some(JOURNAL),
+        _ => None,
+    }
+}
+
+fn inherit(child: &mut EntryData, parent: EntryData, titles: Option<[TextField; 3]>) {
+    let has_journal = child.text.contains_key(&TextField::Journal)
+        || child.text.contains_key(&TextField::JournalTitle);
+
+    for (field, data) in parent.text {
+        let field = match (titles, field) {
+            (Some(titles), TextField::Title) => titles[0],
+            (Some(titles), TextField::Subtitle) => titles[1],
+            (Some(titles), TextField::TitleAddon) => titles[2],
+            (_, field) => field,
+        };
+
+        if has_journal && matches!(field, TextField::Journal | TextField::JournalTitle) {
+            continue;
+        }
+
+        child.text.entry(field).or_insert(data);
+    }
+
+    for (field, data) in parent.author {
+        child.author.entry(field).or_insert(data);
+    }
+
+    // A year of the child must not be combined with the month or the full date of the parent.
+    let has_date =
+        child.date.contains_key(&DateField::Date) || child.date.contains_key(&DateField::Year);
+
+    for (field, data) in parent.date {
+        let is_issued = matches!(field, DateField::Date | DateField::Year | DateField::Month);
+        if !(has_date && is_issued) {
+            child.date.entry(field).or_insert(data);
+        }
+    }
+
+    for (field, data) in parent.number {
+        child.number.entry(field).or_insert(data);
+    }
+}
@@ -0,0 +1,92 @@
+mod locale;
+mod render;
+mod style;
+mod variables;
+
+use itertools::Itertools;
+use syntax::bibtex;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::{crossref, entry::EntryData};
+
+pub use self::{locale::Locale, style::Style};
+
+use self::{locale::LocaleChain, render::Renderer, variables::Variables};
+
+/// Renders an entry with the bibliography layout of a CSL style.
+/// Styles without a bibliography use their citation layout instead.
+///
+/// Terms and localized dates are taken from the locales embedded in the style,
+/// then from `locale` and finally from a built-in subset of the `en-US` locale.
+/// References to other entries are resolved like in [`crate::render`].
+#[must_use]
+pub fn render(
+    entry: &bibtex::Entry,
+    bibliography: &[bibtex::Root],
+    style: &Style,
+    locale: Option<&Locale>,
+) -> Option<String> {
+    let members = crossref::set_members(entry, bibliography);
+    if !members.is_empty() {
+        return render_set(&members, |member| {
+            render(member, bibliography, style, locale)
+        });
+    }
+
+    render_data(crossref::resolve(entry, bibliography), style, locale)
+}
+
+/// Renders an item of a CSL-JSON or Hayagriva bibliography like [`render`].
+#[must_use]
+pub fn render_item(
+    item: &syntax::csl::Item,
+    style: &Style,
+    locale: Option<&Locale>,
+) -> Option<String> {
+    render_data(EntryData::from(item), style, locale)
+}
+
+fn render_data(data: EntryData, style: &Style, locale: Option<&Locale>) -> Option<String> {
+    let layout = style.bibliography.as_ref().or(style.citation.as_ref())?;
+
+    let lang = locale
+        .and_then(Locale::lang)
+        .or(style.default_locale())
+        .unwrap_or("en-US");
+
+    let embedded = style.locales.iter().filter(|embedded| {
+        embedded.lang().map_or(true, |other| {
+            lang.starts_with(other) || other.starts_with(lang)
+        })
+    });
+
+    let locales = LocaleChain::new(embedded.chain(locale));
+    let vars = Variables::from(data);
+    let output = Renderer::new(style, locales, &vars).render_layout(layout)?;
+
+    let output = output.split_whitespace().collect::<Vec<_>>().join(" ");
+    if output.is_empty() {
+        None
+    } else {
+        Some(output.nfc().collect())
+    }
+}
+
+/// Joins the rendered members of an `@set` entry with semicolons.
+fn render_set(
+    members: &[bibtex::Entry],
+    render: impl Fn(&bibtex::Entry) -> Option<String>,
+) -> Option<String> {
+    let mut parts: Vec<_> = members.iter().filter_map(render).collect();
+    let last = parts.pop()?;
+    let output = parts
+        .iter()
+        .map(|part| part.trim_end_matches('.'))
+        .chain([last.as_str()])
+        .join("; ");
+
+    Some(output)
+}
+
+#[cfg(test)]
+mod tests;
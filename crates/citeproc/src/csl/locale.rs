@@ -0,0 +1,340 @@
+use once_cell::sync::Lazy;
+use roxmltree::{Document, Node};
+use rustc_hash::FxHashMap;
+
+use super::style::{children, parse_date, DateElement, DateForm};
+
+const XML_NAMESPACE: &str = "http://www.w3.org/XML/1998/namespace";
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub(crate) enum TermForm {
+    Long,
+    Short,
+    Verb,
+    VerbShort,
+    Symbol,
+}
+
+impl TermForm {
+    pub(crate) fn parse(input: &str) -> Self {
+        match input {
+            "short" => Self::Short,
+            "verb" => Self::Verb,
+            "verb-short" => Self::VerbShort,
+            "symbol" => Self::Symbol,
+            _ => Self::Long,
+        }
+    }
+
+    /// The form that is used if a term is not defined in this form (CSL specification, "Terms").
+    fn fallback(self) -> Option<Self> {
+        match self {
+            Self::Long => None,
+            Self::Short | Self::Verb => Some(Self::Long),
+            Self::VerbShort => Some(Self::Verb),
+            Self::Symbol => Some(Self::Short),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Term {
+    single: String,
+    multiple: String,
+}
+
+/// A CSL locale with terms, localized dates and style options.
+/// Locales are usually loaded from the `locales-xx-XX.xml` files of the CSL project.
+#[derive(Debug, Clone, Default)]
+pub struct Locale {
+    lang: Option<String>,
+    terms: FxHashMap<(String, TermForm), Term>,
+    dates: FxHashMap<DateForm, DateElement>,
+    punctuation_in_quote: Option<bool>,
+}
+
+impl Locale {
+    /// Parses a standalone locale file.
+    #[must_use]
+    pub fn parse(text: &str) -> Option<Self> {
+        let document = Document::parse(text).ok()?;
+        let root = document.root_element();
+        (root.tag_name().name() == "locale").then(|| Self::from_node(root))
+    }
+
+    pub(crate) fn from_node(node: Node) -> Self {
+        let mut locale = Self {
+            lang: node.attribute((XML_NAMESPACE, "lang")).map(String::from),
+            ..Self::default()
+        };
+
+        for child in children(node) {
+            match child.tag_name().name() {
+                "terms" => {
+                    for term in children(child).filter(|term| term.has_tag_name("term")) {
+                        locale.add_term(term);
+                    }
+                }
+                "date" => {
+                    if let Some(date) = parse_date(child) {
+                        if let Some(form) = date.form {
+                            locale.dates.insert(form, date);
+                        }
+                    }
+                }
+                "style-options" => {
+                    locale.punctuation_in_quote = child
+                        .attribute("punctuation-in-quote")
+                        .map(|value| value == "true");
+                }
+                _ => {}
+            }
+        }
+
+        locale
+    }
+
+    fn add_term(&mut self, node: Node) {
+        let Some(name) = node.attribute("name") else { return };
+        let form = TermForm::parse(node.attribute("form").unwrap_or("long"));
+
+        let single = children(node).find(|child| child.has_tag_name("single"));
+        let multiple = children(node).find(|child| child.has_tag_name("multiple"));
+        let term = match (single, multiple) {
+            (None, None) => {
+                let text = node.text().unwrap_or_default().to_string();
+                Term {
+                    single: text.clone(),
+                    multiple: text,
+                }
+            }
+            (single, multiple) => {
+                let single = single.and_then(|node| node.text()).unwrap_or_default();
+                let multiple = multiple.and_then(|node| node.text()).unwrap_or(single);
+                Term {
+                    single: single.into(),
+                    multiple: multiple.into(),
+                }
+            }
+        };
+
+        self.terms.insert((name.into(), form), term);
+    }
+
+    pub fn lang(&self) -> Option<&str> {
+        self.lang.as_deref()
+    }
+
+    fn term(&self, name: &str, form: TermForm, plural: bool) -> Option<&str> {
+        let term = self.terms.get(&(name.into(), form))?;
+        Some(if plural { &term.multiple } else { &term.single })
+    }
+}
+
+/// The locales that are consulted in order when looking up terms and localized dates.
+/// The built-in English locale is always the last resort.
+#[derive(Debug)]
+pub(crate) struct LocaleChain<'a> {
+    locales: Vec<&'a Locale>,
+}
+
+impl<'a> LocaleChain<'a> {
+    pub(crate) fn new(locales: impl IntoIterator<Item = &'a Locale>) -> Self {
+        let mut locales: Vec<_> = locales.into_iter().collect();
+        locales.push(&EN_US);
+        Self { locales }
+    }
+
+    pub(crate) fn term(&self, name: &str, form: TermForm, plural: bool) -> Option<&'a str> {
+        let mut form = Some(form);
+        while let Some(current) = form {
+            let term = self
+                .locales
+                .iter()
+                .find_map(|locale| locale.term(name, current, plural));
+
+            if term.is_some() {
+                return term;
+            }
+
+            form = current.fallback();
+        }
+
+        None
+    }
+
+    /// Resolves the ordinal suffix of a number. A locale that defines any ordinal term
+    /// replaces all ordinal terms of the locales that follow it.
+    pub(crate) fn ordinal_suffix(&self, n: u32) -> &'a str {
+        let Some(locale) = self.locales.iter().find(|locale| {
+            locale
+                .terms
+                .keys()
+                .any(|(name, _)| name.starts_with("ordinal"))
+        }) else { return "" };
+
+        let term = |name: &str| locale.term(name, TermForm::Long, false);
+        (n % 100 >= 10)
+            .then(|| term(&format!("ordinal-{:02}", n % 100)))
+            .flatten()
+            .or_else(|| term(&format!("ordinal-{:02}", n % 10)))
+            .or_else(|| term("ordinal"))
+            .unwrap_or_default()
+    }
+
+    pub(crate) fn date(&self, form: DateForm) -> Option<&'a DateElement> {
+        self.locales
+            .iter()
+            .find_map(|locale| locale.dates.get(&form))
+    }
+
+    pub(crate) fn punctuation_in_quote(&self) -> bool {
+        self.locales
+            .iter()
+            .find_map(|locale| locale.punctuation_in_quote)
+            .unwrap_or(false)
+    }
+}
+
+static EN_US: Lazy<Locale> = Lazy::new(|| Locale::parse(EN_US_LOCALE).unwrap());
+
+/// A subset of the `en-US` locale of the CSL project that covers the commonly used terms.
+const EN_US_LOCALE: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<locale xmlns="http://purl.org/net/xbiblio/csl" version="1.0" xml:lang="en-US">
+  <style-options punctuation-in-quote="true"/>
+  <date form="text">
+    <date-part name="month" suffix=" "/>
+    <date-part name="day" suffix=", "/>
+    <date-part name="year"/>
+  </date>
+  <date form="numeric">
+    <date-part name="month" form="numeric-leading-zeros" suffix="/"/>
+    <date-part name="day" form="numeric-leading-zeros" suffix="/"/>
+    <date-part name="year"/>
+  </date>
+  <terms>
+    <term name="accessed">accessed</term>
+    <term name="and">and</term>
+    <term name="and others">and others</term>
+    <term name="anonymous">anonymous</term>
+    <term name="anonymous" form="short">anon.</term>
+    <term name="at">at</term>
+    <term name="available at">available at</term>
+    <term name="by">by</term>
+    <term name="circa">circa</term>
+    <term name="circa" form="short">c.</term>
+    <term name="cited">cited</term>
+    <term name="et-al">et al.</term>
+    <term name="forthcoming">forthcoming</term>
+    <term name="from">from</term>
+    <term name="ibid">ibid.</term>
+    <term name="in">in</term>
+    <term name="in press">in press</term>
+    <term name="internet">internet</term>
+    <term name="no date">no date</term>
+    <term name="no date" form="short">n.d.</term>
+    <term name="online">online</term>
+    <term name="presented at">presented at the</term>
+    <term name="retrieved">retrieved</term>
+    <term name="scale">scale</term>
+    <term name="version">version</term>
+    <term name="and" form="symbol">&amp;</term>
+    <term name="open-quote">“</term>
+    <term name="close-quote">”</term>
+    <term name="open-inner-quote">‘</term>
+    <term name="close-inner-quote">’</term>
+    <term name="page-range-delimiter">–</term>
+    <term name="ordinal">th</term>
+    <term name="ordinal-01">st</term>
+    <term name="ordinal-02">nd</term>
+    <term name="ordinal-03">rd</term>
+    <term name="ordinal-11">th</term>
+    <term name="ordinal-12">th</term>
+    <term name="ordinal-13">th</term>
+    <term name="long-ordinal-01">first</term>
+    <term name="long-ordinal-02">second</term>
+    <term name="long-ordinal-03">third</term>
+    <term name="long-ordinal-04">fourth</term>
+    <term name="long-ordinal-05">fifth</term>
+    <term name="long-ordinal-06">sixth</term>
+    <term name="long-ordinal-07">seventh</term>
+    <term name="long-ordinal-08">eighth</term>
+    <term name="long-ordinal-09">ninth</term>
+    <term name="long-ordinal-10">tenth</term>
+    <term name="book"><single>book</single><multiple>books</multiple></term>
+    <term name="chapter"><single>chapter</single><multiple>chapters</multiple></term>
+    <term name="edition"><single>edition</single><multiple>editions</multiple></term>
+    <term name="issue"><single>issue</single><multiple>issues</multiple></term>
+    <term name="line"><single>line</single><multiple>lines</multiple></term>
+    <term name="number"><single>number</single><multiple>numbers</multiple></term>
+    <term name="page"><single>page</single><multiple>pages</multiple></term>
+    <term name="number-of-pages"><single>page</single><multiple>pages</multiple></term>
+    <term name="section"><single>section</single><multiple>sections</multiple></term>
+    <term name="volume"><single>volume</single><multiple>volumes</multiple></term>
+    <term name="number-of-volumes"><single>volume</single><multiple>volumes</multiple></term>
+    <term name="book" form="short"><single>bk.</single><multiple>bks.</multiple></term>
+    <term name="chapter" form="short"><single>chap.</single><multiple>chaps.</multiple></term>
+    <term name="edition" form="short"><single>ed.</single><multiple>eds.</multiple></term>
+    <term name="issue" form="short"><single>no.</single><multiple>nos.</multiple></term>
+    <term name="number" form="short"><single>no.</single><multiple>nos.</multiple></term>
+    <term name="page" form="short"><single>p.</single><multiple>pp.</multiple></term>
+    <term name="number-of-pages" form="short"><single>p.</single><multiple>pp.</multiple></term>
+    <term name="section" form="short"><single>sec.</single><multiple>secs.</multiple></term>
+    <term name="volume" form="short"><single>vol.</single><multiple>vols.</multiple></term>
+    <term name="number-of-volumes" form="short"><single>vol.</single><multiple>vols.</multiple></term>
+    <term name="section" form="symbol"><single>§</single><multiple>§§</multiple></term>
+    <term name="director"><single>director</single><multiple>directors</multiple></term>
+    <term name="editor"><single>editor</single><multiple>editors</multiple></term>
+    <term name="editorial-director"><single>editor</single><multiple>editors</multiple></term>
+    <term name="illustrator"><single>illustrator</single><multiple>illustrators</multiple></term>
+    <term name="translator"><single>translator</single><multiple>translators</multiple></term>
+    <term name="editortranslator"><single>editor &amp; translator</single><multiple>editors &amp; translators</multiple></term>
+    <term name="director" form="short"><single>dir.</single><multiple>dirs.</multiple></term>
+    <term name="editor" form="short"><single>ed.</single><multiple>eds.</multiple></term>
+    <term name="editorial-director" form="short"><single>ed.</single><multiple>eds.</multiple></term>
+    <term name="illustrator" form="short"><single>ill.</single><multiple>ills.</multiple></term>
+    <term name="translator" form="short"><single>tran.</single><multiple>trans.</multiple></term>
+    <term name="editortranslator" form="short"><single>ed. &amp; tran.</single><multiple>eds. &amp; trans.</multiple></term>
+    <term name="container-author" form="verb">by</term>
+    <term name="director" form="verb">directed by</term>
+    <term name="editor" form="verb">edited by</term>
+    <term name="editorial-director" form="verb">edited by</term>
+    <term name="illustrator" form="verb">illustrated by</term>
+    <term name="interviewer" form="verb">interview by</term>
+    <term name="recipient" form="verb">to</term>
+    <term name="reviewed-author" form="verb">by</term>
+    <term name="translator" form="verb">translated by</term>
+    <term name="editortranslator" form="verb">edited &amp; translated by</term>
+    <term name="director" form="verb-short">dir. by</term>
+    <term name="editor" form="verb-short">ed. by</term>
+    <term name="editorial-director" form="verb-short">ed. by</term>
+    <term name="illustrator" form="verb-short">illus. by</term>
+    <term name="translator" form="verb-short">trans. by</term>
+    <term name="editortranslator" form="verb-short">ed. &amp; trans. by</term>
+    <term name="month-01">January</term>
+    <term name="month-02">February</term>
+    <term name="month-03">March</term>
+    <term name="month-04">April</term>
+    <term name="month-05">May</term>
+    <term name="month-06">June</term>
+    <term name="month-07">July</term>
+    <term name="month-08">August</term>
+    <term name="month-09">September</term>
+    <term name="month-10">October</term>
+    <term name="month-11">November</term>
+    <term name="month-12">December</term>
+    <term name="month-01" form="short">Jan.</term>
+    <term name="month-02" form="short">Feb.</term>
+    <term name="month-03" form="short">Mar.</term>
+    <term name="month-04" form="short">Apr.</term>
+    <term name="month-05" form="short">May</term>
+    <term name="month-06" form="short">Jun.</term>
+    <term name="month-07" form="short">Jul.</term>
+    <term name="month-08" form="short">Aug.</term>
+    <term name="month-09" form="short">Sep.</term>
+    <term name="month-10" form="short">Oct.</term>
+    <term name="month-11" form="short">Nov.</term>
+    <term name="month-12" form="short">Dec.</term>
+  </terms>
+</locale>
+"#;
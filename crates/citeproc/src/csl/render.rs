@@ -0,0 +1,720 @@
+use human_name::Name;
+use rustc_hash::FxHashSet;
+use titlecase::titlecase;
+
+use super::{
+    locale::{LocaleChain, TermForm},
+    style::{
+        Branch, DateElement, DateForm, DatePart, DatePartName, Display, Element, GroupElement,
+        LabelElement, Layout, Match, NameOptions, NamesElement, NumberElement, NumberForm, Plural,
+        Style, Test, TextCase, TextSource, VariableForm,
+    },
+    variables::{DateValue, Variables},
+};
+
+const MAX_MACRO_DEPTH: usize = 32;
+
+/// Evaluates the elements of a style for a single entry.
+pub(crate) struct Renderer<'a> {
+    style: &'a Style,
+    locales: LocaleChain<'a>,
+    vars: &'a Variables,
+    names: NameOptions,
+    suppressed: FxHashSet<String>,
+    rendered_vars: Vec<String>,
+    called: usize,
+    rendered: usize,
+    depth: usize,
+}
+
+impl<'a> Renderer<'a> {
+    pub(crate) fn new(style: &'a Style, locales: LocaleChain<'a>, vars: &'a Variables) -> Self {
+        Self {
+            style,
+            locales,
+            vars,
+            names: NameOptions::default(),
+            suppressed: FxHashSet::default(),
+            rendered_vars: Vec::new(),
+            called: 0,
+            rendered: 0,
+            depth: 0,
+        }
+    }
+
+    pub(crate) fn render_layout(&mut self, layout: &Layout) -> Option<String> {
+        self.names = layout.names.clone();
+        let parts: Vec<_> = layout
+            .elements
+            .iter()
+            .filter_map(|element| self.render(element))
+            .collect();
+
+        let text = self.join(&parts, &layout.delimiter);
+        self.apply(text, &layout.display)
+    }
+
+    fn render(&mut self, element: &Element) -> Option<String> {
+        match element {
+            Element::Text(source, display) => self.render_text(source, display),
+            Element::Number(number) => self.render_number(number),
+            Element::Label(label) => self.render_label(label),
+            Element::Date(date) => self.render_date(date),
+            Element::Names(names) => self.render_names(names),
+            Element::Group(group) => self.render_group(group),
+            Element::Choose(branches) => self.render_choose(branches),
+        }
+    }
+
+    fn render_text(&mut self, source: &TextSource, display: &Display) -> Option<String> {
+        let text = match source {
+            TextSource::Variable(name, form) => {
+                let short = format!("{name}-short");
+                let value = match form {
+                    VariableForm::Short if self.vars.text.contains_key(short.as_str()) => {
+                        self.variable(&short)?
+                    }
+                    VariableForm::Short | VariableForm::Long => self.variable(name)?,
+                };
+
+                if name == "page" {
+                    self.page_range(&value)
+                } else {
+                    value
+                }
+            }
+            TextSource::Macro(name) => {
+                let elements = self.style.macros.get(name)?;
+                if self.depth >= MAX_MACRO_DEPTH {
+                    return None;
+                }
+
+                self.depth += 1;
+                let parts: Vec<_> = elements
+                    .iter()
+                    .filter_map(|element| self.render(element))
+                    .collect();
+
+                self.depth -= 1;
+                self.join(&parts, "")
+            }
+            TextSource::Term(name, form, plural) => {
+                self.locales.term(name, *form, *plural)?.to_string()
+            }
+            TextSource::Value(value) => value.clone(),
+        };
+
+        self.apply(text, display)
+    }
+
+    fn render_number(&mut self, number: &NumberElement) -> Option<String> {
+        let value = self.variable(&number.variable)?;
+        if !is_numeric(&value) {
+            return self.apply(value, &number.display);
+        }
+
+        let mut text = String::new();
+        let mut digits = String::new();
+        for c in value.chars().chain(std::iter::once('\0')) {
+            if c.is_ascii_digit() {
+                digits.push(c);
+                continue;
+            }
+
+            if !digits.is_empty() {
+                let n: u32 = digits.parse().unwrap_or_default();
+                text.push_str(&self.format_number(n, number.form));
+                digits.clear();
+            }
+
+            match c {
+                '\0' => {}
+                '-' => text.push_str(self.term_or("page-range-delimiter", "-")),
+                c => text.push(c),
+            };
+        }
+
+        self.apply(text, &number.display)
+    }
+
+    fn format_number(&self, n: u32, form: NumberForm) -> String {
+        match form {
+            NumberForm::Numeric => n.to_string(),
+            NumberForm::Ordinal => format!("{n}{}", self.locales.ordinal_suffix(n)),
+            NumberForm::LongOrdinal => {
+                let name = format!("long-ordinal-{n:02}");
+                match self.locales.term(&name, TermForm::Long, false) {
+                    Some(term) if (1..=10).contains(&n) => term.to_string(),
+                    _ => format!("{n}{}", self.locales.ordinal_suffix(n)),
+                }
+            }
+            NumberForm::Roman => to_roman(n).unwrap_or_else(|| n.to_string()),
+        }
+    }
+
+    fn render_label(&mut self, label: &LabelElement) -> Option<String> {
+        let plural = if let Some(names) = self.vars.names.get(label.variable.as_str()) {
+            names.len() > 1
+        } else {
+            let value = self.vars.text.get(label.variable.as_str())?;
+            is_plural(value)
+        };
+
+        self.render_term_label(label, plural)
+    }
+
+    fn render_term_label(&self, label: &LabelElement, plural: bool) -> Option<String> {
+        let plural = match label.plural {
+            Plural::Contextual => plural,
+            Plural::Always => true,
+            Plural::Never => false,
+        };
+
+        let term = self.locales.term(&label.variable, label.form, plural)?;
+        self.apply(term.to_string(), &label.display)
+    }
+
+    fn render_date(&mut self, date: &DateElement) -> Option<String> {
+        self.called += 1;
+        if self.suppressed.contains(&date.variable) {
+            return None;
+        }
+
+        let value = self.vars.dates.get(date.variable.as_str())?;
+        self.mark_rendered(&date.variable);
+
+        let (year, month, day) = match value {
+            DateValue::Parts { year, month, day } => (*year, *month, *day),
+            DateValue::Literal(text) => return self.apply(text.clone(), &date.display),
+        };
+
+        let (parts, delimiter) = match date.form.and_then(|form| self.localized_date(form)) {
+            Some(localized) => {
+                let parts = localized
+                    .parts
+                    .iter()
+                    .map(|part| {
+                        let custom = date.parts.iter().find(|custom| custom.name == part.name);
+                        DatePart {
+                            name: part.name,
+                            form: custom
+                                .and_then(|custom| custom.form.clone())
+                                .or_else(|| part.form.clone()),
+                            display: part.display.clone(),
+                        }
+                    })
+                    .collect();
+
+                (parts, localized.delimiter.as_str())
+            }
+            None => (date.parts.clone(), date.delimiter.as_str()),
+        };
+
+        let mut rendered = Vec::new();
+        for part in parts.iter().filter(|part| match date.precision {
+            DatePartName::Year => part.name == DatePartName::Year,
+            DatePartName::Month => part.name != DatePartName::Day,
+            DatePartName::Day => true,
+        }) {
+            let form = part.form.as_deref();
+            let text = match part.name {
+                DatePartName::Year if form == Some("short") => {
+                    format!("{:02}", year.rem_euclid(100))
+                }
+                DatePartName::Year if year < 0 => format!("{}BC", -year),
+                DatePartName::Year => year.to_string(),
+                DatePartName::Month => {
+                    let Some(month) = month else { continue };
+                    match form {
+                        Some("numeric") => month.to_string(),
+                        Some("numeric-leading-zeros") => format!("{month:02}"),
+                        Some("short") => self.month(month, TermForm::Short)?,
+                        _ => self.month(month, TermForm::Long)?,
+                    }
+                }
+                DatePartName::Day => {
+                    let Some(day) = day.filter(|_| month.is_some()) else { continue };
+                    match form {
+                        Some("numeric-leading-zeros") => format!("{day:02}"),
+                        Some("ordinal") => format!("{day}{}", self.locales.ordinal_suffix(day)),
+                        _ => day.to_string(),
+                    }
+                }
+            };
+
+            rendered.extend(self.apply(text, &part.display));
+        }
+
+        let text = self.join(&rendered, delimiter);
+        self.apply(text, &date.display)
+    }
+
+    fn localized_date(&self, form: DateForm) -> Option<&'a DateElement> {
+        self.locales.date(form)
+    }
+
+    fn month(&self, month: u32, form: TermForm) -> Option<String> {
+        let name = format!("month-{month:02}");
+        self.locales.term(&name, form, false).map(String::from)
+    }
+
+    fn render_names(&mut self, names: &NamesElement) -> Option<String> {
+        let options = names.options.clone().inherit(&self.names);
+        let mut outputs = Vec::new();
+        for variable in &names.variables {
+            self.called += 1;
+            if self.suppressed.contains(variable) {
+                continue;
+            }
+
+            let Some(list) = self.vars.names.get(variable.as_str()) else { continue };
+            self.mark_rendered(variable);
+
+            let mut text = self.render_name_list(list, &options, names);
+            if let Some((label, after_name)) = &names.label {
+                let label = LabelElement {
+                    variable: variable.clone(),
+                    ..label.clone()
+                };
+
+                if let Some(term) = self.render_term_label(&label, list.len() > 1) {
+                    text = if *after_name {
+                        format!("{text}{term}")
+                    } else {
+                        format!("{term}{text}")
+                    };
+                }
+            }
+
+            outputs.push(text);
+        }
+
+        if outputs.is_empty() {
+            return self.render_substitute(names);
+        }
+
+        let delimiter = names
+            .delimiter
+            .as_deref()
+            .or(options.names_delimiter.as_deref())
+            .unwrap_or_default();
+
+        let text = self.join(&outputs, delimiter);
+        self.apply(text, &names.display)
+    }
+
+    /// Renders the first non-empty element of `cs:substitute`.
+    /// The variables that have been substituted are suppressed in the rest of the output.
+    fn render_substitute(&mut self, names: &NamesElement) -> Option<String> {
+        for element in &names.substitute {
+            let start = self.rendered_vars.len();
+            let text = match element {
+                Element::Names(inner) if !inner.has_name => {
+                    let inherited = NamesElement {
+                        variables: inner.variables.clone(),
+                        options: names.options.clone(),
+                        has_name: true,
+                        name_display: names.name_display.clone(),
+                        family_display: names.family_display.clone(),
+                        given_display: names.given_display.clone(),
+                        et_al_term: names.et_al_term.clone(),
+                        label: names.label.clone(),
+                        substitute: Vec::new(),
+                        delimiter: inner.delimiter.clone().or_else(|| names.delimiter.clone()),
+                        display: inner.display.clone(),
+                    };
+
+                    self.render_names(&inherited)
+                }
+                element => self.render(element),
+            };
+
+            if let Some(text) = text {
+                let substituted = self.rendered_vars[start..].to_vec();
+                self.suppressed.extend(substituted);
+                return self.apply(text, &names.display);
+            }
+        }
+
+        None
+    }
+
+    fn render_name_list(
+        &self,
+        list: &[Name],
+        options: &NameOptions,
+        names: &NamesElement,
+    ) -> String {
+        if options.form.as_deref() == Some("count") {
+            return list.len().to_string();
+        }
+
+        let use_first = options.et_al_use_first.unwrap_or(1).max(1);
+        let truncated = options
+            .et_al_min
+            .map_or(false, |min| list.len() >= min && use_first < list.len());
+
+        let shown = if truncated { &list[..use_first] } else { list };
+        let delimiter = options.delimiter.as_deref().unwrap_or(", ");
+        let formatted: Vec<_> = shown
+            .iter()
+            .enumerate()
+            .map(|(i, name)| self.render_name(name, i, options, names))
+            .collect();
+
+        let mut text = String::new();
+        for (i, (name, _)) in formatted.iter().enumerate() {
+            if i > 0 {
+                let is_last = i == formatted.len() - 1 && !truncated;
+                match options.and.as_deref().filter(|_| is_last) {
+                    Some(and) => {
+                        let and = if and == "symbol" {
+                            "&"
+                        } else {
+                            self.locales
+                                .term("and", TermForm::Long, false)
+                                .unwrap_or("and")
+                        };
+
+                        let use_delimiter = match options.delimiter_precedes_last.as_deref() {
+                            Some("always") => true,
+                            Some("never") => false,
+                            Some("after-inverted-name") => formatted[i - 1].1,
+                            _ => formatted.len() >= 3,
+                        };
+
+                        text.push_str(if use_delimiter { delimiter } else { " " });
+                        text.push_str(and);
+                        text.push(' ');
+                    }
+                    None => text.push_str(delimiter),
+                };
+            }
+
+            text.push_str(name);
+        }
+
+        if truncated {
+            let use_delimiter = match options.delimiter_precedes_et_al.as_deref() {
+                Some("always") => true,
+                Some("never") => false,
+                Some("after-inverted-name") => formatted.last().map_or(false, |(_, inv)| *inv),
+                _ => formatted.len() >= 2,
+            };
+
+            let et_al = self
+                .locales
+                .term(&names.et_al_term, TermForm::Long, false)
+                .unwrap_or("et al.");
+
+            text.push_str(if use_delimiter { delimiter } else { " " });
+            text.push_str(et_al);
+        }
+
+        self.apply(text, &names.name_display).unwrap_or_default()
+    }
+
+    fn render_name(
+        &self,
+        name: &Name,
+        index: usize,
+        options: &NameOptions,
+        names: &NamesElement,
+    ) -> (String, bool) {
+        let family = self
+            .apply(name.surname().to_string(), &names.family_display)
+            .unwrap_or_default();
+
+        if options.form.as_deref() == Some("short") {
+            return (family, false);
+        }
+
+        let given = match name.given_name() {
+            Some(given) => {
+                let middle: Vec<String> = match name.middle_names() {
+                    Some(names) => names.into_iter().map(String::from).collect(),
+                    None => name
+                        .middle_initials()
+                        .unwrap_or_default()
+                        .chars()
+                        .map(|c| format!("{c}."))
+                        .collect(),
+                };
+
+                std::iter::once(given.to_string())
+                    .chain(middle)
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            }
+            None => name
+                .initials()
+                .chars()
+                .map(|c| format!("{c}."))
+                .collect::<Vec<_>>()
+                .join(" "),
+        };
+
+        let given = match &options.initialize_with {
+            Some(with) if options.initialize.unwrap_or(true) => initialize(&given, with),
+            _ => given,
+        };
+
+        let given = self.apply(given, &names.given_display).unwrap_or_default();
+
+        let inverted = match options.name_as_sort_order.as_deref() {
+            Some("all") => true,
+            Some("first") => index == 0,
+            _ => false,
+        };
+
+        let suffix = name.generational_suffix();
+        let text = if given.is_empty() {
+            family
+        } else if inverted {
+            let separator = options.sort_separator.as_deref().unwrap_or(", ");
+            match suffix {
+                Some(suffix) => format!("{family}{separator}{given}{separator}{suffix}"),
+                None => format!("{family}{separator}{given}"),
+            }
+        } else {
+            match suffix {
+                Some(suffix) => format!("{given} {family} {suffix}"),
+                None => format!("{given} {family}"),
+            }
+        };
+
+        (text, inverted)
+    }
+
+    fn render_group(&mut self, group: &GroupElement) -> Option<String> {
+        let called = self.called;
+        let rendered = self.rendered;
+        let parts: Vec<_> = group
+            .children
+            .iter()
+            .filter_map(|element| self.render(element))
+            .collect();
+
+        // A group is suppressed if it calls variables but all of them are empty.
+        if parts.is_empty() || (self.called > called && self.rendered == rendered) {
+            return None;
+        }
+
+        let text = self.join(&parts, &group.delimiter);
+        self.apply(text, &group.display)
+    }
+
+    fn render_choose(&mut self, branches: &[Branch]) -> Option<String> {
+        let branch = branches.iter().find(|branch| {
+            branch.condition.as_ref().map_or(true, |condition| {
+                let mut results = condition.tests.iter().map(|test| self.evaluate(test));
+                match condition.matching {
+                    Match::All => results.all(|result| result),
+                    Match::Any => results.any(|result| result),
+                    Match::None => !results.any(|result| result),
+                }
+            })
+        })?;
+
+        let parts: Vec<_> = branch
+            .children
+            .iter()
+            .filter_map(|element| self.render(element))
+            .collect();
+
+        let text = self.join(&parts, "");
+        (!text.is_empty()).then_some(text)
+    }
+
+    fn evaluate(&self, test: &Test) -> bool {
+        match test {
+            Test::Type(kind) => self.vars.kind == kind,
+            Test::Variable(name) => !self.suppressed.contains(name) && !self.vars.is_empty(name),
+            Test::IsNumeric(name) => self
+                .vars
+                .text
+                .get(name.as_str())
+                .map_or(false, |value| is_numeric(value)),
+            Test::Unsupported => false,
+        }
+    }
+
+    fn variable(&mut self, name: &str) -> Option<String> {
+        self.called += 1;
+        if self.suppressed.contains(name) {
+            return None;
+        }
+
+        let value = self.vars.text.get(name)?.clone();
+        self.mark_rendered(name);
+        Some(value)
+    }
+
+    fn mark_rendered(&mut self, name: &str) {
+        self.rendered += 1;
+        self.rendered_vars.push(name.into());
+    }
+
+    fn page_range(&self, value: &str) -> String {
+        let delimiter = self.term_or("page-range-delimiter", "-");
+        value.replace("--", "-").replace('-', delimiter)
+    }
+
+    /// Applies the formatting and affixes of an element to the rendered text.
+    fn apply(&self, text: String, display: &Display) -> Option<String> {
+        if text.is_empty() {
+            return None;
+        }
+
+        let mut text = match display.text_case {
+            Some(TextCase::Lowercase) => text.to_lowercase(),
+            Some(TextCase::Uppercase) => text.to_uppercase(),
+            Some(TextCase::CapitalizeFirst | TextCase::Sentence) => capitalize_first(&text),
+            Some(TextCase::CapitalizeAll) => text
+                .split(' ')
+                .map(capitalize_first)
+                .collect::<Vec<_>>()
+                .join(" "),
+            Some(TextCase::Title) => titlecase(&text),
+            None => text,
+        };
+
+        if display.strip_periods {
+            text.retain(|c| c != '.');
+        }
+
+        if display.quotes {
+            let open = self.term_or("open-quote", "\"");
+            let close = self.term_or("close-quote", "\"");
+            text = format!("{open}{text}{close}");
+        }
+
+        if display.italic {
+            text = format!("*{text}*");
+        }
+
+        if display.bold {
+            text = format!("**{text}**");
+        }
+
+        let mut output = display.prefix.clone();
+        self.append(&mut output, &text);
+        self.append(&mut output, &display.suffix);
+        Some(output)
+    }
+
+    fn term_or(&self, name: &str, default: &'a str) -> &'a str {
+        self.locales
+            .term(name, TermForm::Long, false)
+            .unwrap_or(default)
+    }
+
+    fn join(&self, parts: &[String], delimiter: &str) -> String {
+        let mut output = String::new();
+        for (i, part) in parts.iter().enumerate() {
+            if i > 0 {
+                self.append(&mut output, delimiter);
+            }
+
+            self.append(&mut output, part);
+        }
+
+        output
+    }
+
+    /// Appends text while avoiding duplicate punctuation like `?.` or `..`
+    /// and moving punctuation into quotes if the locale requires it.
+    fn append(&self, output: &mut String, text: &str) {
+        let mut text = text;
+        if output.ends_with(' ') && text.starts_with(' ') {
+            text = &text[1..];
+        }
+
+        let close_quote = self.term_or("close-quote", "\"");
+        if let Some(first) = text.chars().next().filter(|c| matches!(c, '.' | ',')) {
+            let tail = output.trim_end_matches(['*', '"', '”', '’', '\'']);
+            if first == '.' && tail.ends_with(['.', '?', '!']) {
+                text = &text[1..];
+            } else if self.locales.punctuation_in_quote() && output.ends_with(close_quote) {
+                output.truncate(output.len() - close_quote.len());
+                output.push(first);
+                output.push_str(close_quote);
+                text = &text[1..];
+            }
+        }
+
+        output.push_str(text);
+    }
+}
+
+fn capitalize_first(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Reduces given names to initials, e.g. `John Ronald` becomes `J. R.` with `initialize-with=". "`.
+fn initialize(given: &str, with: &str) -> String {
+    let mut output = String::new();
+    for word in given.split_whitespace() {
+        let parts: Vec<_> = word
+            .split('-')
+            .filter_map(|part| part.chars().next())
+            .map(|c| format!("{c}{}", with.trim_end()))
+            .collect();
+
+        output.push_str(&parts.join("-"));
+        output.push_str(&with[with.trim_end().len()..]);
+    }
+
+    output.trim_end().to_string()
+}
+
+fn is_numeric(value: &str) -> bool {
+    value
+        .split(['-', '–', ',', '&'])
+        .map(str::trim)
+        .all(|token| {
+            !token.is_empty()
+                && token.chars().any(|c| c.is_ascii_digit())
+                && token.chars().all(char::is_alphanumeric)
+        })
+}
+
+fn is_plural(value: &str) -> bool {
+    is_numeric(value) && value.contains(['-', '–', ',', '&'])
+}
+
+fn to_roman(mut n: u32) -> Option<String> {
+    if n == 0 || n >= 4000 {
+        return None;
+    }
+
+    const NUMERALS: [(u32, &str); 13] = [
+        (1000, "m"),
+        (900, "cm"),
+        (500, "d"),
+        (400, "cd"),
+        (100, "c"),
+        (90, "xc"),
+        (50, "l"),
+        (40, "xl"),
+        (10, "x"),
+        (9, "ix"),
+        (5, "v"),
+        (4, "iv"),
+        (1, "i"),
+    ];
+
+    let mut output = String::new();
+    for (value, numeral) in NUMERALS {
+        while n >= value {
+            output.push_str(numeral);
+            n -= value;
+        }
+    }
+
+    Some(output)
+}
@@ -0,0 +1,565 @@
+use roxmltree::{Document, Node};
+use rustc_hash::FxHashMap;
+
+use super::locale::{Locale, TermForm};
+
+/// A parsed CSL 1.0.2 style.
+#[derive(Debug, Clone)]
+pub struct Style {
+    pub(crate) title: Option<String>,
+    pub(crate) independent_parent: Option<String>,
+    pub(crate) default_locale: Option<String>,
+    pub(crate) locales: Vec<Locale>,
+    pub(crate) macros: FxHashMap<String, Vec<Element>>,
+    pub(crate) citation: Option<Layout>,
+    pub(crate) bibliography: Option<Layout>,
+}
+
+impl Style {
+    /// Parses the XML of a `.csl` file. Returns `None` if the document is not a CSL style.
+    #[must_use]
+    pub fn parse(text: &str) -> Option<Self> {
+        let document = Document::parse(text).ok()?;
+        let root = document.root_element();
+        if root.tag_name().name() != "style" {
+            return None;
+        }
+
+        let options = NameOptions::parse(root);
+        let mut style = Self {
+            title: None,
+            independent_parent: None,
+            default_locale: root.attribute("default-locale").map(String::from),
+            locales: Vec::new(),
+            macros: FxHashMap::default(),
+            citation: None,
+            bibliography: None,
+        };
+
+        for child in children(root) {
+            match child.tag_name().name() {
+                "info" => {
+                    style.title = children(child)
+                        .find(|node| node.has_tag_name("title"))
+                        .and_then(|node| node.text())
+                        .map(String::from);
+
+                    style.independent_parent = children(child)
+                        .filter(|node| node.has_tag_name("link"))
+                        .find(|node| node.attribute("rel") == Some("independent-parent"))
+                        .and_then(|node| node.attribute("href"))
+                        .map(String::from);
+                }
+                "locale" => {
+                    style.locales.push(Locale::from_node(child));
+                }
+                "macro" => {
+                    if let Some(name) = child.attribute("name") {
+                        style.macros.insert(name.into(), parse_elements(child));
+                    }
+                }
+                "citation" => {
+                    style.citation = Layout::parse(child, &options);
+                }
+                "bibliography" => {
+                    style.bibliography = Layout::parse(child, &options);
+                }
+                _ => {}
+            }
+        }
+
+        Some(style)
+    }
+
+    /// The title from the `info` section of the style.
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    /// The URI of the style that provides the formatting of a dependent style,
+    /// e.g. `http://www.zotero.org/styles/apa`.
+    pub fn independent_parent(&self) -> Option<&str> {
+        self.independent_parent.as_deref()
+    }
+
+    /// The locale that the style is written for, e.g. `en-US`.
+    pub fn default_locale(&self) -> Option<&str> {
+        self.default_locale.as_deref()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Layout {
+    pub(crate) elements: Vec<Element>,
+    pub(crate) delimiter: String,
+    pub(crate) display: Display,
+    pub(crate) names: NameOptions,
+}
+
+impl Layout {
+    fn parse(node: Node, parent: &NameOptions) -> Option<Self> {
+        let layout = children(node).find(|child| child.has_tag_name("layout"))?;
+        Some(Self {
+            elements: parse_elements(layout),
+            delimiter: layout.attribute("delimiter").unwrap_or_default().into(),
+            display: Display::parse(layout),
+            names: NameOptions::parse(node).inherit(parent),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Display {
+    pub(crate) prefix: String,
+    pub(crate) suffix: String,
+    pub(crate) italic: bool,
+    pub(crate) bold: bool,
+    pub(crate) quotes: bool,
+    pub(crate) strip_periods: bool,
+    pub(crate) text_case: Option<TextCase>,
+}
+
+impl Display {
+    pub(crate) fn parse(node: Node) -> Self {
+        Self {
+            prefix: node.attribute("prefix").unwrap_or_default().into(),
+            suffix: node.attribute("suffix").unwrap_or_default().into(),
+            italic: matches!(node.attribute("font-style"), Some("italic" | "oblique")),
+            bold: node.attribute("font-weight") == Some("bold"),
+            quotes: node.attribute("quotes") == Some("true"),
+            strip_periods: node.attribute("strip-periods") == Some("true"),
+            text_case: node.attribute("text-case").and_then(TextCase::parse),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub(crate) enum TextCase {
+    Lowercase,
+    Uppercase,
+    CapitalizeFirst,
+    CapitalizeAll,
+    Sentence,
+    Title,
+}
+
+impl TextCase {
+    fn parse(input: &str) -> Option<Self> {
+        Some(match input {
+            "lowercase" => Self::Lowercase,
+            "uppercase" => Self::Uppercase,
+            "capitalize-first" => Self::CapitalizeFirst,
+            "capitalize-all" => Self::CapitalizeAll,
+            "sentence" => Self::Sentence,
+            "title" => Self::Title,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum Element {
+    Text(TextSource, Display),
+    Number(NumberElement),
+    Label(LabelElement),
+    Date(DateElement),
+    Names(Box<NamesElement>),
+    Group(GroupElement),
+    Choose(Vec<Branch>),
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum TextSource {
+    Variable(String, VariableForm),
+    Macro(String),
+    Term(String, TermForm, bool),
+    Value(String),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub(crate) enum VariableForm {
+    Long,
+    Short,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct NumberElement {
+    pub(crate) variable: String,
+    pub(crate) form: NumberForm,
+    pub(crate) display: Display,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub(crate) enum NumberForm {
+    Numeric,
+    Ordinal,
+    LongOrdinal,
+    Roman,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct LabelElement {
+    pub(crate) variable: String,
+    pub(crate) form: TermForm,
+    pub(crate) plural: Plural,
+    pub(crate) display: Display,
+}
+
+impl LabelElement {
+    fn parse(node: Node) -> Self {
+        Self {
+            variable: node.attribute("variable").unwrap_or_default().into(),
+            form: TermForm::parse(node.attribute("form").unwrap_or("long")),
+            plural: match node.attribute("plural") {
+                Some("always") => Plural::Always,
+                Some("never") => Plural::Never,
+                _ => Plural::Contextual,
+            },
+            display: Display::parse(node),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub(crate) enum Plural {
+    Contextual,
+    Always,
+    Never,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub(crate) enum DateForm {
+    Text,
+    Numeric,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub(crate) enum DatePartName {
+    Year,
+    Month,
+    Day,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct DatePart {
+    pub(crate) name: DatePartName,
+    pub(crate) form: Option<String>,
+    pub(crate) display: Display,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct DateElement {
+    pub(crate) variable: String,
+    pub(crate) form: Option<DateForm>,
+    pub(crate) precision: DatePartName,
+    pub(crate) parts: Vec<DatePart>,
+    pub(crate) delimiter: String,
+    pub(crate) display: Display,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct NamesElement {
+    pub(crate) variables: Vec<String>,
+    pub(crate) options: NameOptions,
+    pub(crate) has_name: bool,
+    pub(crate) name_display: Display,
+    pub(crate) family_display: Display,
+    pub(crate) given_display: Display,
+    pub(crate) et_al_term: String,
+    pub(crate) label: Option<(LabelElement, bool)>,
+    pub(crate) substitute: Vec<Element>,
+    pub(crate) delimiter: Option<String>,
+    pub(crate) display: Display,
+}
+
+/// The inheritable attributes of `cs:name` (CSL specification, "Inheritable Name Options").
+#[derive(Debug, Clone, Default)]
+pub(crate) struct NameOptions {
+    pub(crate) and: Option<String>,
+    pub(crate) delimiter: Option<String>,
+    pub(crate) delimiter_precedes_et_al: Option<String>,
+    pub(crate) delimiter_precedes_last: Option<String>,
+    pub(crate) et_al_min: Option<usize>,
+    pub(crate) et_al_use_first: Option<usize>,
+    pub(crate) form: Option<String>,
+    pub(crate) initialize: Option<bool>,
+    pub(crate) initialize_with: Option<String>,
+    pub(crate) name_as_sort_order: Option<String>,
+    pub(crate) sort_separator: Option<String>,
+    pub(crate) names_delimiter: Option<String>,
+}
+
+impl NameOptions {
+    fn parse(node: Node) -> Self {
+        let attr = |name| node.attribute(name).map(String::from);
+        Self {
+            and: attr("and"),
+            delimiter: attr("name-delimiter").or_else(|| {
+                node.has_tag_name("name")
+                    .then(|| attr("delimiter"))
+                    .flatten()
+            }),
+            delimiter_precedes_et_al: attr("delimiter-precedes-et-al"),
+            delimiter_precedes_last: attr("delimiter-precedes-last"),
+            et_al_min: attr("et-al-min").and_then(|value| value.parse().ok()),
+            et_al_use_first: attr("et-al-use-first").and_then(|value| value.parse().ok()),
+            form: node
+                .attribute("name-form")
+                .or_else(|| node.has_tag_name("name").then(|| node.attribute("form"))?)
+                .map(String::from),
+            initialize: attr("initialize").map(|value| value != "false"),
+            initialize_with: attr("initialize-with"),
+            name_as_sort_order: attr("name-as-sort-order"),
+            sort_separator: attr("sort-separator"),
+            names_delimiter: attr("names-delimiter"),
+        }
+    }
+
+    pub(crate) fn inherit(self, parent: &Self) -> Self {
+        Self {
+            and: self.and.or_else(|| parent.and.clone()),
+            delimiter: self.delimiter.or_else(|| parent.delimiter.clone()),
+            delimiter_precedes_et_al: self
+                .delimiter_precedes_et_al
+                .or_else(|| parent.delimiter_precedes_et_al.clone()),
+            delimiter_precedes_last: self
+                .delimiter_precedes_last
+                .or_else(|| parent.delimiter_precedes_last.clone()),
+            et_al_min: self.et_al_min.or(parent.et_al_min),
+            et_al_use_first: self.et_al_use_first.or(parent.et_al_use_first),
+            form: self.form.or_else(|| parent.form.clone()),
+            initialize: self.initialize.or(parent.initialize),
+            initialize_with: self
+                .initialize_with
+                .or_else(|| parent.initialize_with.clone()),
+            name_as_sort_order: self
+                .name_as_sort_order
+                .or_else(|| parent.name_as_sort_order.clone()),
+            sort_separator: self
+                .sort_separator
+                .or_else(|| parent.sort_separator.clone()),
+            names_delimiter: self
+                .names_delimiter
+                .or_else(|| parent.names_delimiter.clone()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct GroupElement {
+    pub(crate) children: Vec<Element>,
+    pub(crate) delimiter: String,
+    pub(crate) display: Display,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Branch {
+    pub(crate) condition: Option<Condition>,
+    pub(crate) children: Vec<Element>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Condition {
+    pub(crate) matching: Match,
+    pub(crate) tests: Vec<Test>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub(crate) enum Match {
+    All,
+    Any,
+    None,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum Test {
+    Type(String),
+    Variable(String),
+    IsNumeric(String),
+    /// Conditions that depend on the citation context (e.g. `position` or `locator`).
+    /// They never apply when rendering a single entry.
+    Unsupported,
+}
+
+pub(crate) fn children<'a, 'input>(
+    node: Node<'a, 'input>,
+) -> impl Iterator<Item = Node<'a, 'input>> {
+    node.children().filter(|child| child.is_element())
+}
+
+fn parse_elements(node: Node) -> Vec<Element> {
+    children(node).filter_map(parse_element).collect()
+}
+
+fn parse_element(node: Node) -> Option<Element> {
+    let element = match node.tag_name().name() {
+        "text" => {
+            let source = if let Some(variable) = node.attribute("variable") {
+                let form = match node.attribute("form") {
+                    Some("short") => VariableForm::Short,
+                    _ => VariableForm::Long,
+                };
+
+                TextSource::Variable(variable.into(), form)
+            } else if let Some(name) = node.attribute("macro") {
+                TextSource::Macro(name.into())
+            } else if let Some(term) = node.attribute("term") {
+                let form = TermForm::parse(node.attribute("form").unwrap_or("long"));
+                let plural = node.attribute("plural") == Some("true");
+                TextSource::Term(term.into(), form, plural)
+            } else {
+                TextSource::Value(node.attribute("value")?.into())
+            };
+
+            Element::Text(source, Display::parse(node))
+        }
+        "number" => Element::Number(NumberElement {
+            variable: node.attribute("variable")?.into(),
+            form: match node.attribute("form") {
+                Some("ordinal") => NumberForm::Ordinal,
+                Some("long-ordinal") => NumberForm::LongOrdinal,
+                Some("roman") => NumberForm::Roman,
+                _ => NumberForm::Numeric,
+            },
+            display: Display::parse(node),
+        }),
+        "label" => Element::Label(LabelElement::parse(node)),
+        "date" => Element::Date(parse_date(node)?),
+        "names" => Element::Names(Box::new(parse_names(node))),
+        "group" => Element::Group(GroupElement {
+            children: parse_elements(node),
+            delimiter: node.attribute("delimiter").unwrap_or_default().into(),
+            display: Display::parse(node),
+        }),
+        "choose" => Element::Choose(children(node).map(parse_branch).collect()),
+        _ => return None,
+    };
+
+    Some(element)
+}
+
+pub(crate) fn parse_date(node: Node) -> Option<DateElement> {
+    let form = match node.attribute("form") {
+        Some("text") => Some(DateForm::Text),
+        Some("numeric") => Some(DateForm::Numeric),
+        _ => None,
+    };
+
+    let precision = match node.attribute("date-parts") {
+        Some("year") => DatePartName::Year,
+        Some("year-month") => DatePartName::Month,
+        _ => DatePartName::Day,
+    };
+
+    let parts = children(node)
+        .filter(|child| child.has_tag_name("date-part"))
+        .filter_map(|child| {
+            let name = match child.attribute("name")? {
+                "year" => DatePartName::Year,
+                "month" => DatePartName::Month,
+                "day" => DatePartName::Day,
+                _ => return None,
+            };
+
+            Some(DatePart {
+                name,
+                form: child.attribute("form").map(String::from),
+                display: Display::parse(child),
+            })
+        })
+        .collect();
+
+    Some(DateElement {
+        variable: node.attribute("variable").unwrap_or_default().into(),
+        form,
+        precision,
+        parts,
+        delimiter: node.attribute("delimiter").unwrap_or_default().into(),
+        display: Display::parse(node),
+    })
+}
+
+fn parse_names(node: Node) -> NamesElement {
+    let mut names = NamesElement {
+        variables: node
+            .attribute("variable")
+            .unwrap_or_default()
+            .split_whitespace()
+            .map(String::from)
+            .collect(),
+        options: NameOptions::default(),
+        has_name: false,
+        name_display: Display::default(),
+        family_display: Display::default(),
+        given_display: Display::default(),
+        et_al_term: String::from("et-al"),
+        label: None,
+        substitute: Vec::new(),
+        delimiter: node.attribute("delimiter").map(String::from),
+        display: Display::parse(node),
+    };
+
+    for child in children(node) {
+        match child.tag_name().name() {
+            "name" => {
+                names.has_name = true;
+                names.options = NameOptions::parse(child);
+                names.name_display = Display::parse(child);
+                for part in children(child).filter(|part| part.has_tag_name("name-part")) {
+                    match part.attribute("name") {
+                        Some("family") => names.family_display = Display::parse(part),
+                        Some("given") => names.given_display = Display::parse(part),
+                        _ => {}
+                    };
+                }
+            }
+            "et-al" => {
+                if let Some(term) = child.attribute("term") {
+                    names.et_al_term = term.into();
+                }
+            }
+            "label" => {
+                names.label = Some((LabelElement::parse(child), names.has_name));
+            }
+            "substitute" => {
+                names.substitute = parse_elements(child);
+            }
+            _ => {}
+        };
+    }
+
+    names
+}
+
+fn parse_branch(node: Node) -> Branch {
+    let children = parse_elements(node);
+    if node.has_tag_name("else") {
+        return Branch {
+            condition: None,
+            children,
+        };
+    }
+
+    let matching = match node.attribute("match") {
+        Some("any") => Match::Any,
+        Some("none") => Match::None,
+        _ => Match::All,
+    };
+
+    let mut tests = Vec::new();
+    for attribute in node.attributes() {
+        let values = attribute.value().split_whitespace().map(String::from);
+        match attribute.name() {
+            "type" => tests.extend(values.map(Test::Type)),
+            "variable" => tests.extend(values.map(Test::Variable)),
+            "is-numeric" => tests.extend(values.map(Test::IsNumeric)),
+            "match" => {}
+            _ => tests.push(Test::Unsupported),
+        };
+    }
+
+    Branch {
+        condition: Some(Condition { matching, tests }),
+        children,
+    }
+}
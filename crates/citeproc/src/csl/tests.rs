@@ -0,0 +1,308 @@
+use insta::assert_snapshot;
+use parser::parse_bibtex;
+use rowan::ast::AstNode;
+use syntax::bibtex;
+
+use super::{Locale, Style};
+
+const APA: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<style xmlns="http://purl.org/net/xbiblio/csl" class="in-text" version="1.0" default-locale="en-US">
+  <info><title>APA (excerpt)</title></info>
+  <macro name="author">
+    <names variable="author">
+      <name name-as-sort-order="all" and="symbol" sort-separator=", " initialize-with=". "
+            delimiter=", " delimiter-precedes-last="always"/>
+      <substitute>
+        <names variable="editor"/>
+        <text variable="title"/>
+      </substitute>
+      <label form="short" prefix=" (" suffix=")" text-case="capitalize-first"/>
+    </names>
+  </macro>
+  <macro name="issued">
+    <group prefix=" (" suffix=")">
+      <date variable="issued">
+        <date-part name="year"/>
+      </date>
+    </group>
+  </macro>
+  <macro name="title">
+    <choose>
+      <if type="book report thesis" match="any">
+        <text variable="title" font-style="italic"/>
+      </if>
+      <else>
+        <text variable="title"/>
+      </else>
+    </choose>
+  </macro>
+  <macro name="container">
+    <choose>
+      <if type="article-journal">
+        <group delimiter=", ">
+          <text variable="container-title" font-style="italic"/>
+          <group>
+            <text variable="volume" font-style="italic"/>
+            <text variable="issue" prefix="(" suffix=")"/>
+          </group>
+          <text variable="page"/>
+        </group>
+      </if>
+      <else-if type="chapter paper-conference" match="any">
+        <group delimiter=" ">
+          <text term="in" text-case="capitalize-first"/>
+          <group delimiter=", ">
+            <names variable="editor">
+              <name and="symbol" initialize-with=". " delimiter=", "/>
+              <label form="short" prefix=" (" suffix=")" text-case="capitalize-first"/>
+            </names>
+            <text variable="container-title" font-style="italic"/>
+          </group>
+          <group prefix="(" suffix=")">
+            <label variable="page" form="short"/>
+            <text variable="page" prefix=" "/>
+          </group>
+        </group>
+      </else-if>
+    </choose>
+  </macro>
+  <citation>
+    <layout>
+      <text macro="author"/>
+    </layout>
+  </citation>
+  <bibliography>
+    <layout suffix=".">
+      <group delimiter=". ">
+        <group>
+          <text macro="author"/>
+          <text macro="issued"/>
+        </group>
+        <text macro="title"/>
+        <text macro="container"/>
+        <text variable="publisher"/>
+      </group>
+      <text variable="DOI" prefix=". https://doi.org/"/>
+    </layout>
+  </bibliography>
+</style>
+"#;
+
+const IEEE: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<style xmlns="http://purl.org/net/xbiblio/csl" class="in-text" version="1.0" default-locale="en-US"
+       et-al-min="7" et-al-use-first="1">
+  <info><title>IEEE (excerpt)</title></info>
+  <bibliography>
+    <layout suffix=".">
+      <group display="left-margin">
+        <text variable="citation-number" prefix="[" suffix="]"/>
+      </group>
+      <group delimiter=", ">
+        <names variable="author">
+          <name and="text" initialize-with=". " delimiter=", "/>
+        </names>
+        <text variable="title" quotes="true"/>
+        <text variable="container-title" font-style="italic"/>
+        <group delimiter=" ">
+          <label variable="volume" form="short"/>
+          <text variable="volume"/>
+        </group>
+        <group delimiter=" ">
+          <label variable="issue" form="short"/>
+          <text variable="issue"/>
+        </group>
+        <group delimiter=" ">
+          <label variable="page" form="short"/>
+          <text variable="page"/>
+        </group>
+        <date variable="issued" delimiter=" ">
+          <date-part name="month" form="short"/>
+          <date-part name="year"/>
+        </date>
+        <text variable="DOI" prefix="doi: "/>
+      </group>
+    </layout>
+  </bibliography>
+</style>
+"#;
+
+const CHICAGO: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<style xmlns="http://purl.org/net/xbiblio/csl" class="note" version="1.0" default-locale="de-DE">
+  <info><title>Chicago (excerpt)</title></info>
+  <bibliography>
+    <layout suffix=".">
+      <group delimiter=". ">
+        <names variable="author">
+          <name name-as-sort-order="first" and="text" sort-separator=", " delimiter=", "/>
+          <label form="short" prefix=", "/>
+          <substitute>
+            <names variable="editor"/>
+          </substitute>
+        </names>
+        <text variable="title" font-style="italic"/>
+        <group delimiter=" ">
+          <number variable="edition" form="ordinal"/>
+          <text term="edition" form="short"/>
+        </group>
+        <group delimiter=": ">
+          <text variable="publisher-place"/>
+          <group delimiter=", ">
+            <text variable="publisher"/>
+            <date variable="issued" form="text" date-parts="year"/>
+          </group>
+        </group>
+      </group>
+    </layout>
+  </bibliography>
+</style>
+"#;
+
+const GERMAN: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<locale xmlns="http://purl.org/net/xbiblio/csl" version="1.0" xml:lang="de-DE">
+  <date form="text">
+    <date-part name="day" form="numeric-leading-zeros" suffix=". "/>
+    <date-part name="month" suffix=" "/>
+    <date-part name="year"/>
+  </date>
+  <terms>
+    <term name="and">und</term>
+    <term name="edition" form="short">Aufl.</term>
+    <term name="ordinal">.</term>
+    <term name="editor" form="short">
+      <single>Hrsg.</single>
+      <multiple>Hrsg.</multiple>
+    </term>
+  </terms>
+</locale>
+"#;
+
+fn render_entry(style: &str, locale: Option<&str>, input: &str) -> String {
+    let style = Style::parse(style).unwrap();
+    let locale = locale.map(|text| Locale::parse(text).unwrap());
+    let green = parse_bibtex(input);
+    let root = bibtex::Root::cast(bibtex::SyntaxNode::new_root(green)).unwrap();
+    let entry = root.entries().next().unwrap();
+    super::render(&entry, std::slice::from_ref(&root), &style, locale.as_ref()).unwrap()
+}
+
+const RIVEST_1978: &str = r#"
+@article{10.1145/359340.359342,
+    author = {Rivest, R. L. and Shamir, A. and Adleman, L.},
+    title = {A Method for Obtaining Digital Signatures and Public-Key Cryptosystems},
+    year = {1978},
+    volume = {21},
+    number = {2},
+    doi = {10.1145/359340.359342},
+    journal = {Commun. ACM},
+    month = {feb},
+    pages = {120--126},
+}"#;
+
+#[test]
+fn apa_article() {
+    assert_snapshot!(render_entry(APA, None, RIVEST_1978));
+}
+
+#[test]
+fn apa_incollection() {
+    assert_snapshot!(render_entry(
+        APA,
+        None,
+        r#"
+@incollection{westfahl:space,
+    author       = {Westfahl, Gary},
+    title        = {The True Frontier},
+    subtitle     = {Confronting and Avoiding the Realities of Space in {American}
+                    Science Fiction Films},
+    pages        = {55-65},
+    crossref     = {westfahl:frontier},
+    editor       = {Westfahl, Gary},
+    booktitle    = {Space and Beyond},
+    publisher    = {Greenwood},
+    date         = 2000,
+}"#
+    ));
+}
+
+#[test]
+fn apa_substitute_editor() {
+    assert_snapshot!(render_entry(
+        APA,
+        None,
+        r#"
+@book{jaffe,
+    editor       = {Jaffé, Philipp and Loewenfeld, Samuel and Kaltenbrunner, Ferdinand},
+    title        = {Regesta Pontificum Romanorum},
+    date         = {1885/1888},
+    publisher    = {Veit},
+}"#
+    ));
+}
+
+#[test]
+fn ieee_article() {
+    assert_snapshot!(render_entry(IEEE, None, RIVEST_1978));
+}
+
+#[test]
+fn ieee_et_al() {
+    assert_snapshot!(render_entry(
+        IEEE,
+        None,
+        r#"
+@article{many,
+    author  = {Lovelace, Ada and Babbage, Charles and Turing, Alan and Hopper, Grace
+                and Dijkstra, Edsger and Knuth, Donald and Liskov, Barbara},
+    title   = {Collaboration},
+    journal = {Journal},
+    year    = {2020},
+}"#
+    ));
+}
+
+#[test]
+fn chicago_locale() {
+    assert_snapshot!(render_entry(
+        CHICAGO,
+        Some(GERMAN),
+        r#"
+@book{knuth,
+    editor    = {Knuth, Donald E. and Doe, John},
+    title     = {The Art of Computer Programming},
+    edition   = {3},
+    location  = {Reading},
+    publisher = {Addison-Wesley},
+    date      = {1997-05-12},
+}"#
+    ));
+}
+
+#[test]
+fn style_metadata() {
+    let style = Style::parse(CHICAGO).unwrap();
+    assert_eq!(style.title(), Some("Chicago (excerpt)"));
+    assert_eq!(style.default_locale(), Some("de-DE"));
+    assert!(Style::parse(GERMAN).is_none());
+}
+
+#[test]
+fn style_independent_parent() {
+    let style = Style::parse(
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<style xmlns="http://purl.org/net/xbiblio/csl" class="in-text" version="1.0" default-locale="en-GB">
+  <info>
+    <title>Journal of Examples</title>
+    <link href="http://www.zotero.org/styles/journal-of-examples" rel="self"/>
+    <link href="http://www.zotero.org/styles/apa" rel="independent-parent"/>
+  </info>
+</style>"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        style.independent_parent(),
+        Some("http://www.zotero.org/styles/apa")
+    );
+    assert_eq!(style.default_locale(), Some("en-GB"));
+    assert_eq!(Style::parse(APA).unwrap().independent_parent(), None);
+}
@@ -0,0 +1,229 @@
+use chrono::Datelike;
+use human_name::Name;
+use rustc_hash::FxHashMap;
+
+use crate::{
+    entry::{EntryData, EntryKind},
+    field::{
+        author::AuthorField,
+        date::{DateField, DateFieldData},
+        number::NumberField,
+        text::TextField,
+    },
+};
+
+#[derive(Debug, Clone)]
+pub(crate) enum DateValue {
+    Parts {
+        year: i32,
+        month: Option<u32>,
+        day: Option<u32>,
+    },
+    Literal(String),
+}
+
+/// The CSL variables of a BibTeX entry.
+#[derive(Debug, Default)]
+pub(crate) struct Variables {
+    pub(crate) kind: &'static str,
+    pub(crate) text: FxHashMap<&'static str, String>,
+    pub(crate) names: FxHashMap<&'static str, Vec<Name>>,
+    pub(crate) dates: FxHashMap<&'static str, DateValue>,
+}
+
+impl Variables {
+    pub(crate) fn is_empty(&self, name: &str) -> bool {
+        !self.text.contains_key(name)
+            && !self.names.contains_key(name)
+            && !self.dates.contains_key(name)
+    }
+}
+
+impl From<EntryData> for Variables {
+    fn from(mut entry: EntryData) -> Self {
+        let kind = csl_type(&entry);
+        let mut vars = Variables {
+            kind,
+            ..Self::default()
+        };
+
+        let mut text = |name, value: Option<String>| {
+            if let Some(value) = value.filter(|value| !value.is_empty()) {
+                vars.text.insert(name, value);
+            }
+        };
+
+        let title = join_title(&mut entry, TextField::Title, TextField::Subtitle);
+        let book_title = join_title(&mut entry, TextField::BookTitle, TextField::BookSubtitle);
+        let journal = entry
+            .text
+            .remove(&TextField::JournalTitle)
+            .or_else(|| entry.text.remove(&TextField::Journal))
+            .map(|data| data.text);
+
+        let container_title = match kind {
+            "article-journal" | "article-magazine" | "article-newspaper" => journal,
+            _ => book_title.or(journal),
+        };
+
+        text("title", title);
+        text("container-title", container_title);
+        text("collection-title", take_text(&mut entry, TextField::Series));
+        text("publisher", take_text(&mut entry, TextField::Publisher));
+        text(
+            "publisher-place",
+            take_text(&mut entry, TextField::Location),
+        );
+        text("event-title", take_text(&mut entry, TextField::EventTitle));
+        text("event-place", take_text(&mut entry, TextField::Venue));
+        text("genre", take_text(&mut entry, TextField::Type));
+        text("DOI", take_text(&mut entry, TextField::Doi));
+        text("ISBN", take_text(&mut entry, TextField::Isbn));
+        text("ISSN", take_text(&mut entry, TextField::Issn));
+        text("URL", take_text(&mut entry, TextField::Url));
+        text("note", take_text(&mut entry, TextField::Note));
+        text("abstract", take_text(&mut entry, TextField::Abstract));
+        text("version", take_text(&mut entry, TextField::Version));
+        text("status", take_text(&mut entry, TextField::Pubstate));
+        text("language", take_text(&mut entry, TextField::Language));
+        text("chapter-number", take_text(&mut entry, TextField::Chapter));
+        text("medium", take_text(&mut entry, TextField::HowPublished));
+
+        let mut number = |field| entry.number.remove(&field).map(|data| data.to_string());
+        let issue_or_number = number(NumberField::Number);
+        text("volume", number(NumberField::Volume));
+        text("number-of-volumes", number(NumberField::Volumes));
+        text("edition", number(NumberField::Edition));
+        text("page", number(NumberField::Pages));
+        text("number-of-pages", number(NumberField::PageTotal));
+        text("part-number", number(NumberField::Part));
+        match kind {
+            "article-journal" | "article-magazine" | "article-newspaper" => {
+                let issue = entry.text.remove(&TextField::Issue).map(|data| data.text);
+                text("issue", issue.or(issue_or_number));
+            }
+            _ => text("number", issue_or_number),
+        };
+
+        for (field, name) in [
+            (AuthorField::Author, "author"),
+            (AuthorField::Editor, "editor"),
+            (AuthorField::Translator, "translator"),
+            (AuthorField::Commentator, "commenter"),
+        ] {
+            if let Some(data) = entry.author.remove(&field) {
+                if !data.authors.is_empty() {
+                    vars.names.insert(name, data.authors);
+                }
+            }
+        }
+
+        if let Some(issued) = issued_date(&mut entry) {
+            vars.dates.insert("issued", issued);
+        }
+
+        for (field, name) in [
+            (DateField::UrlDate, "accessed"),
+            (DateField::EventDate, "event-date"),
+        ] {
+            if let Some(date) = entry.date.remove(&field).map(date_value) {
+                vars.dates.insert(name, date);
+            }
+        }
+
+        vars
+    }
+}
+
+fn take_text(entry: &mut EntryData, field: TextField) -> Option<String> {
+    entry.text.remove(&field).map(|data| data.text)
+}
+
+fn join_title(entry: &mut EntryData, title: TextField, subtitle: TextField) -> Option<String> {
+    let title = take_text(entry, title)?;
+    Some(match take_text(entry, subtitle) {
+        Some(subtitle) => format!("{title}: {subtitle}"),
+        None => title,
+    })
+}
+
+fn issued_date(entry: &mut EntryData) -> Option<DateValue> {
+    if let Some(date) = entry.date.remove(&DateField::Date) {
+        return Some(date_value(date));
+    }
+
+    let year = match entry.date.remove(&DateField::Year)? {
+        DateFieldData::Year(year) => year,
+        other => return Some(date_value(other)),
+    };
+
+    let month = match entry.date.remove(&DateField::Month) {
+        Some(DateFieldData::Month(month)) => Some(month.number_from_month()),
+        _ => None,
+    };
+
+    Some(DateValue::Parts {
+        year,
+        month,
+        day: None,
+    })
+}
+
+fn date_value(data: DateFieldData) -> DateValue {
+    match data {
+        DateFieldData::Date(date) => DateValue::Parts {
+            year: date.year(),
+            month: Some(date.month()),
+            day: Some(date.day()),
+        },
+        DateFieldData::Year(year) => DateValue::Parts {
+            year,
+            month: None,
+            day: None,
+        },
+        DateFieldData::Month(month) => DateValue::Literal(month.name().into()),
+        DateFieldData::Other(text) => {
+            let mut parts = text.splitn(3, '-').map(|part| part.parse::<u32>().ok());
+            match (parts.next().flatten(), parts.next().flatten(), parts.next()) {
+                (Some(year), Some(month), None) if (1..=12).contains(&month) => DateValue::Parts {
+                    year: year as i32,
+                    month: Some(month),
+                    day: None,
+                },
+                _ => DateValue::Literal(text),
+            }
+        }
+    }
+}
+
+/// Maps the biblatex entry types to the closest CSL item type.
+fn csl_type(entry: &EntryData) -> &'static str {
+    match entry.kind {
+        EntryKind::Article => "article-journal",
+        EntryKind::Book
+        | EntryKind::MVBook
+        | EntryKind::Collection
+        | EntryKind::MVCollection
+        | EntryKind::Proceedings
+        | EntryKind::MVProceedings
+        | EntryKind::Reference
+        | EntryKind::MVReference
+        | EntryKind::Manual => "book",
+        EntryKind::InBook
+        | EntryKind::BookInBook
+        | EntryKind::SuppBook
+        | EntryKind::InCollection
+        | EntryKind::SuppCollection => "chapter",
+        EntryKind::InProceedings | EntryKind::Conference => "paper-conference",
+        EntryKind::InReference => "entry-encyclopedia",
+        EntryKind::Booklet => "pamphlet",
+        EntryKind::DataSet => "dataset",
+        EntryKind::Online | EntryKind::Electronic | EntryKind::Www => "webpage",
+        EntryKind::Patent => "patent",
+        EntryKind::Periodical | EntryKind::SuppPeriodical => "periodical",
+        EntryKind::Report | EntryKind::TechReport => "report",
+        EntryKind::Software => "software",
+        EntryKind::Thesis | EntryKind::MasterThesis | EntryKind::PhdThesis => "thesis",
+        EntryKind::Misc | EntryKind::Set | EntryKind::Unknown => "document",
+    }
+}
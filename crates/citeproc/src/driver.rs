@@ -1,7 +1,5 @@
 use isocountry::CountryCode;
 use itertools::Itertools;
-use syntax::bibtex;
-use titlecase::titlecase;
 use url::Url;
 
 use super::{
@@ -21,8 +19,7 @@ pub struct Driver {
 }
 
 impl Driver {
-    pub fn process(&mut self, entry: &bibtex::Entry) {
-        let entry = EntryData::from(entry);
+    pub fn process(&mut self, entry: EntryData) {
         match entry.kind {
             EntryKind::Article
             | EntryKind::DataSet
@@ -244,11 +241,11 @@ impl Driver {
         let subtitle = entry
             .text
             .remove(&TextField::MainSubtitle)
-            .map(|data| format!(". {}", data.text))
+            .map(|data| format!(". {}", data.to_titlecase()))
             .unwrap_or_default();
 
         self.builder.push(
-            Inline::Italic(titlecase(&format!("{}{}", title.text, subtitle))),
+            Inline::Italic(format!("{}{}", title.to_titlecase(), subtitle)),
             Punct::Dot,
             Punct::Dot,
         );
@@ -265,11 +262,11 @@ impl Driver {
         let subtitle = entry
             .text
             .remove(&TextField::BookSubtitle)
-            .map(|data| format!(". {}", data.text))
+            .map(|data| format!(". {}", data.to_titlecase()))
             .unwrap_or_default();
 
         self.builder.push(
-            Inline::Italic(titlecase(&format!("{}{}", title.text, subtitle))),
+            Inline::Italic(format!("{}{}", title.to_titlecase(), subtitle)),
             Punct::Dot,
             Punct::Dot,
         );
@@ -286,11 +283,11 @@ impl Driver {
         let subtitle = entry
             .text
             .remove(&TextField::IssueSubtitle)
-            .map(|data| format!(" {}", data.text))
+            .map(|data| format!(" {}", data.to_titlecase()))
             .unwrap_or_default();
 
         self.builder.push(
-            Inline::Italic(titlecase(&format!("{}{}", issue.text, subtitle))),
+            Inline::Italic(format!("{}{}", issue.to_titlecase(), subtitle)),
             Punct::Colon,
             Punct::Space,
         );
@@ -602,10 +599,10 @@ impl Driver {
         let subtitle = entry
             .text
             .remove(&TextField::JournalSubtitle)
-            .map_or(String::new(), |data| format!(" {}", data.text));
+            .map_or(String::new(), |data| format!(" {}", data.to_titlecase()));
 
         self.builder.push(
-            Inline::Italic(titlecase(&format!("{}{}", title.text, subtitle))),
+            Inline::Italic(format!("{}{}", title.to_titlecase(), subtitle)),
             Punct::Dot,
             Punct::Space,
         );
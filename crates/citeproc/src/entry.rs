@@ -1,11 +1,17 @@
+use chrono::NaiveDate;
+use human_name::Name;
 use rustc_hash::FxHashMap;
-use syntax::bibtex::{Entry, Field, HasName, HasType, HasValue, Value};
+use syntax::{
+    bibtex::{Entry, Field, HasName, HasType, HasValue, Root, Value},
+    csl,
+};
 
 use super::field::{
     author::{AuthorField, AuthorFieldData},
     date::{DateField, DateFieldData},
     number::{NumberField, NumberFieldData},
     text::{TextField, TextFieldData},
+    FieldError,
 };
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
@@ -87,6 +93,28 @@ impl EntryKind {
             _ => Self::Unknown,
         }
     }
+
+    /// Maps the CSL item types to the closest biblatex entry type.
+    pub fn from_csl(input: &str) -> Self {
+        match input {
+            "article" | "article-journal" | "article-magazine" | "article-newspaper" => {
+                Self::Article
+            }
+            "book" => Self::Book,
+            "chapter" => Self::InCollection,
+            "paper-conference" => Self::InProceedings,
+            "entry" | "entry-dictionary" | "entry-encyclopedia" => Self::InReference,
+            "webpage" | "post" | "post-weblog" => Self::Online,
+            "dataset" => Self::DataSet,
+            "pamphlet" => Self::Booklet,
+            "patent" => Self::Patent,
+            "periodical" => Self::Periodical,
+            "report" => Self::Report,
+            "software" => Self::Software,
+            "thesis" => Self::Thesis,
+            _ => Self::Misc,
+        }
+    }
 }
 
 impl Default for EntryKind {
@@ -104,8 +132,117 @@ pub struct EntryData {
     pub number: FxHashMap<NumberField, NumberFieldData>,
 }
 
-impl From<&Entry> for EntryData {
-    fn from(entry: &Entry) -> Self {
+impl From<&csl::Item> for EntryData {
+    fn from(item: &csl::Item) -> Self {
+        let mut data = EntryData {
+            kind: EntryKind::from_csl(&item.kind),
+            ..EntryData::default()
+        };
+
+        for (name, text) in &item.text {
+            let field = match name.as_str() {
+                "container-title" if data.kind == EntryKind::Article => TextField::Journal,
+                "container-title" => TextField::BookTitle,
+                "collection-title" => TextField::Series,
+                "publisher-place" => TextField::Location,
+                "event-title" | "event" => TextField::EventTitle,
+                "event-place" => TextField::Venue,
+                "genre" => TextField::Type,
+                "status" => TextField::Pubstate,
+                "chapter-number" => TextField::Chapter,
+                "medium" => TextField::HowPublished,
+                "volume" | "number-of-volumes" | "edition" | "page" | "number-of-pages"
+                | "part-number" | "number" => {
+                    let field = match name.as_str() {
+                        "volume" => NumberField::Volume,
+                        "number-of-volumes" => NumberField::Volumes,
+                        "edition" => NumberField::Edition,
+                        "page" => NumberField::Pages,
+                        "number-of-pages" => NumberField::PageTotal,
+                        "part-number" => NumberField::Part,
+                        _ => NumberField::Number,
+                    };
+
+                    let number = NumberFieldData::parse_text(text.clone());
+                    data.number.insert(field, number);
+                    continue;
+                }
+                name => TextField::parse(name).unwrap_or(TextField::Unknown),
+            };
+
+            if field != TextField::Unknown {
+                let text = TextFieldData {
+                    text: text.clone(),
+                    protected: Vec::new(),
+                };
+
+                data.text.insert(field, text);
+            }
+        }
+
+        for (name, names) in &item.names {
+            let field = match name.as_str() {
+                "author" => AuthorField::Author,
+                "editor" => AuthorField::Editor,
+                "translator" => AuthorField::Translator,
+                "commenter" => AuthorField::Commentator,
+                _ => continue,
+            };
+
+            let authors = names
+                .iter()
+                .filter_map(|name| match &name.given {
+                    Some(given) => Name::parse(&format!("{given} {}", name.family)),
+                    None => Name::parse(&name.family),
+                })
+                .collect();
+
+            data.author.insert(field, AuthorFieldData { authors });
+        }
+
+        for (name, date) in &item.dates {
+            let (field, date) = match date {
+                csl::Date::Parts {
+                    year,
+                    month: None,
+                    day: None,
+                } => (DateField::Year, DateFieldData::Year(*year)),
+                csl::Date::Parts {
+                    year,
+                    month: Some(month),
+                    day,
+                } => {
+                    let date = day
+                        .and_then(|day| NaiveDate::from_ymd_opt(*year, *month, day))
+                        .map_or_else(
+                            || DateFieldData::Other(format!("{year}-{month:02}")),
+                            DateFieldData::Date,
+                        );
+
+                    (DateField::Date, date)
+                }
+                csl::Date::Parts { year, .. } => (DateField::Year, DateFieldData::Year(*year)),
+                csl::Date::Literal(text) => (DateField::Date, DateFieldData::Other(text.clone())),
+            };
+
+            let field = match name.as_str() {
+                "issued" => field,
+                "accessed" => DateField::UrlDate,
+                "event-date" => DateField::EventDate,
+                _ => continue,
+            };
+
+            data.date.insert(field, date);
+        }
+
+        data
+    }
+}
+
+impl EntryData {
+    /// Parses the fields of an entry. String references that are not defined in the document
+    /// of the entry are looked up in the `bibliography`.
+    pub fn parse(entry: &Entry, bibliography: &[Root]) -> Self {
         let mut data = EntryData {
             kind: entry.type_token().map_or(EntryKind::Unknown, |token| {
                 EntryKind::parse(&token.text()[1..])
@@ -114,49 +251,77 @@ impl From<&Entry> for EntryData {
         };
 
         for field in entry.fields() {
-            let _ = data.parse_field(&field);
+            let _ = data.parse_field(&field, bibliography);
         }
 
         data
     }
-}
 
-impl EntryData {
-    fn parse_field(&mut self, field: &Field) -> Option<()> {
-        let name = field.name_token()?;
+    /// Parses a field and adds it to the entry. A value that is not in the format expected
+    /// by biblatex is added nevertheless but reported as an error.
+    pub fn parse_field(&mut self, field: &Field, bibliography: &[Root]) -> Result<(), FieldError> {
+        let Some(name) = field.name_token() else { return Ok(()) };
         let name = name.text();
-        let value = field.value()?;
-        self.parse_author_field(name, &value)
-            .or_else(|| self.parse_date_field(name, &value))
-            .or_else(|| self.parse_number_field(name, &value))
-            .or_else(|| self.parse_text_field(name, &value))
+        let Some(value) = field.value() else { return Ok(()) };
+        self.parse_author_field(name, &value, bibliography)
+            .map(Ok)
+            .or_else(|| self.parse_date_field(name, &value, bibliography))
+            .or_else(|| self.parse_number_field(name, &value, bibliography))
+            .or_else(|| self.parse_text_field(name, &value, bibliography))
+            .unwrap_or(Ok(()))
     }
 
-    fn parse_author_field(&mut self, name: &str, value: &Value) -> Option<()> {
+    fn parse_author_field(
+        &mut self,
+        name: &str,
+        value: &Value,
+        bibliography: &[Root],
+    ) -> Option<()> {
         let name = AuthorField::parse(name)?;
-        let data = AuthorFieldData::parse(value)?;
+        let data = AuthorFieldData::parse_with(value, bibliography)?;
         self.author.insert(name, data);
         Some(())
     }
 
-    fn parse_date_field(&mut self, name: &str, value: &Value) -> Option<()> {
+    fn parse_date_field(
+        &mut self,
+        name: &str,
+        value: &Value,
+        bibliography: &[Root],
+    ) -> Option<Result<(), FieldError>> {
         let name = DateField::parse(name)?;
-        let data = DateFieldData::parse(value)?;
+        let data = DateFieldData::parse_with(value, bibliography)?;
         self.date.insert(name, data);
-        Some(())
+        Some(name.check(value))
     }
 
-    fn parse_number_field(&mut self, name: &str, value: &Value) -> Option<()> {
+    fn parse_number_field(
+        &mut self,
+        name: &str,
+        value: &Value,
+        bibliography: &[Root],
+    ) -> Option<Result<(), FieldError>> {
         let name = NumberField::parse(name)?;
-        let data = NumberFieldData::parse(value)?;
+        let data = NumberFieldData::parse_with(value, bibliography)?;
         self.number.insert(name, data);
-        Some(())
+        Some(name.check(value))
     }
 
-    fn parse_text_field(&mut self, name: &str, value: &Value) -> Option<()> {
+    fn parse_text_field(
+        &mut self,
+        name: &str,
+        value: &Value,
+        bibliography: &[Root],
+    ) -> Option<Result<(), FieldError>> {
         let name = TextField::parse(name).unwrap_or(TextField::Unknown);
-        let data = TextFieldData::parse(value)?;
+        let data = match name {
+            TextField::Doi | TextField::Eprint | TextField::Url => {
+                TextFieldData::parse_verbatim_with(value, bibliography)?
+            }
+            _ => TextFieldData::parse_with(value, bibliography)?,
+        };
+
         self.text.insert(name, data);
-        Some(())
+        Some(name.check(value))
     }
 }
@@ -2,3 +2,80 @@ pub mod author;
 pub mod date;
 pub mod number;
 pub mod text;
+
+use rowan::{ast::AstNode, TextRange};
+use syntax::bibtex::{self, HasInteger};
+
+/// A field value that can be rendered but is not in the format expected by biblatex.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct FieldError {
+    /// The range of the value without its delimiters.
+    pub range: TextRange,
+    pub kind: FieldErrorKind,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum FieldErrorKind {
+    InvalidDate,
+    /// A month that is not a number, together with the number of the month if it is spelled out.
+    InvalidMonth(Option<u32>),
+    /// Page ranges that do not use the `--` separator, together with the corrected ranges.
+    InvalidPageRange(String),
+    InvalidIsbn,
+    /// A DOI with a resolver URL or a `doi:` prefix, together with the DOI without the prefix.
+    DoiWithPrefix(String),
+    InvalidUrl,
+}
+
+/// Applies `check` to the trimmed content of a field value that consists of a single group
+/// without markup or a number. String references, concatenations and empty values are accepted.
+fn check_content(
+    value: &bibtex::Value,
+    check: impl FnOnce(&str) -> Option<FieldErrorKind>,
+) -> Result<(), FieldError> {
+    let Some((range, text)) = value_content(value) else { return Ok(()) };
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(());
+    }
+
+    match check(text) {
+        Some(kind) => Err(FieldError { range, kind }),
+        None => Ok(()),
+    }
+}
+
+fn value_content(value: &bibtex::Value) -> Option<(TextRange, String)> {
+    let tokens = match value {
+        bibtex::Value::Literal(literal) => {
+            let token = literal.integer_token()?;
+            return Some((token.text_range(), token.text().into()));
+        }
+        bibtex::Value::CurlyGroup(_) | bibtex::Value::QuoteGroup(_) => value
+            .syntax()
+            .children_with_tokens()
+            .map(|element| element.into_token())
+            .collect::<Option<Vec<_>>>()?,
+        bibtex::Value::Join(_) | bibtex::Value::Accent(_) | bibtex::Value::Command(_) => {
+            return None;
+        }
+    };
+
+    let (left, rest) = tokens.split_first()?;
+    let (right, inner) = rest.split_last()?;
+    let expected = match left.kind() {
+        bibtex::L_CURLY => bibtex::R_CURLY,
+        kind => kind,
+    };
+
+    if right.kind() != expected {
+        return None;
+    }
+
+    let range = TextRange::new(left.text_range().end(), right.text_range().start());
+    let text = inner.iter().map(|token| token.text()).collect();
+    Some((range, text))
+}
+
+#[cfg(test)]
+mod tests;
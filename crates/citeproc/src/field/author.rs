@@ -2,7 +2,7 @@ use std::{borrow::Cow, fmt};
 
 use human_name::Name;
 use itertools::Itertools;
-use syntax::bibtex::Value;
+use syntax::bibtex::{Root, Value};
 
 use super::text::TextFieldData;
 
@@ -59,7 +59,12 @@ impl fmt::Display for AuthorFieldData {
 
 impl AuthorFieldData {
     pub fn parse(value: &Value) -> Option<Self> {
-        let TextFieldData { text } = TextFieldData::parse(value)?;
+        Self::parse_with(value, &[])
+    }
+
+    /// Parses a field value and looks up string references like [`TextFieldData::parse_with`].
+    pub fn parse_with(value: &Value, bibliography: &[Root]) -> Option<Self> {
+        let TextFieldData { text, .. } = TextFieldData::parse_with(value, bibliography)?;
         let mut authors = Vec::new();
         let mut words = Vec::new();
         for word in text.split_whitespace() {
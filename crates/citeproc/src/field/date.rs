@@ -1,15 +1,16 @@
 use std::{fmt, ops::Add, str::FromStr};
 
-use chrono::{Datelike, Month, NaiveDate};
-use syntax::bibtex::Value;
+use chrono::{Datelike, Month, NaiveDate, NaiveTime};
+use syntax::bibtex::{Root, Value};
 
-use super::text::TextFieldData;
+use super::{check_content, text::TextFieldData, FieldError, FieldErrorKind};
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
 pub enum DateField {
     Date,
     EventDate,
     Month,
+    OrigDate,
     UrlDate,
     Year,
 }
@@ -20,11 +21,27 @@ impl DateField {
             "date" => Self::Date,
             "eventdate" => Self::EventDate,
             "month" => Self::Month,
+            "origdate" => Self::OrigDate,
             "urldate" => Self::UrlDate,
             "year" => Self::Year,
             _ => return None,
         })
     }
+
+    /// Checks that a month is a number and that a date uses the Extended Date/Time Format.
+    pub(crate) fn check(self, value: &Value) -> Result<(), FieldError> {
+        check_content(value, |text| match self {
+            Self::Month => match text.parse::<u32>() {
+                Ok(1..=12) => None,
+                Ok(_) => Some(FieldErrorKind::InvalidMonth(None)),
+                Err(_) => Some(FieldErrorKind::InvalidMonth(month_number(text))),
+            },
+            Self::Date | Self::EventDate | Self::OrigDate | Self::UrlDate => {
+                (!is_edtf(text)).then_some(FieldErrorKind::InvalidDate)
+            }
+            Self::Year => None,
+        })
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
@@ -74,7 +91,12 @@ impl fmt::Display for DateFieldData {
 
 impl DateFieldData {
     pub fn parse(value: &Value) -> Option<Self> {
-        let TextFieldData { text } = TextFieldData::parse(value)?;
+        Self::parse_with(value, &[])
+    }
+
+    /// Parses a field value and looks up string references like [`TextFieldData::parse_with`].
+    pub fn parse_with(value: &Value, bibliography: &[Root]) -> Option<Self> {
+        let TextFieldData { text, .. } = TextFieldData::parse_with(value, bibliography)?;
         NaiveDate::from_str(&text)
             .ok()
             .map(Self::Date)
@@ -83,3 +105,118 @@ impl DateFieldData {
             .or(Some(Self::Other(text)))
     }
 }
+
+/// The standard month macros of BibTeX together with the names of the months.
+pub const MONTH_MACROS: [(&str, &str); 12] = [
+    ("jan", "January"),
+    ("feb", "February"),
+    ("mar", "March"),
+    ("apr", "April"),
+    ("may", "May"),
+    ("jun", "June"),
+    ("jul", "July"),
+    ("aug", "August"),
+    ("sep", "September"),
+    ("oct", "October"),
+    ("nov", "November"),
+    ("dec", "December"),
+];
+
+/// Expands one of the month macros `jan` to `dec` to the name of the month.
+pub fn month_macro(name: &str) -> Option<&'static str> {
+    MONTH_MACROS
+        .iter()
+        .find(|(abbreviation, _)| abbreviation.eq_ignore_ascii_case(name))
+        .map(|(_, month)| *month)
+}
+
+/// Returns the number of a month that is given by its English name or abbreviation.
+pub fn month_number(name: &str) -> Option<u32> {
+    name.trim()
+        .parse::<Month>()
+        .ok()
+        .map(|month| month.number_from_month())
+}
+
+/// Checks whether the input is a date or a date range in the Extended Date/Time Format
+/// as supported by biblatex, e.g. `2004-04-05`, `2004-22`, `199X` or `1850/1855-02~`.
+pub fn is_edtf(input: &str) -> bool {
+    let Some((start, end)) = input.split_once('/') else { return is_edtf_date(input) };
+    let is_open = |date: &str| date.is_empty() || date == "..";
+    match (is_open(start), is_open(end)) {
+        (true, true) => false,
+        (true, false) => is_edtf_date(end),
+        (false, true) => is_edtf_date(start),
+        (false, false) => is_edtf_date(start) && is_edtf_date(end),
+    }
+}
+
+fn is_edtf_date(input: &str) -> bool {
+    let input = input.trim_end_matches(['?', '~', '%']);
+    let (date, time) = match input.split_once('T') {
+        Some((date, time)) => (date, Some(time)),
+        None => (input, None),
+    };
+
+    if !time.map_or(true, is_edtf_time) {
+        return false;
+    }
+
+    let date = date.strip_prefix('-').unwrap_or(date);
+    if let Some(year) = date.strip_prefix('Y') {
+        let year = year.strip_prefix('-').unwrap_or(year);
+        return year.len() > 4 && year.bytes().all(|c| c.is_ascii_digit());
+    }
+
+    let mut parts = date.split('-');
+    let year = parts.next().unwrap_or_default();
+    let month = parts.next();
+    let day = parts.next();
+    if parts.next().is_some() {
+        return false;
+    }
+
+    let digits = year.trim_end_matches('X');
+    if year.len() != 4 || !digits.bytes().all(|c| c.is_ascii_digit()) {
+        return false;
+    }
+
+    let Some(month) = month else { return true };
+    let is_unspecified = |part: &str| part == "XX";
+    let Some(month_number) = parse_two_digits(month) else {
+        return is_unspecified(month) && day.map_or(true, is_unspecified);
+    };
+
+    // The months 21 to 24 denote the seasons and cannot be combined with a day.
+    let is_month = (1..=12).contains(&month_number);
+    let Some(day) = day else { return is_month || (21..=24).contains(&month_number) };
+    let Some(day_number) = parse_two_digits(day) else { return is_month && is_unspecified(day) };
+
+    match year.parse() {
+        Ok(year) => NaiveDate::from_ymd_opt(year, month_number, day_number).is_some(),
+        Err(_) => is_month && (1..=31).contains(&day_number),
+    }
+}
+
+fn is_edtf_time(input: &str) -> bool {
+    let (time, offset) = input.split_at(input.len().min(8));
+    if NaiveTime::parse_from_str(time, "%H:%M:%S").is_err() {
+        return false;
+    }
+
+    match offset.strip_prefix(['+', '-']) {
+        Some(offset) => {
+            NaiveTime::parse_from_str(offset, "%H:%M").is_ok()
+                || parse_two_digits(offset).map_or(false, |hours| hours < 24)
+        }
+        None => offset.is_empty() || offset == "Z",
+    }
+}
+
+fn parse_two_digits(input: &str) -> Option<u32> {
+    if input.len() == 2 && input.bytes().all(|c| c.is_ascii_digit()) {
+        input.parse().ok()
+    } else {
+        None
+    }
+}
@@ -1,8 +1,8 @@
 use std::fmt;
 
-use syntax::bibtex::Value;
+use syntax::bibtex::{Root, Value};
 
-use super::text::TextFieldData;
+use super::{check_content, text::TextFieldData, FieldError, FieldErrorKind};
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
 pub enum NumberField {
@@ -28,6 +28,14 @@ impl NumberField {
             _ => return None,
         })
     }
+
+    /// Checks that page ranges use the `--` separator.
+    pub(crate) fn check(self, value: &Value) -> Result<(), FieldError> {
+        check_content(value, |text| match self {
+            Self::Pages => normalize_page_ranges(text).map(FieldErrorKind::InvalidPageRange),
+            _ => None,
+        })
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Hash)]
@@ -49,12 +57,56 @@ impl fmt::Display for NumberFieldData {
 
 impl NumberFieldData {
     pub fn parse(value: &Value) -> Option<Self> {
-        let TextFieldData { text } = TextFieldData::parse(value)?;
-        text.split_once("--")
-            .or_else(|| text.split_once('-'))
+        Self::parse_with(value, &[])
+    }
+
+    /// Parses a field value and looks up string references like [`TextFieldData::parse_with`].
+    pub fn parse_with(value: &Value, bibliography: &[Root]) -> Option<Self> {
+        let TextFieldData { text, .. } = TextFieldData::parse_with(value, bibliography)?;
+        Some(Self::parse_text(text))
+    }
+
+    pub fn parse_text(text: String) -> Self {
+        text.split_once(['-', '\u{2013}'])
             .and_then(|(a, b)| Some((a.parse().ok()?, b.parse().ok()?)))
             .map(|(a, b)| Self::Range(a, b))
             .or_else(|| text.parse().ok().map(Self::Scalar))
-            .or(Some(Self::Other(text)))
+            .unwrap_or(Self::Other(text))
     }
 }
+
+/// Rewrites page ranges like `12-15` or `12–15` to use the `--` separator of BibTeX.
+/// Multiple ranges are separated by commas. Returns `None` if the input does not
+/// contain any malformed ranges.
+pub fn normalize_page_ranges(input: &str) -> Option<String> {
+    let is_dash = |c: char| matches!(c, '-' | '\u{2013}' | '\u{2014}');
+    let mut changed = false;
+    let mut parts = Vec::new();
+    for part in input.split(',') {
+        let range = part
+            .find(is_dash)
+            .map(|start| (start, part[start..].find(|c| !is_dash(c))))
+            .and_then(|(start, end)| Some((start, start + end?)));
+
+        let Some((start, end)) = range else {
+            parts.push(part.to_string());
+            continue;
+        };
+
+        let first = &part[..start];
+        let last = &part[end..];
+        if &part[start..end] == "--"
+            || first.trim().is_empty()
+            || last.trim().is_empty()
+            || last.contains(is_dash)
+        {
+            parts.push(part.to_string());
+            continue;
+        }
+
+        changed = true;
+        parts.push(format!("{}--{}", first.trim_end(), last.trim_start()));
+    }
+
+    changed.then(|| parts.join(","))
+}
@@ -0,0 +1,148 @@
+use parser::parse_bibtex;
+use rowan::ast::AstNode;
+use syntax::bibtex::{self, HasName};
+
+use super::{
+    date::{is_edtf, month_number},
+    number::normalize_page_ranges,
+    text::{is_valid_isbn, strip_doi_prefix},
+    FieldErrorKind,
+};
+
+/// Checks every field of the first entry and returns the names of the invalid fields.
+fn check_fields(input: &str) -> Vec<(String, FieldErrorKind)> {
+    let root = bibtex::Root::cast(bibtex::SyntaxNode::new_root(parse_bibtex(input))).unwrap();
+    let entry = root.entries().next().unwrap();
+    entry
+        .fields()
+        .filter_map(|field| {
+            let error = crate::check_field(&field).err()?;
+            Some((field.name_token()?.text().into(), error.kind))
+        })
+        .collect()
+}
+
+#[test]
+fn edtf_dates() {
+    for input in [
+        "2004",
+        "2004-04",
+        "2004-04-05",
+        "2004-22",
+        "-0050",
+        "199X",
+        "19XX-XX",
+        "2004-04-05T14:34:00Z",
+        "2004-04-05T14:34:00+02:00",
+        "2004?",
+        "2004-04~",
+        "1850/1855-02",
+        "1850/",
+        "../1855",
+        "Y170000002",
+    ] {
+        assert!(is_edtf(input), "{input}");
+    }
+
+    for input in [
+        "",
+        "04/05/2004",
+        "2004-13",
+        "2004-02-30",
+        "2004-22-01",
+        "20",
+        "2004-04-05T25:00:00",
+        "/",
+        "April 2004",
+    ] {
+        assert!(!is_edtf(input), "{input}");
+    }
+}
+
+#[test]
+fn month_names() {
+    assert_eq!(month_number("January"), Some(1));
+    assert_eq!(month_number("sep"), Some(9));
+    assert_eq!(month_number("Spring"), None);
+}
+
+#[test]
+fn page_ranges() {
+    assert_eq!(normalize_page_ranges("12-15").as_deref(), Some("12--15"));
+    assert_eq!(
+        normalize_page_ranges("12 \u{2013} 15").as_deref(),
+        Some("12--15")
+    );
+    assert_eq!(
+        normalize_page_ranges("1-4, 7---9").as_deref(),
+        Some("1--4, 7--9")
+    );
+    assert_eq!(normalize_page_ranges("12--15"), None);
+    assert_eq!(normalize_page_ranges("12"), None);
+    assert_eq!(normalize_page_ranges("12-"), None);
+}
+
+#[test]
+fn isbn_checksums() {
+    assert!(is_valid_isbn("0-201-13447-0"));
+    assert!(is_valid_isbn("978-0-201-13447-6"));
+    assert!(is_valid_isbn("0-8044-2957-X"));
+    assert!(!is_valid_isbn("0-201-13447-1"));
+    assert!(!is_valid_isbn("978-0-201-13447-7"));
+    assert!(!is_valid_isbn("978-0-201"));
+    assert!(!is_valid_isbn("X-201-13447-0"));
+}
+
+#[test]
+fn doi_prefixes() {
+    assert_eq!(
+        strip_doi_prefix("https://doi.org/10.1000/182"),
+        Some("10.1000/182")
+    );
+    assert_eq!(strip_doi_prefix("DOI: 10.1000/182"), Some("10.1000/182"));
+    assert_eq!(strip_doi_prefix("10.1000/182"), None);
+}
+
+#[test]
+fn field_errors() {
+    let errors = check_fields(
+        r#"
+@book{foo,
+    author = {Foo Bar},
+    date = {04/05/2004},
+    origdate = {2004-04-05},
+    month = {March},
+    pages = {12-15},
+    isbn = {0-201-13447-1},
+    doi = {https://doi.org/10.1000/182},
+    url = {example.org},
+    title = {12-15},
+}"#,
+    );
+
+    assert_eq!(
+        errors,
+        vec![
+            ("date".into(), FieldErrorKind::InvalidDate),
+            ("month".into(), FieldErrorKind::InvalidMonth(Some(3))),
+            (
+                "pages".into(),
+                FieldErrorKind::InvalidPageRange("12--15".into())
+            ),
+            ("isbn".into(), FieldErrorKind::InvalidIsbn),
+            (
+                "doi".into(),
+                FieldErrorKind::DoiWithPrefix("10.1000/182".into())
+            ),
+            ("url".into(), FieldErrorKind::InvalidUrl),
+        ]
+    );
+}
+
+#[test]
+fn field_errors_string_reference() {
+    assert_eq!(
+        check_fields("@book{foo, month = mar, doi = prefix # {10.1000/182}}"),
+        vec![]
+    );
+}
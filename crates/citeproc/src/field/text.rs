@@ -1,10 +1,17 @@
+use std::ops::Range;
+
 use rowan::{ast::AstNode, NodeOrToken};
 use rustc_hash::FxHashSet;
 
 use syntax::bibtex::{
-    Accent, Command, CurlyGroup, HasAccentName, HasCommandName, HasName, HasValue, HasWord, Join,
-    Literal, QuoteGroup, Root, SyntaxKind::*, SyntaxToken, Value,
+    HasName, HasValue, Join, Literal, Root, SyntaxKind, SyntaxKind::*, SyntaxNode, SyntaxToken,
+    Value,
 };
+use titlecase::titlecase;
+
+use crate::unicode::{self, UnicodeText};
+
+use super::{check_content, date::month_macro, FieldError, FieldErrorKind};
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
 pub enum TextField {
@@ -110,47 +117,114 @@ impl TextField {
             _ => return None,
         })
     }
+
+    /// Checks the ISBN check digit, that a URL can be parsed and that a DOI has no prefix.
+    pub(crate) fn check(self, value: &Value) -> Result<(), FieldError> {
+        check_content(value, |text| match self {
+            Self::Isbn => (!is_valid_isbn(text)).then_some(FieldErrorKind::InvalidIsbn),
+            Self::Url => url::Url::parse(text)
+                .is_err()
+                .then_some(FieldErrorKind::InvalidUrl),
+            Self::Doi => {
+                strip_doi_prefix(text).map(|doi| FieldErrorKind::DoiWithPrefix(doi.into()))
+            }
+            _ => None,
+        })
+    }
 }
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Hash, Default)]
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Default)]
 pub struct TextFieldData {
     pub text: String,
+    pub protected: Vec<Range<usize>>,
 }
 
 impl TextFieldData {
+    /// Parses a field value and converts its LaTeX markup to Unicode.
     pub fn parse(value: &Value) -> Option<Self> {
-        let mut builder = TextFieldDataBuilder::default();
-        builder.visit_value(value)?;
-        Some(builder.data)
+        Self::parse_with(value, &[])
+    }
+
+    /// Parses a field value and converts its LaTeX markup to Unicode. String references
+    /// that are not defined in the document of the value are looked up in the `bibliography`.
+    pub fn parse_with(value: &Value, bibliography: &[Root]) -> Option<Self> {
+        let source = TextFieldSource::parse(value, bibliography)?;
+        let UnicodeText { text, protected } = unicode::convert(&source);
+        Some(Self { text, protected })
+    }
+
+    /// Parses a field value like a URL or a DOI without interpreting LaTeX markup.
+    pub fn parse_verbatim(value: &Value) -> Option<Self> {
+        Self::parse_verbatim_with(value, &[])
+    }
+
+    /// Parses a field value like [`TextFieldData::parse_verbatim`] and looks up
+    /// string references like [`TextFieldData::parse_with`].
+    pub fn parse_verbatim_with(value: &Value, bibliography: &[Root]) -> Option<Self> {
+        let source = TextFieldSource::parse(value, bibliography)?;
+        Some(Self {
+            text: unicode::unescape(&source),
+            protected: Vec::new(),
+        })
+    }
+
+    /// Converts the text to title case while keeping the protected characters unchanged.
+    pub fn to_titlecase(&self) -> String {
+        let output = titlecase(&self.text);
+        if self.protected.is_empty() || output.chars().count() != self.text.chars().count() {
+            return output;
+        }
+
+        self.text
+            .char_indices()
+            .zip(output.chars())
+            .map(|((offset, original), cased)| {
+                if self.protected.iter().any(|range| range.contains(&offset)) {
+                    original
+                } else {
+                    cased
+                }
+            })
+            .collect()
     }
 }
 
-#[derive(Default)]
-struct TextFieldDataBuilder {
-    data: TextFieldData,
+/// Collects the LaTeX source of a field value. String references are expanded
+/// and the delimiters of the value are removed.
+struct TextFieldSource<'a> {
+    text: String,
     string_stack: FxHashSet<String>,
+    bibliography: &'a [Root],
 }
 
-impl TextFieldDataBuilder {
+impl<'a> TextFieldSource<'a> {
+    fn parse(value: &Value, bibliography: &'a [Root]) -> Option<String> {
+        let mut builder = Self {
+            text: String::new(),
+            string_stack: FxHashSet::default(),
+            bibliography,
+        };
+
+        builder.visit_value(value)?;
+        Some(builder.text)
+    }
+
     fn visit_value(&mut self, value: &Value) -> Option<()> {
         match value {
             Value::Literal(lit) => {
                 self.visit_literal(lit);
             }
             Value::CurlyGroup(group) => {
-                self.visit_curly_group(group)?;
+                self.visit_group(group.syntax(), L_CURLY, R_CURLY);
             }
             Value::QuoteGroup(group) => {
-                self.visit_quote_group(group)?;
+                self.visit_group(group.syntax(), QUOTE, QUOTE);
             }
             Value::Join(join) => {
                 self.visit_join(join)?;
             }
-            Value::Accent(accent) => {
-                let _ = self.visit_accent(accent);
-            }
-            Value::Command(command) => {
-                let _ = self.visit_command(command);
+            Value::Accent(_) | Value::Command(_) => {
+                self.push_node(value.syntax());
             }
         };
 
@@ -163,9 +237,7 @@ impl TextFieldDataBuilder {
             .and_then(|name| self.visit_string_reference(&name))
             .is_none()
         {
-            lit.syntax()
-                .text()
-                .for_each_chunk(|text| self.data.text.push_str(text));
+            self.push_node(lit.syntax());
         }
     }
 
@@ -173,14 +245,10 @@ impl TextFieldDataBuilder {
         let root = Root::cast(name.parent_ancestors().last()?)?;
         let name = name.text();
 
-        let value = root
-            .strings()
-            .filter(|string| {
-                string
-                    .name_token()
-                    .map_or(false, |token| token.text() == name)
-            })
-            .find_map(|string| string.value())?;
+        let Some(value) = find_string(&root, self.bibliography, name) else {
+            self.text.push_str(month_macro(name)?);
+            return Some(());
+        };
 
         if !self.string_stack.insert(name.to_string()) {
             return None;
@@ -191,42 +259,14 @@ impl TextFieldDataBuilder {
         Some(())
     }
 
-    fn visit_curly_group(&mut self, group: &CurlyGroup) -> Option<()> {
-        for child in group.syntax().children_with_tokens() {
-            match child {
-                NodeOrToken::Node(node) => {
-                    self.visit_value(&Value::cast(node)?)?;
-                }
-                NodeOrToken::Token(token) => {
-                    match token.kind() {
-                        L_CURLY | R_CURLY => (),
-                        WHITESPACE | NBSP => self.data.text.push(' '),
-                        _ => self.data.text.push_str(token.text()),
-                    };
-                }
-            };
-        }
-
-        Some(())
-    }
-
-    fn visit_quote_group(&mut self, group: &QuoteGroup) -> Option<()> {
-        for child in group.syntax().children_with_tokens() {
+    fn visit_group(&mut self, group: &SyntaxNode, left: SyntaxKind, right: SyntaxKind) {
+        for child in group.children_with_tokens() {
             match child {
-                NodeOrToken::Node(node) => {
-                    self.visit_value(&Value::cast(node)?)?;
-                }
-                NodeOrToken::Token(token) => {
-                    match token.kind() {
-                        QUOTE => (),
-                        WHITESPACE | NBSP => self.data.text.push(' '),
-                        _ => self.data.text.push_str(token.text()),
-                    };
-                }
+                NodeOrToken::Node(node) => self.push_node(&node),
+                NodeOrToken::Token(token) if token.kind() == left || token.kind() == right => (),
+                NodeOrToken::Token(token) => self.text.push_str(token.text()),
             };
         }
-
-        Some(())
     }
 
     fn visit_join(&mut self, join: &Join) -> Option<()> {
@@ -241,66 +281,82 @@ impl TextFieldDataBuilder {
         Some(())
     }
 
-    fn visit_accent(&mut self, accent: &Accent) -> Option<()> {
-        let name = accent.accent_name_token()?;
-        let word = accent.word_token()?;
-
-        let mut chars = word.text().chars();
-        let a = chars.next()?;
-
-        if chars.next().is_some() {
-            self.data.text.push_str(word.text());
-        } else {
-            let b = match name.text() {
-                r#"\`"# => '\u{0300}',
-                r#"\'"# => '\u{0301}',
-                r#"\^"# => '\u{0302}',
-                r#"\""# => '\u{0308}',
-                r#"\H"# => '\u{030B}',
-                r#"\~"# => '\u{0303}',
-
-                r#"\c"# => '\u{0327}',
-                r#"\k"# => '\u{0328}',
-                r#"\="# => '\u{0304}',
-                r#"\b"# => '\u{0331}',
-                r#"\."# => '\u{0307}',
-                r#"\d"# => '\u{0323}',
-                r#"\r"# => '\u{030A}',
-                r#"\u"# => '\u{0306}',
-                r#"\v"# => '\u{030C}',
-                _ => '\u{0000}',
-            };
+    fn push_node(&mut self, node: &SyntaxNode) {
+        node.text().for_each_chunk(|text| self.text.push_str(text));
+    }
+}
 
-            match unicode_normalization::char::compose(a, b) {
-                Some(c) => self.data.text.push(c),
-                None => self.data.text.push_str(word.text()),
-            };
-        }
+/// Parses the comma-separated entry keys of a field like `crossref` or `entryset`.
+pub fn parse_keys(value: &Value) -> Vec<String> {
+    TextFieldData::parse(value).map_or_else(Vec::new, |data| {
+        data.text
+            .split(',')
+            .map(str::trim)
+            .filter(|key| !key.is_empty())
+            .map(String::from)
+            .collect()
+    })
+}
 
-        Some(())
-    }
+/// Finds the value of the `@string` with the given name. The strings of `root` take precedence
+/// over the strings of the other documents of the `bibliography`.
+pub fn find_string(root: &Root, bibliography: &[Root], name: &str) -> Option<Value> {
+    std::iter::once(root)
+        .chain(bibliography)
+        .flat_map(|root| root.strings())
+        .filter(|string| {
+            string
+                .name_token()
+                .map_or(false, |token| token.text().eq_ignore_ascii_case(name))
+        })
+        .find_map(|string| string.value())
+}
 
-    fn visit_command(&mut self, command: &Command) -> Option<()> {
-        let name = command.command_name_token()?;
-        let replacement = match name.text() {
-            r#"\l"# => "\u{0142}",
-            r#"\o"# => "\u{00F8}",
-            r#"\i"# => "\u{0131}",
-            r#"\&"# => "&",
-            r#"\$"# => "$",
-            r#"\{"# => "{",
-            r#"\}"# => "}",
-            r#"\%"# => "%",
-            r#"\#"# => "#",
-            r#"\_"# => "_",
-            r#"\ "# | r#"\,"# | r#"\;"# => " ",
-            r#"\hyphen"# => "-",
-            r#"\TeX"# => "TeX",
-            r#"\LaTeX"# => "LaTeX",
-            text => text,
-        };
+/// Checks the length and the check digit of an ISBN-10 or an ISBN-13.
+/// Hyphens and spaces between the digits are ignored.
+pub fn is_valid_isbn(input: &str) -> bool {
+    let digits: Vec<_> = input
+        .chars()
+        .filter(|c| !matches!(c, '-' | ' '))
+        .enumerate()
+        .map(|(i, c)| match c {
+            'X' | 'x' if i == 9 => Some(10),
+            _ => c.to_digit(10),
+        })
+        .collect::<Option<_>>()
+        .unwrap_or_default();
 
-        self.data.text.push_str(replacement);
-        Some(())
+    match digits.len() {
+        10 => {
+            let sum: u32 = digits.iter().zip((1..=10).rev()).map(|(d, w)| d * w).sum();
+            sum % 11 == 0
+        }
+        13 => {
+            let sum: u32 = digits
+                .iter()
+                .zip([1, 3].iter().cycle())
+                .map(|(d, w)| d * w)
+                .sum();
+            digits.iter().all(|&d| d < 10) && sum % 10 == 0
+        }
+        _ => false,
     }
 }
+
+/// Removes a resolver URL like `https://doi.org/` or a `doi:` prefix from a DOI.
+/// Returns `None` if the DOI does not have a prefix.
+pub fn strip_doi_prefix(input: &str) -> Option<&str> {
+    const PREFIXES: [&str; 5] = [
+        "https://doi.org/",
+        "http://doi.org/",
+        "https://dx.doi.org/",
+        "http://dx.doi.org/",
+        "doi:",
+    ];
+
+    PREFIXES.iter().find_map(|prefix| {
+        let head = input.get(..prefix.len())?;
+        head.eq_ignore_ascii_case(prefix)
+            .then(|| input[prefix.len()..].trim_start())
+    })
+}
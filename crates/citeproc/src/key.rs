@@ -0,0 +1,237 @@
+use chrono::Datelike;
+use human_name::Name;
+use syntax::bibtex;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::{
+    crossref,
+    entry::EntryData,
+    field::{
+        author::AuthorField,
+        date::{DateField, DateFieldData},
+        number::{NumberField, NumberFieldData},
+        text::TextField,
+    },
+};
+
+/// The key pattern that is used if no pattern is configured.
+pub const DEFAULT_KEY_PATTERN: &str = "[auth:lower][year][shorttitle:1]";
+
+const STOP_WORDS: &[&str] = &[
+    "a", "about", "an", "and", "as", "at", "but", "by", "for", "from", "in", "into", "is", "nor",
+    "of", "on", "or", "over", "the", "to", "under", "via", "with",
+];
+
+/// Generates the citation key of an entry from a key pattern like `[auth:lower][year]`.
+///
+/// Text outside of brackets is copied into the key. The following markers are supported:
+///
+/// - `auth`: the last name of the first author (or editor)
+/// - `authors`: the last names of all authors
+/// - `authEtAl`: both last names for two authors and the first one followed by `EtAl` otherwise
+/// - `year` and `shortyear`: the year with four or two digits
+/// - `title`, `shorttitle` and `veryshorttitle`: all, three or one of the significant
+///   words of the title with their first letter capitalized
+/// - `firstpage`: the first page of the `pages` field
+/// - any other field name like `journal` or `volume`: the value of the field
+///
+/// A marker can be followed by modifiers: `lower` and `upper` change the case and a number
+/// keeps the first words of a title or the first characters of any other marker.
+/// Characters that are not allowed in keys are removed and accented letters are replaced by
+/// their ASCII base letter. Returns `None` if the pattern produces an empty key.
+#[must_use]
+pub fn generate_key(
+    entry: &bibtex::Entry,
+    bibliography: &[bibtex::Root],
+    pattern: &str,
+) -> Option<String> {
+    let data = crossref::resolve(entry, bibliography);
+    let mut key = String::new();
+    let mut rest = pattern;
+    while let Some(start) = rest.find('[') {
+        push_literal(&mut key, &rest[..start]);
+        let Some(end) = rest[start..].find(']') else {
+            rest = &rest[start..];
+            break;
+        };
+
+        key.push_str(&expand_marker(&data, &rest[start + 1..start + end]));
+        rest = &rest[start + end + 1..];
+    }
+
+    push_literal(&mut key, rest);
+    Some(key).filter(|key| !key.is_empty())
+}
+
+fn push_literal(key: &mut String, text: &str) {
+    key.extend(
+        text.chars().filter(|c| {
+            c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ':' | '.' | '+' | '/')
+        }),
+    );
+}
+
+fn expand_marker(data: &EntryData, marker: &str) -> String {
+    let mut parts = marker.split(':');
+    let name = parts.next().unwrap_or_default().trim();
+    let modifiers: Vec<_> = parts.map(str::trim).collect();
+    let limit = modifiers.iter().find_map(|modifier| modifier.parse().ok());
+
+    let value = match name {
+        "auth" => last_names(data).into_iter().next().unwrap_or_default(),
+        "authors" => last_names(data).concat(),
+        "authEtAl" => {
+            let names = last_names(data);
+            match names.len() {
+                0..=2 => names.concat(),
+                _ => format!("{}EtAl", names[0]),
+            }
+        }
+        "year" => year(data).map(|year| year.to_string()).unwrap_or_default(),
+        "shortyear" => year(data)
+            .map(|year| format!("{:02}", year.rem_euclid(100)))
+            .unwrap_or_default(),
+        "title" => title(data, false, limit),
+        "shorttitle" => title(data, true, limit.or(Some(3))),
+        "veryshorttitle" => title(data, true, limit.or(Some(1))),
+        "firstpage" => match data.number.get(&NumberField::Pages) {
+            Some(NumberFieldData::Scalar(page) | NumberFieldData::Range(page, _)) => {
+                page.to_string()
+            }
+            Some(NumberFieldData::Other(pages)) => {
+                let first = pages.split(['-', '\u{2013}', ',']).next();
+                sanitize(first.unwrap_or_default())
+            }
+            None => String::new(),
+        },
+        _ => field(data, name),
+    };
+
+    let mut value = if name.ends_with("title") {
+        value
+    } else if let Some(limit) = limit {
+        value.chars().take(limit).collect()
+    } else {
+        value
+    };
+
+    for modifier in &modifiers {
+        match *modifier {
+            "lower" => value = value.to_lowercase(),
+            "upper" => value = value.to_uppercase(),
+            _ => {}
+        }
+    }
+
+    value
+}
+
+fn last_names(data: &EntryData) -> Vec<String> {
+    let names = data
+        .author
+        .get(&AuthorField::Author)
+        .or_else(|| data.author.get(&AuthorField::Editor));
+
+    names
+        .into_iter()
+        .flat_map(|names| &names.authors)
+        .map(Name::surname)
+        .filter(|name| !name.eq_ignore_ascii_case("others"))
+        .map(|name| {
+            name.split_whitespace()
+                .last()
+                .map(sanitize)
+                .unwrap_or_default()
+        })
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+fn year(data: &EntryData) -> Option<i32> {
+    [DateField::Date, DateField::Year]
+        .iter()
+        .filter_map(|field| data.date.get(field))
+        .find_map(|date| match date {
+            DateFieldData::Date(date) => Some(date.year()),
+            DateFieldData::Year(year) => Some(*year),
+            DateFieldData::Month(_) => None,
+            DateFieldData::Other(text) => {
+                let digits: String = text
+                    .trim_start()
+                    .chars()
+                    .take_while(char::is_ascii_digit)
+                    .collect();
+
+                Some(digits)
+                    .filter(|digits| digits.len() == 4)
+                    .and_then(|digits| digits.parse().ok())
+            }
+        })
+}
+
+fn title(data: &EntryData, significant: bool, limit: Option<usize>) -> String {
+    let Some(title) = data.text.get(&TextField::Title) else { return String::new() };
+
+    title
+        .text
+        .split(|c: char| c.is_whitespace() || matches!(c, '-' | '\u{2013}' | '\u{2014}' | '/'))
+        .map(sanitize)
+        .filter(|word| !word.is_empty())
+        .filter(|word| !significant || !STOP_WORDS.contains(&word.to_lowercase().as_str()))
+        .take(limit.unwrap_or(usize::MAX))
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|c| c.to_ascii_uppercase())
+                .into_iter()
+                .chain(chars)
+                .collect::<String>()
+        })
+        .collect()
+}
+
+fn field(data: &EntryData, name: &str) -> String {
+    if let Some(field) = TextField::parse(name) {
+        data.text
+            .get(&field)
+            .map(|data| sanitize(&data.text))
+            .unwrap_or_default()
+    } else if let Some(field) = NumberField::parse(name) {
+        data.number
+            .get(&field)
+            .map(|data| sanitize(&data.to_string()))
+            .unwrap_or_default()
+    } else {
+        String::new()
+    }
+}
+
+/// Replaces accented letters with their ASCII base letter and removes all other characters
+/// that are not ASCII letters or digits.
+fn sanitize(text: &str) -> String {
+    let mut output = String::new();
+    for c in text.nfd() {
+        match c {
+            'ß' => output.push_str("ss"),
+            'æ' => output.push_str("ae"),
+            'Æ' => output.push_str("Ae"),
+            'œ' => output.push_str("oe"),
+            'Œ' => output.push_str("Oe"),
+            'ø' => output.push('o'),
+            'Ø' => output.push('O'),
+            'ł' => output.push('l'),
+            'Ł' => output.push('L'),
+            'đ' => output.push('d'),
+            'Đ' => output.push('D'),
+            'ı' => output.push('i'),
+            c if c.is_ascii_alphanumeric() => output.push(c),
+            _ => {}
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,94 @@
+use parser::parse_bibtex;
+use rowan::ast::AstNode;
+use syntax::bibtex;
+
+use super::{generate_key, DEFAULT_KEY_PATTERN};
+
+fn key(input: &str, pattern: &str) -> Option<String> {
+    let root = bibtex::Root::cast(bibtex::SyntaxNode::new_root(parse_bibtex(input))).unwrap();
+    let entry = root.entries().next().unwrap();
+    generate_key(&entry, std::slice::from_ref(&root), pattern)
+}
+
+#[test]
+fn default_pattern() {
+    let input = r#"@book{foo, author = {Knuth, Donald E.}, title = {The {\TeX}book}, year = 1984}"#;
+    assert_eq!(
+        key(input, DEFAULT_KEY_PATTERN).as_deref(),
+        Some("knuth1984TeXbook")
+    );
+}
+
+#[test]
+fn authors() {
+    let input = r#"@article{foo,
+    author = {Erd\H{o}s, Paul and R\'enyi, Alfr\'ed and Ludwig van Beethoven},
+    year = {1959}
+}"#;
+
+    assert_eq!(key(input, "[auth]").as_deref(), Some("Erdos"));
+    assert_eq!(
+        key(input, "[authors:lower]").as_deref(),
+        Some("erdosrenyibeethoven")
+    );
+
+    assert_eq!(
+        key(input, "[authEtAl]_[shortyear]").as_deref(),
+        Some("ErdosEtAl_59")
+    );
+    assert_eq!(key(input, "[auth:3:upper]").as_deref(), Some("ERD"));
+}
+
+#[test]
+fn editor_fallback() {
+    let input = r#"@collection{foo, editor = {Gr{\"a}tzer, George}, date = {2012-05-01}}"#;
+    assert_eq!(key(input, "[auth][year]").as_deref(), Some("Gratzer2012"));
+}
+
+#[test]
+fn titles() {
+    let input = r#"@article{foo, title = {On the Self-Organizing Nature of {L}\"ubeck}}"#;
+    assert_eq!(
+        key(input, "[title]").as_deref(),
+        Some("OnTheSelfOrganizingNatureOfLubeck")
+    );
+
+    assert_eq!(
+        key(input, "[shorttitle]").as_deref(),
+        Some("SelfOrganizingNature")
+    );
+
+    assert_eq!(
+        key(input, "[veryshorttitle:lower]").as_deref(),
+        Some("self")
+    );
+    assert_eq!(
+        key(input, "[shorttitle:2]").as_deref(),
+        Some("SelfOrganizing")
+    );
+}
+
+#[test]
+fn fields() {
+    let input = r#"@article{foo, journal = {Phys. Rev.}, volume = {12}, pages = {100--120}}"#;
+    assert_eq!(
+        key(input, "[journal]:[volume]-[firstpage]").as_deref(),
+        Some("PhysRev:12-100")
+    );
+}
+
+#[test]
+fn crossref() {
+    let input = r#"@inbook{foo, crossref = {bar}, title = {Chapter}}
+@book{bar, author = {Knuth, Donald}, year = {1984}}"#;
+    assert_eq!(
+        key(input, "[auth:lower][year]").as_deref(),
+        Some("knuth1984")
+    );
+}
+
+#[test]
+fn empty() {
+    assert_eq!(key("@misc{foo, note = {bar}}", "[auth][year]"), None);
+    assert_eq!(key("@misc{foo,}", "{}"), None);
+}
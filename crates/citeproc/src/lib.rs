@@ -1,28 +1,60 @@
+mod crossref;
+pub mod csl;
 mod driver;
 mod entry;
 pub mod field;
+pub mod key;
 mod output;
+pub mod unicode;
 
 use syntax::bibtex;
 use unicode_normalization::UnicodeNormalization;
 
-use self::{driver::Driver, output::Inline};
+use self::{driver::Driver, entry::EntryData};
 
+pub use self::output::{Format, Html, Inline, Latex, Markdown, PlainText, Punct};
+
+/// Renders an entry with the built-in citation style as Markdown. The entries of the
+/// `bibliography` are used to resolve `crossref`, `xdata` and `entryset` references.
 #[must_use]
-pub fn render(entry: &bibtex::Entry) -> Option<String> {
-    let mut output = String::new();
+pub fn render(entry: &bibtex::Entry, bibliography: &[bibtex::Root]) -> Option<String> {
+    render_with(entry, bibliography, &Markdown)
+}
+
+/// Renders an entry with the built-in citation style in the given output format.
+#[must_use]
+pub fn render_with(
+    entry: &bibtex::Entry,
+    bibliography: &[bibtex::Root],
+    format: &dyn Format,
+) -> Option<String> {
+    write_inlines(render_inlines(entry, bibliography), format)
+}
+
+/// Renders an item of a CSL-JSON or Hayagriva bibliography with the built-in citation style
+/// as Markdown.
+#[must_use]
+pub fn render_item(item: &syntax::csl::Item) -> Option<String> {
     let mut driver = Driver::default();
-    driver.process(entry);
-    driver.finish().for_each(|(inline, punct)| {
-        let text = match inline {
-            Inline::Regular(text) => text,
-            Inline::Italic(text) => format!("*{text}*"),
-            Inline::Quoted(text) => format!("\"{text}\""),
-            Inline::Link { url, alt } => format!("[{alt}]({url})"),
-        };
-        output.push_str(&text);
+    driver.process(EntryData::from(item));
+    write_inlines(driver.finish(), &Markdown)
+}
+
+/// Parses a field of a BibTeX entry like [`render`] and reports a value
+/// that is not in the format expected by biblatex.
+pub fn check_field(field: &bibtex::Field) -> Result<(), field::FieldError> {
+    EntryData::default().parse_field(field, &[])
+}
+
+fn write_inlines(
+    inlines: impl IntoIterator<Item = (Inline, Punct)>,
+    format: &dyn Format,
+) -> Option<String> {
+    let mut output = String::new();
+    for (inline, punct) in inlines {
+        format.write(&inline, &mut output);
         output.push_str(punct.as_str());
-    });
+    }
 
     if output.is_empty() {
         None
@@ -32,5 +64,37 @@ pub fn render(entry: &bibtex::Entry) -> Option<String> {
     }
 }
 
+/// Renders an entry with the built-in citation style into a sequence of inlines,
+/// each followed by the punctuation that separates it from the next one.
+/// The members of an `@set` entry are separated by semicolons.
+#[must_use]
+pub fn render_inlines(
+    entry: &bibtex::Entry,
+    bibliography: &[bibtex::Root],
+) -> Vec<(Inline, Punct)> {
+    let members = crossref::set_members(entry, bibliography);
+    if members.is_empty() {
+        let mut driver = Driver::default();
+        driver.process(crossref::resolve(entry, bibliography));
+        return driver.finish().collect();
+    }
+
+    let mut inlines: Vec<(Inline, Punct)> = Vec::new();
+    for member in &members {
+        let member_inlines = render_inlines(member, bibliography);
+        if member_inlines.is_empty() {
+            continue;
+        }
+
+        if let Some((_, last)) = inlines.last_mut() {
+            *last = Punct::Semicolon;
+        }
+
+        inlines.extend(member_inlines);
+    }
+
+    inlines
+}
+
 #[cfg(test)]
 mod tests;
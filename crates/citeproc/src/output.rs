@@ -1,5 +1,6 @@
 use std::ops::Add;
 
+/// The punctuation that separates two inlines.
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
 pub enum Punct {
     Nothing,
@@ -7,6 +8,7 @@ pub enum Punct {
     Comma,
     Dot,
     Colon,
+    Semicolon,
 }
 
 impl Punct {
@@ -17,6 +19,7 @@ impl Punct {
             Self::Comma => ", ",
             Self::Dot => ". ",
             Self::Colon => ": ",
+            Self::Semicolon => "; ",
         }
     }
 }
@@ -27,6 +30,7 @@ impl Add for Punct {
     fn add(self, rhs: Self) -> Self::Output {
         match (self, rhs) {
             (Self::Nothing, punct) | (punct, Self::Nothing) => punct,
+            (_, Self::Semicolon) | (Self::Semicolon, _) => Self::Semicolon,
             (_, Self::Colon) | (Self::Colon, _) => Self::Colon,
             (Self::Space, Self::Space) => Self::Space,
             (Self::Space | Self::Comma | Self::Dot, Self::Comma)
@@ -36,6 +40,7 @@ impl Add for Punct {
     }
 }
 
+/// A piece of formatted text of a rendered entry.
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Hash)]
 pub enum Inline {
     Regular(String),
@@ -45,7 +50,7 @@ pub enum Inline {
 }
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Hash, Default)]
-pub struct InlineBuilder {
+pub(crate) struct InlineBuilder {
     items: Vec<(Inline, Punct)>,
 }
 
@@ -66,3 +71,143 @@ impl InlineBuilder {
         self.items.into_iter()
     }
 }
+
+/// Converts the inlines of a rendered entry into a concrete output format.
+pub trait Format {
+    /// Appends the formatted inline to `output`.
+    fn write(&self, inline: &Inline, output: &mut String);
+}
+
+/// Markdown as used by the hover and completion documentation of the server.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Default)]
+pub struct Markdown;
+
+impl Format for Markdown {
+    fn write(&self, inline: &Inline, output: &mut String) {
+        match inline {
+            Inline::Regular(text) => output.push_str(text),
+            Inline::Italic(text) => {
+                output.push('*');
+                output.push_str(text);
+                output.push('*');
+            }
+            Inline::Quoted(text) => {
+                output.push('"');
+                output.push_str(text);
+                output.push('"');
+            }
+            Inline::Link { url, alt } => {
+                output.push_str(&format!("[{alt}]({url})"));
+            }
+        };
+    }
+}
+
+/// Plain text without any markup. Links are replaced by their text.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Default)]
+pub struct PlainText;
+
+impl Format for PlainText {
+    fn write(&self, inline: &Inline, output: &mut String) {
+        match inline {
+            Inline::Regular(text) | Inline::Italic(text) | Inline::Link { alt: text, .. } => {
+                output.push_str(text);
+            }
+            Inline::Quoted(text) => {
+                output.push('"');
+                output.push_str(text);
+                output.push('"');
+            }
+        };
+    }
+}
+
+/// An HTML fragment. Special characters are replaced by character references.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Default)]
+pub struct Html;
+
+impl Html {
+    fn escape(text: &str, output: &mut String) {
+        for c in text.chars() {
+            match c {
+                '&' => output.push_str("&amp;"),
+                '<' => output.push_str("&lt;"),
+                '>' => output.push_str("&gt;"),
+                '"' => output.push_str("&quot;"),
+                _ => output.push(c),
+            };
+        }
+    }
+}
+
+impl Format for Html {
+    fn write(&self, inline: &Inline, output: &mut String) {
+        match inline {
+            Inline::Regular(text) => Self::escape(text, output),
+            Inline::Italic(text) => {
+                output.push_str("<i>");
+                Self::escape(text, output);
+                output.push_str("</i>");
+            }
+            Inline::Quoted(text) => {
+                output.push_str("&ldquo;");
+                Self::escape(text, output);
+                output.push_str("&rdquo;");
+            }
+            Inline::Link { url, alt } => {
+                output.push_str("<a href=\"");
+                Self::escape(url, output);
+                output.push_str("\">");
+                Self::escape(alt, output);
+                output.push_str("</a>");
+            }
+        };
+    }
+}
+
+/// LaTeX markup using `\emph` for italics and `\url` or `\href` (from `hyperref`) for links.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash, Default)]
+pub struct Latex;
+
+impl Latex {
+    fn escape(text: &str, output: &mut String) {
+        for c in text.chars() {
+            match c {
+                '#' | '$' | '%' | '&' | '_' | '{' | '}' => {
+                    output.push('\\');
+                    output.push(c);
+                }
+                '\\' => output.push_str("\\textbackslash{}"),
+                '~' => output.push_str("\\textasciitilde{}"),
+                '^' => output.push_str("\\textasciicircum{}"),
+                _ => output.push(c),
+            };
+        }
+    }
+}
+
+impl Format for Latex {
+    fn write(&self, inline: &Inline, output: &mut String) {
+        match inline {
+            Inline::Regular(text) => Self::escape(text, output),
+            Inline::Italic(text) => {
+                output.push_str("\\emph{");
+                Self::escape(text, output);
+                output.push('}');
+            }
+            Inline::Quoted(text) => {
+                output.push_str("``");
+                Self::escape(text, output);
+                output.push_str("''");
+            }
+            Inline::Link { url, alt } if url == alt => {
+                output.push_str(&format!("\\url{{{url}}}"));
+            }
+            Inline::Link { url, alt } => {
+                output.push_str(&format!("\\href{{{url}}}{{"));
+                Self::escape(alt, output);
+                output.push('}');
+            }
+        };
+    }
+}
@@ -3,16 +3,47 @@ use parser::parse_bibtex;
 use rowan::ast::AstNode;
 use syntax::bibtex;
 
-fn render_entry(input: &str) -> String {
+use super::{Format, Html, Latex, Markdown, PlainText};
+
+fn render_entry(input: &str, format: &dyn Format) -> String {
     let green = parse_bibtex(input);
     let root = bibtex::Root::cast(bibtex::SyntaxNode::new_root(green)).unwrap();
     let entry = root.entries().next().unwrap();
-    super::render(&entry).unwrap()
+    super::render_with(&entry, std::slice::from_ref(&root), format).unwrap()
+}
+
+/// Checks the Markdown output and the output of the other formats
+/// using the snapshot suffixes `plain`, `html` and `latex`.
+macro_rules! assert_formats {
+    ($input:expr) => {{
+        let input = $input;
+        assert_snapshot!(render_entry(input, &Markdown));
+
+        let formats: [(&str, &dyn Format); 3] =
+            [("plain", &PlainText), ("html", &Html), ("latex", &Latex)];
+
+        for (suffix, format) in formats {
+            let mut settings = insta::Settings::clone_current();
+            settings.set_snapshot_suffix(suffix);
+            settings.bind(|| assert_snapshot!(render_entry(input, format)));
+        }
+    }};
+}
+
+/// Renders the first entry of the first file with the entries of all files as bibliography.
+fn render_with_bibliography(inputs: &[&str]) -> String {
+    let roots: Vec<_> = inputs
+        .iter()
+        .map(|input| bibtex::Root::cast(bibtex::SyntaxNode::new_root(parse_bibtex(input))).unwrap())
+        .collect();
+
+    let entry = roots[0].entries().next().unwrap();
+    super::render(&entry, &roots).unwrap()
 }
 
 #[test]
 fn article_rivest_1978() {
-    assert_snapshot!(render_entry(
+    assert_formats!(
         r#"
 @article{10.1145/359340.359342,
     author = {Rivest, R. L. and Shamir, A. and Adleman, L.},
@@ -31,12 +62,12 @@ fn article_rivest_1978() {
     pages = {120-126},
     numpages = {7},
 }"#
-    ));
+    );
 }
 
 #[test]
 fn article_jain_1999() {
-    assert_snapshot!(render_entry(
+    assert_formats!(
         r#"
 @article{10.1145/331499.331504,
     author = {Jain, A. K. and Murty, M. N. and Flynn, P. J.},
@@ -56,12 +87,12 @@ fn article_jain_1999() {
     numpages = {60},
     keywords = {incremental clustering, clustering applications, exploratory data analysis, cluster analysis, similarity indices, unsupervised learning}
 }"#
-    ));
+    );
 }
 
 #[test]
 fn article_aksin_2006() {
-    assert_snapshot!(render_entry(
+    assert_formats!(
         r#"
 @string{jomch   = {J.~Organomet. Chem.}}
 
@@ -79,12 +110,12 @@ fn article_aksin_2006() {
     pages        = {3027-3036},
     indextitle   = {Effect of immobilization on catalytic characteristics},
 }"#
-    ));
+    );
 }
 
 #[test]
 fn article_betram_1996() {
-    assert_snapshot!(render_entry(
+    assert_formats!(
         r#"
 @string{jams    = {J.~Amer. Math. Soc.}}
 
@@ -102,12 +133,12 @@ fn article_betram_1996() {
     annotation   = {An \texttt{article} entry with a \texttt{volume} and a
                     \texttt{number} field},
 }"#
-    ));
+    );
 }
 
 #[test]
 fn article_kastenholz_2006() {
-    assert_snapshot!(render_entry(
+    assert_formats!(
         r#"
 @string{jchph   = {J.~Chem. Phys.}}
 
@@ -130,12 +161,12 @@ fn article_kastenholz_2006() {
                     enabled},
 }
         "#
-    ));
+    );
 }
 
 #[test]
 fn article_blom_2021() {
-    assert_snapshot!(render_entry(
+    assert_formats!(
         r#"
 @article{DBLP:journals/corr/abs-2107-11903,
     author    = {Michelle L. Blom and
@@ -157,12 +188,12 @@ fn article_blom_2021() {
     biburl    = {https://dblp.org/rec/journals/corr/abs-2107-11903.bib},
     bibsource = {dblp computer science bibliography, https://dblp.org}
 }"#
-    ));
+    );
 }
 
 #[test]
 fn book_aho_2006() {
-    assert_snapshot!(render_entry(
+    assert_formats!(
         r#"
 @book{10.5555/1177220,
     author = {Aho, Alfred V. and Lam, Monica S. and Sethi, Ravi and Ullman, Jeffrey D.},
@@ -172,12 +203,12 @@ fn book_aho_2006() {
     publisher = {Addison-Wesley Longman Publishing Co., Inc.},
     address = {USA}
 }"#
-    ));
+    );
 }
 
 #[test]
 fn book_averroes_1998() {
-    assert_snapshot!(render_entry(
+    assert_formats!(
         r#"
 @book{averroes/bland,
     author       = {Averroes},
@@ -200,12 +231,12 @@ fn book_averroes_1998() {
                     and \texttt{translator} fields as well as the
                     \texttt{indextitle} field},
 }"#
-    ));
+    );
 }
 
 #[test]
 fn book_knuth_1984() {
-    assert_snapshot!(render_entry(
+    assert_formats!(
         r#"
 @book{knuth:ct:a,
     author       = {Knuth, Donald E.},
@@ -230,12 +261,12 @@ fn book_knuth_1984() {
                     to be protected from expansion},
 }
     "#
-    ));
+    );
 }
 
 #[test]
 fn mvbook_nietzsche_1988() {
-    assert_snapshot!(render_entry(
+    assert_formats!(
         r#"
 @string{dtv     = {Deutscher Taschenbuch-Verlag}}
 
@@ -261,12 +292,12 @@ fn mvbook_nietzsche_1988() {
                     sorting order of the bibliography. We want this item listed
                     first in the bibliography},
 }"#
-    ));
+    );
 }
 
 #[test]
 fn inproceedings_erwin_2007() {
-    assert_snapshot!(render_entry(
+    assert_formats!(
         r#"
 @inproceedings{10.5555/1386993.1386994,
     author = {Erwin, Alva and Gopalan, Raj P. and Achuthan, N. R.},
@@ -282,12 +313,12 @@ fn inproceedings_erwin_2007() {
     location = {Gold Coast, Australia},
     series = {AIDM '07}
 }"#
-    ));
+    );
 }
 
 #[test]
 fn inproceedings_combi_2004() {
-    assert_snapshot!(render_entry(
+    assert_formats!(
         r#"
 @inproceedings{10.1145/967900.968040,
     author = {Combi, Carlo and Pozzi, Giuseppe},
@@ -305,12 +336,12 @@ fn inproceedings_combi_2004() {
     location = {Nicosia, Cyprus},
     series = {SAC '04}
 }"#
-    ));
+    );
 }
 
 #[test]
 fn collection_matuz_1990() {
-    assert_snapshot!(render_entry(
+    assert_formats!(
         r#"
 @collection{matuz:doody,
     editor       = {Matuz, Roger},
@@ -326,12 +357,12 @@ fn collection_matuz_1990() {
                     for the \texttt{doody} entry. Note the format of the
                     \texttt{pages} field},
 }"#
-    ));
+    );
 }
 
 #[test]
 fn patent_almendro_1998() {
-    assert_snapshot!(render_entry(
+    assert_formats!(
         r#"
 @patent{almendro,
     author       = {Almendro, Jos{\'e} L. and Mart{\'i}n, Jacinto and S{\'a}nchez,
@@ -347,5 +378,198 @@ fn patent_almendro_1998() {
                     file. Compare \texttt{laufenberg}, \texttt{sorace}, and
                     \texttt{kowalik}},
 }"#
-    ));
+    );
+}
+
+#[test]
+fn inbook_latex_markup() {
+    assert_formats!(
+        r#"
+@inbook{markup,
+    author    = {M{\"{\i}}ller, Hans and {\O}stergaard, S{\o}ren},
+    title     = {Folding of $\alpha$-helices in H\textsubscript{2}O\textemdash a {\ss}tudy},
+    booktitle = {methods for the analysis of {DNA} and {pH}},
+    pages     = {11--42},
+    year      = {2001},
+    url       = {https://example.com/~mueller/a_b},
+}"#
+    );
+}
+
+#[test]
+fn crossref_inproceedings() {
+    assert_snapshot!(render_with_bibliography(&[
+        r#"
+@inproceedings{moraux,
+    author       = {Moraux, Paul},
+    title        = {Le De Anima dans la tradition gr{\`e}cque},
+    pages        = {281-324},
+    crossref     = {Lloyd:1978},
+}"#,
+        r#"
+@proceedings{Lloyd:1978,
+    editor       = {Lloyd, G. E. R. and Owen, G. E. L.},
+    title        = {Aristotle on Mind and the Senses},
+    eventtitle   = {Seventh Symposium Aristotelicum},
+    publisher    = {Cambridge University Press},
+    location     = {Cambridge},
+    date         = 1979,
+}"#
+    ]));
+}
+
+#[test]
+fn crossref_child_fields_take_precedence() {
+    assert_snapshot!(render_with_bibliography(&[r#"
+@inbook{kant:kpv,
+    title        = {Kritik der praktischen Vernunft},
+    author       = {Kant, Immanuel},
+    pages        = {1-163},
+    year         = {1913},
+    crossref     = {kant:ak},
+}
+
+@book{kant:ak,
+    title        = {Kants Werke. Akademie Textausgabe},
+    author       = {Kant, Immanuel},
+    volume       = 5,
+    publisher    = {Walter de Gruyter},
+    location     = {Berlin},
+    date         = {1968-05-12},
+}"#]));
+}
+
+#[test]
+fn crossref_cycle() {
+    assert_snapshot!(render_with_bibliography(&[r#"
+@incollection{foo,
+    author       = {Foo, Bar},
+    title        = {Foo},
+    crossref     = {bar},
+}
+
+@collection{bar,
+    title        = {Bar},
+    publisher    = {Baz},
+    crossref     = {foo},
+}"#]));
+}
+
+#[test]
+fn string_from_other_document() {
+    assert_snapshot!(render_with_bibliography(&[
+        r#"
+@article{foo,
+    author       = {Foo, Bar},
+    title        = {Foo},
+    journal      = jcp # { Letters},
+    year         = {2020},
+    month        = mar,
+}"#,
+        r#"
+@string{jcp = {J. Chem. Phys.}}"#,
+    ]));
+}
+
+#[test]
+fn string_case_insensitive() {
+    assert_snapshot!(render_with_bibliography(&[r#"
+@string{JCP = {J. Chem. Phys.}}
+
+@article{foo,
+    author       = {Foo, Bar},
+    title        = {Foo},
+    journal      = jcp,
+    year         = {2020},
+}"#]));
+}
+
+#[test]
+fn xdata() {
+    assert_snapshot!(render_with_bibliography(&[r#"
+@book{knuth:ct:a,
+    author       = {Knuth, Donald E.},
+    title        = {The \TeX book},
+    volume       = {A},
+    xdata        = {knuth:ct, knuth:ct:related},
+}
+
+@xdata{knuth:ct,
+    publisher    = {Addison-Wesley},
+    location     = {Reading, Mass.},
+}
+
+@xdata{knuth:ct:related,
+    date         = 1984,
+    title        = {Ignored},
+}"#]));
+}
+
+#[test]
+fn set() {
+    assert_snapshot!(render_with_bibliography(&[r#"
+@set{stdmodel,
+    entryset     = {glashow, weinberg},
+}
+
+@article{glashow,
+    author       = {Glashow, Sheldon},
+    title        = {Partial Symmetries of Weak Interactions},
+    journaltitle = {Nucl.~Phys.},
+    volume       = {22},
+    date         = 1961,
+    pages        = {579-588},
+}
+
+@article{weinberg,
+    author       = {Weinberg, Steven},
+    title        = {A Model of Leptons},
+    journaltitle = {Phys.~Rev.~Lett.},
+    volume       = {19},
+    date         = 1967,
+    pages        = {1264-1266},
+}"#]));
+}
+
+#[test]
+fn csl_json_article() {
+    let bibliography = parser::parse_csl_json(
+        r#"[
+  {
+    "id": "einstein1905",
+    "type": "article-journal",
+    "author": [{ "family": "Einstein", "given": "Albert" }],
+    "title": "Zur Elektrodynamik bewegter Körper",
+    "container-title": "Annalen der Physik",
+    "volume": "322",
+    "issue": 10,
+    "page": "891-921",
+    "issued": { "date-parts": [[1905, 6, 30]] },
+    "DOI": "10.1002/andp.19053221004"
+  }
+]"#,
+    );
+
+    assert_snapshot!(super::render_item(&bibliography.items[0]).unwrap());
+}
+
+#[test]
+fn hayagriva_chapter() {
+    let bibliography = parser::parse_hayagriva(
+        r#"
+turing1950:
+  type: chapter
+  title: Computing Machinery and Intelligence
+  author: ["Turing, Alan"]
+  page-range: 433-460
+  parent:
+    type: anthology
+    title: The Philosophy of Artificial Intelligence
+    editor: ["Boden, Margaret A."]
+    publisher: Oxford University Press
+    date: 1990
+"#,
+    );
+
+    assert_snapshot!(super::render_item(&bibliography.items[0]).unwrap());
 }
@@ -0,0 +1,719 @@
+use std::ops::Range;
+
+/// Text that has been converted from LaTeX markup to Unicode.
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Default)]
+pub struct UnicodeText {
+    pub text: String,
+
+    /// The byte ranges of `text` that must not be changed by case conversions.
+    /// These come from braces at the top level (e.g. `{NASA}`) and from math.
+    pub protected: Vec<Range<usize>>,
+}
+
+impl UnicodeText {
+    pub fn is_protected(&self, offset: usize) -> bool {
+        self.protected.iter().any(|range| range.contains(&offset))
+    }
+}
+
+/// Converts LaTeX markup like `{\"a}`, `\textendash`, `--` or `$\alpha$` to Unicode.
+/// Unknown commands are kept as they are.
+#[must_use]
+pub fn convert(input: &str) -> UnicodeText {
+    let mut converter = Converter::new(input, false, 0);
+    converter.run();
+    converter.output
+}
+
+/// Removes the escapes of special characters (e.g. `\_` or `\%`) from verbatim text like URLs
+/// without interpreting any other markup.
+#[must_use]
+pub fn unescape(input: &str) -> String {
+    let mut output = String::new();
+    let mut chars = input.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('\\', Some(&next)) if r#"&$%#_{}~"#.contains(next) => {
+                output.push(next);
+                chars.next();
+            }
+            (c, _) if c.is_whitespace() => {
+                while chars.next_if(|c| c.is_whitespace()).is_some() {}
+                output.push(' ');
+            }
+            (c, _) => output.push(c),
+        }
+    }
+
+    output
+}
+
+struct Converter {
+    chars: Vec<char>,
+    pos: usize,
+    output: UnicodeText,
+    depth: usize,
+    math: bool,
+}
+
+impl Converter {
+    fn new(input: &str, math: bool, depth: usize) -> Self {
+        Self {
+            chars: input.chars().collect(),
+            pos: 0,
+            output: UnicodeText::default(),
+            depth,
+            math,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        let matches = self.peek() == Some(c);
+        if matches {
+            self.pos += 1;
+        }
+
+        matches
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().map_or(false, char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    fn push(&mut self, text: &str) {
+        self.output.text.push_str(text);
+    }
+
+    fn run(&mut self) {
+        while self.pos < self.chars.len() {
+            self.step();
+        }
+    }
+
+    fn step(&mut self) {
+        let c = self.chars[self.pos];
+        self.pos += 1;
+        match c {
+            '{' => self.group(),
+            '}' => (),
+            '$' if !self.math => self.math_mode(),
+            '$' => (),
+            '\\' => self.command(),
+            '~' => self.push(" "),
+            c if c.is_whitespace() => {
+                self.skip_whitespace();
+                self.push(" ");
+            }
+            '^' if self.math => self.script(superscript, "^"),
+            '_' if self.math => self.script(subscript, "_"),
+            '-' if self.math => self.push("\u{2212}"),
+            '\'' if self.math => self.push("\u{2032}"),
+            '-' if self.eat('-') => {
+                if self.eat('-') {
+                    self.push("\u{2014}");
+                } else {
+                    self.push("\u{2013}");
+                }
+            }
+            '`' if self.eat('`') => self.push("\u{201C}"),
+            '`' => self.push("\u{2018}"),
+            '\'' if self.eat('\'') => self.push("\u{201D}"),
+            c => self.output.text.push(c),
+        }
+    }
+
+    /// Converts the contents of a group after its opening brace. Groups at the top level
+    /// protect their contents from case conversions unless they start with a command
+    /// like `{\"a}` (BibTeX treats these as special characters).
+    fn group(&mut self) {
+        let protect = self.depth == 0 && !self.math && self.peek() != Some('\\');
+        let start = self.output.text.len();
+        self.depth += 1;
+        while let Some(c) = self.peek() {
+            if c == '}' {
+                self.pos += 1;
+                break;
+            }
+
+            self.step();
+        }
+
+        self.depth -= 1;
+        let end = self.output.text.len();
+        if protect && start < end {
+            self.output.protected.push(start..end);
+        }
+    }
+
+    fn math_mode(&mut self) {
+        let display = self.eat('$');
+        let start = self.output.text.len();
+        self.math = true;
+        while let Some(c) = self.peek() {
+            if c == '$' {
+                self.pos += 1;
+                if display {
+                    self.eat('$');
+                }
+
+                break;
+            }
+
+            self.step();
+        }
+
+        self.math = false;
+        let end = self.output.text.len();
+        if self.depth == 0 && start < end {
+            self.output.protected.push(start..end);
+        }
+    }
+
+    /// Reads the next argument without converting it, i.e. the contents of a group,
+    /// a control sequence or a single character.
+    fn raw_argument(&mut self) -> String {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('{') => {
+                self.pos += 1;
+                let start = self.pos;
+                let mut depth = 0;
+                while let Some(c) = self.peek() {
+                    match c {
+                        '\\' => self.pos += 1,
+                        '{' => depth += 1,
+                        '}' if depth == 0 => break,
+                        '}' => depth -= 1,
+                        _ => (),
+                    };
+
+                    self.pos += 1;
+                }
+
+                let end = self.pos.min(self.chars.len());
+                self.eat('}');
+                self.chars[start..end].iter().collect()
+            }
+            Some('\\') => {
+                let start = self.pos;
+                self.pos += 1;
+                if self.peek().map_or(false, |c| c.is_ascii_alphabetic()) {
+                    while self.peek().map_or(false, |c| c.is_ascii_alphabetic()) {
+                        self.pos += 1;
+                    }
+                } else {
+                    self.pos = (self.pos + 1).min(self.chars.len());
+                }
+
+                self.chars[start..self.pos].iter().collect()
+            }
+            Some(c) => {
+                self.pos += 1;
+                c.to_string()
+            }
+            None => String::new(),
+        }
+    }
+
+    /// Reads the next argument and converts it in the current mode.
+    fn argument(&mut self) -> String {
+        let input = self.raw_argument();
+        let mut converter = Self::new(&input, self.math, self.depth + 1);
+        converter.run();
+        converter.output.text
+    }
+
+    fn script(&mut self, map: fn(char) -> Option<char>, fallback: &str) {
+        let argument = self.argument();
+        match argument.chars().map(map).collect::<Option<String>>() {
+            Some(script) => self.push(&script),
+            None => {
+                self.push(fallback);
+                self.push(&argument);
+            }
+        };
+    }
+
+    fn command(&mut self) {
+        let Some(c) = self.peek() else {
+            self.push("\\");
+            return;
+        };
+
+        if !c.is_ascii_alphabetic() {
+            self.pos += 1;
+            self.control_symbol(c);
+            return;
+        }
+
+        let start = self.pos;
+        while self.peek().map_or(false, |c| c.is_ascii_alphabetic()) {
+            self.pos += 1;
+        }
+
+        let name: String = self.chars[start..self.pos].iter().collect();
+        if !self.control_word(&name) {
+            self.push("\\");
+            self.push(&name);
+        }
+    }
+
+    fn control_symbol(&mut self, c: char) {
+        if let Some(mark) = accent_mark(c) {
+            self.accent(mark, c);
+            return;
+        }
+
+        match c {
+            ' ' | ',' | ';' | ':' | '\\' => self.push(" "),
+            '!' | '-' | '/' | '@' => (),
+            '(' | '[' => self.math = true,
+            ')' | ']' => self.math = false,
+            c => self.output.text.push(c),
+        };
+    }
+
+    /// Handles a command like `\ss` or `\emph` and returns `false` if it is unknown.
+    fn control_word(&mut self, name: &str) -> bool {
+        if let Some(mark) = letter_accent_mark(name) {
+            self.accent(mark, '\0');
+            return true;
+        }
+
+        if let Some(symbol) = symbol(name) {
+            if !self.math {
+                self.skip_whitespace();
+            }
+
+            self.push(symbol);
+            return true;
+        }
+
+        match name {
+            "emph" | "textit" | "textbf" | "textsc" | "textrm" | "textsf" | "texttt" | "textup"
+            | "textsl" | "textmd" | "textnormal" | "text" | "mbox" | "hbox" | "mathrm"
+            | "mathit" | "mathbf" | "mathsf" | "mathtt" | "mathcal" | "mathnormal"
+            | "operatorname" | "NoCaseChange" | "ensuremath" | "enquote" => {
+                let argument = self.argument();
+                self.push(&argument);
+            }
+            "MakeUppercase" | "uppercase" => {
+                let argument = self.argument();
+                self.push(&argument.to_uppercase());
+            }
+            "MakeLowercase" | "lowercase" => {
+                let argument = self.argument();
+                self.push(&argument.to_lowercase());
+            }
+            "textsuperscript" => self.script(superscript, ""),
+            "textsubscript" => self.script(subscript, ""),
+            "mathbb" => {
+                let argument: String = self.argument().chars().map(double_struck).collect();
+                self.push(&argument);
+            }
+            "sqrt" => {
+                let argument = self.argument();
+                self.push("\u{221A}");
+                if argument.chars().count() > 1 {
+                    self.push(&format!("({argument})"));
+                } else {
+                    self.push(&argument);
+                }
+            }
+            "frac" => {
+                let numerator = self.argument();
+                let denominator = self.argument();
+                self.push(&format!("{numerator}/{denominator}"));
+            }
+            "url" | "nolinkurl" | "path" | "doi" => {
+                let argument = unescape(&self.raw_argument());
+                self.push(&argument);
+            }
+            "href" => {
+                let _ = self.raw_argument();
+                let argument = self.argument();
+                self.push(&argument);
+            }
+            "quad" | "qquad" | "enspace" | "thinspace" | "space" | "newblock" | "newline" => {
+                self.skip_whitespace();
+                self.push(" ");
+            }
+            "relax" | "protect" | "noopsort" | "em" | "it" | "bf" | "sc" | "rm" | "sf" | "tt"
+            | "sl" | "itshape" | "bfseries" | "scshape" | "upshape" | "slshape" | "mdseries"
+            | "rmfamily" | "sffamily" | "ttfamily" | "normalfont" | "tiny" | "scriptsize"
+            | "footnotesize" | "small" | "normalsize" | "large" | "Large" | "LARGE" | "huge"
+            | "Huge" | "displaystyle" | "textstyle" | "left" | "right" | "big" | "Big" => {
+                self.skip_whitespace();
+            }
+            _ => return false,
+        };
+
+        true
+    }
+
+    /// Applies an accent to the first character of the next argument.
+    /// The dotless letters `\i` and `\j` are replaced with their dotted counterparts.
+    fn accent(&mut self, mark: char, name: char) {
+        let argument = self.argument();
+        let mut chars = argument.chars();
+        let Some(base) = chars.next() else {
+            if name != '\0' {
+                self.output.text.push(name);
+            }
+
+            return;
+        };
+
+        let base = match base {
+            '\u{0131}' => 'i',
+            '\u{0237}' => 'j',
+            base => base,
+        };
+
+        match unicode_normalization::char::compose(base, mark) {
+            Some(c) => self.output.text.push(c),
+            None => {
+                self.output.text.push(base);
+                self.output.text.push(mark);
+            }
+        };
+
+        self.output.text.extend(chars);
+    }
+}
+
+fn accent_mark(name: char) -> Option<char> {
+    Some(match name {
+        '`' => '\u{0300}',
+        '\'' => '\u{0301}',
+        '^' => '\u{0302}',
+        '~' => '\u{0303}',
+        '=' => '\u{0304}',
+        '.' => '\u{0307}',
+        '"' => '\u{0308}',
+        _ => return None,
+    })
+}
+
+fn letter_accent_mark(name: &str) -> Option<char> {
+    Some(match name {
+        "H" => '\u{030B}',
+        "c" => '\u{0327}',
+        "k" => '\u{0328}',
+        "b" => '\u{0331}',
+        "d" => '\u{0323}',
+        "r" => '\u{030A}',
+        "u" => '\u{0306}',
+        "v" => '\u{030C}',
+        "t" => '\u{0361}',
+        _ => return None,
+    })
+}
+
+fn symbol(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "ss" => "ß",
+        "SS" => "SS",
+        "ae" => "æ",
+        "AE" => "Æ",
+        "oe" => "œ",
+        "OE" => "Œ",
+        "aa" => "å",
+        "AA" => "Å",
+        "o" => "ø",
+        "O" => "Ø",
+        "l" => "ł",
+        "L" => "Ł",
+        "i" => "ı",
+        "j" => "ȷ",
+        "dh" => "ð",
+        "DH" => "Ð",
+        "th" => "þ",
+        "TH" => "Þ",
+        "ng" => "ŋ",
+        "NG" => "Ŋ",
+        "dj" => "đ",
+        "DJ" => "Đ",
+        "textendash" => "–",
+        "textemdash" => "—",
+        "textquoteleft" => "‘",
+        "textquoteright" => "’",
+        "textquotedblleft" => "“",
+        "textquotedblright" => "”",
+        "quotedblbase" => "„",
+        "quotesinglbase" => "‚",
+        "guillemotleft" | "guillemetleft" => "«",
+        "guillemotright" | "guillemetright" => "»",
+        "guilsinglleft" => "‹",
+        "guilsinglright" => "›",
+        "textellipsis" | "dots" | "ldots" => "…",
+        "S" | "textsection" => "§",
+        "P" | "textparagraph" => "¶",
+        "copyright" | "textcopyright" => "©",
+        "textregistered" => "®",
+        "texttrademark" => "™",
+        "textdegree" | "degree" => "°",
+        "texteuro" | "euro" => "€",
+        "pounds" | "textsterling" => "£",
+        "textyen" => "¥",
+        "textdollar" => "$",
+        "textbackslash" => "\\",
+        "textasciitilde" => "~",
+        "textasciicircum" => "^",
+        "textunderscore" => "_",
+        "textbar" => "|",
+        "textless" => "<",
+        "textgreater" => ">",
+        "textbullet" | "bullet" => "•",
+        "textdagger" | "dag" | "dagger" => "†",
+        "textdaggerdbl" | "ddag" | "ddagger" => "‡",
+        "textperiodcentered" => "·",
+        "textexclamdown" => "¡",
+        "textquestiondown" => "¿",
+        "textordfeminine" => "ª",
+        "textordmasculine" => "º",
+        "textmu" => "µ",
+        "hyphen" => "-",
+        "slash" => "/",
+        "TeX" => "TeX",
+        "LaTeX" => "LaTeX",
+        "LaTeXe" => "LaTeX2ε",
+        "BibTeX" => "BibTeX",
+        "alpha" => "α",
+        "beta" => "β",
+        "gamma" => "γ",
+        "delta" => "δ",
+        "epsilon" => "ϵ",
+        "varepsilon" => "ε",
+        "zeta" => "ζ",
+        "eta" => "η",
+        "theta" => "θ",
+        "vartheta" => "ϑ",
+        "iota" => "ι",
+        "kappa" => "κ",
+        "lambda" => "λ",
+        "mu" => "μ",
+        "nu" => "ν",
+        "xi" => "ξ",
+        "pi" => "π",
+        "varpi" => "ϖ",
+        "rho" => "ρ",
+        "varrho" => "ϱ",
+        "sigma" => "σ",
+        "varsigma" => "ς",
+        "tau" => "τ",
+        "upsilon" => "υ",
+        "phi" => "ϕ",
+        "varphi" => "φ",
+        "chi" => "χ",
+        "psi" => "ψ",
+        "omega" => "ω",
+        "Gamma" => "Γ",
+        "Delta" => "Δ",
+        "Theta" => "Θ",
+        "Lambda" => "Λ",
+        "Xi" => "Ξ",
+        "Pi" => "Π",
+        "Sigma" => "Σ",
+        "Upsilon" => "Υ",
+        "Phi" => "Φ",
+        "Psi" => "Ψ",
+        "Omega" => "Ω",
+        "infty" => "∞",
+        "pm" => "±",
+        "mp" => "∓",
+        "times" => "×",
+        "cdot" => "⋅",
+        "div" => "÷",
+        "ast" => "∗",
+        "star" => "⋆",
+        "circ" => "∘",
+        "leq" | "le" => "≤",
+        "geq" | "ge" => "≥",
+        "ll" => "≪",
+        "gg" => "≫",
+        "neq" | "ne" => "≠",
+        "approx" => "≈",
+        "sim" => "∼",
+        "simeq" => "≃",
+        "cong" => "≅",
+        "equiv" => "≡",
+        "propto" => "∝",
+        "to" | "rightarrow" => "→",
+        "leftarrow" | "gets" => "←",
+        "leftrightarrow" => "↔",
+        "Rightarrow" => "⇒",
+        "Leftarrow" => "⇐",
+        "Leftrightarrow" | "iff" => "⇔",
+        "mapsto" => "↦",
+        "uparrow" => "↑",
+        "downarrow" => "↓",
+        "in" => "∈",
+        "notin" => "∉",
+        "ni" => "∋",
+        "subset" => "⊂",
+        "subseteq" => "⊆",
+        "supset" => "⊃",
+        "supseteq" => "⊇",
+        "cup" => "∪",
+        "cap" => "∩",
+        "setminus" => "∖",
+        "emptyset" | "varnothing" => "∅",
+        "forall" => "∀",
+        "exists" => "∃",
+        "neg" | "lnot" => "¬",
+        "wedge" | "land" => "∧",
+        "vee" | "lor" => "∨",
+        "oplus" => "⊕",
+        "otimes" => "⊗",
+        "partial" => "∂",
+        "nabla" => "∇",
+        "sum" => "∑",
+        "prod" => "∏",
+        "int" => "∫",
+        "oint" => "∮",
+        "ell" => "ℓ",
+        "hbar" => "ℏ",
+        "aleph" => "ℵ",
+        "Re" => "ℜ",
+        "Im" => "ℑ",
+        "wp" => "℘",
+        "langle" => "⟨",
+        "rangle" => "⟩",
+        "lceil" => "⌈",
+        "rceil" => "⌉",
+        "lfloor" => "⌊",
+        "rfloor" => "⌋",
+        "cdots" => "⋯",
+        "vdots" => "⋮",
+        "ddots" => "⋱",
+        "prime" => "′",
+        "perp" => "⊥",
+        "parallel" => "∥",
+        "angle" => "∠",
+        "triangle" => "△",
+        "log" => "log",
+        "ln" => "ln",
+        "exp" => "exp",
+        "sin" => "sin",
+        "cos" => "cos",
+        "tan" => "tan",
+        "lim" => "lim",
+        "max" => "max",
+        "min" => "min",
+        "det" => "det",
+        _ => return None,
+    })
+}
+
+fn superscript(c: char) -> Option<char> {
+    Some(match c {
+        '0' => '⁰',
+        '1' => '¹',
+        '2' => '²',
+        '3' => '³',
+        '4' => '⁴',
+        '5' => '⁵',
+        '6' => '⁶',
+        '7' => '⁷',
+        '8' => '⁸',
+        '9' => '⁹',
+        '+' => '⁺',
+        '-' | '\u{2212}' => '⁻',
+        '=' => '⁼',
+        '(' => '⁽',
+        ')' => '⁾',
+        'a' => 'ᵃ',
+        'b' => 'ᵇ',
+        'c' => 'ᶜ',
+        'd' => 'ᵈ',
+        'e' => 'ᵉ',
+        'f' => 'ᶠ',
+        'g' => 'ᵍ',
+        'h' => 'ʰ',
+        'i' => 'ⁱ',
+        'j' => 'ʲ',
+        'k' => 'ᵏ',
+        'l' => 'ˡ',
+        'm' => 'ᵐ',
+        'n' => 'ⁿ',
+        'o' => 'ᵒ',
+        'p' => 'ᵖ',
+        'r' => 'ʳ',
+        's' => 'ˢ',
+        't' => 'ᵗ',
+        'u' => 'ᵘ',
+        'v' => 'ᵛ',
+        'w' => 'ʷ',
+        'x' => 'ˣ',
+        'y' => 'ʸ',
+        'z' => 'ᶻ',
+        '\u{2032}' => '\u{2032}',
+        _ => return None,
+    })
+}
+
+fn subscript(c: char) -> Option<char> {
+    Some(match c {
+        '0' => '₀',
+        '1' => '₁',
+        '2' => '₂',
+        '3' => '₃',
+        '4' => '₄',
+        '5' => '₅',
+        '6' => '₆',
+        '7' => '₇',
+        '8' => '₈',
+        '9' => '₉',
+        '+' => '₊',
+        '-' | '\u{2212}' => '₋',
+        '=' => '₌',
+        '(' => '₍',
+        ')' => '₎',
+        'a' => 'ₐ',
+        'e' => 'ₑ',
+        'h' => 'ₕ',
+        'i' => 'ᵢ',
+        'j' => 'ⱼ',
+        'k' => 'ₖ',
+        'l' => 'ₗ',
+        'm' => 'ₘ',
+        'n' => 'ₙ',
+        'o' => 'ₒ',
+        'p' => 'ₚ',
+        'r' => 'ᵣ',
+        's' => 'ₛ',
+        't' => 'ₜ',
+        'u' => 'ᵤ',
+        'v' => 'ᵥ',
+        'x' => 'ₓ',
+        _ => return None,
+    })
+}
+
+fn double_struck(c: char) -> char {
+    let offset = match c {
+        'C' => return 'ℂ',
+        'H' => return 'ℍ',
+        'N' => return 'ℕ',
+        'P' => return 'ℙ',
+        'Q' => return 'ℚ',
+        'R' => return 'ℝ',
+        'Z' => return 'ℤ',
+        'A'..='Z' => 0x1D538 + (c as u32 - 'A' as u32),
+        'a'..='z' => 0x1D552 + (c as u32 - 'a' as u32),
+        '0'..='9' => 0x1D7D8 + (c as u32 - '0' as u32),
+        _ => return c,
+    };
+
+    char::from_u32(offset).unwrap_or(c)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,85 @@
+use super::{convert, unescape};
+
+fn text(input: &str) -> String {
+    convert(input).text
+}
+
+#[test]
+fn accents() {
+    assert_eq!(text(r#"Jaff{\'e} and G{\"o}del"#), "Jaffé and Gödel");
+    assert_eq!(text(r#"Erd\H{o}s and Dvo\v{r}\'ak"#), "Erdős and Dvořák");
+    assert_eq!(
+        text(r#"Na{\"{\i}}ve and \c{C}a\u{g}lar"#),
+        "Naïve and Çağlar"
+    );
+    assert_eq!(text(r#"\'{}"#), "'");
+}
+
+#[test]
+fn symbols() {
+    assert_eq!(
+        text(r#"Stra{\ss}e, {\ae}ther, {\AA}ngstr{\"o}m"#),
+        "Straße, æther, Ångström"
+    );
+    assert_eq!(text(r#"\L{}{\'o}d{\'z} and {\o}"#), "Łódź and ø");
+    assert_eq!(text(r#"1990\textendash 2000 \S 3"#), "1990–2000 §3");
+    assert_eq!(text(r#"Tom \& Jerry: 50\% off"#), "Tom & Jerry: 50% off");
+    assert_eq!(text(r#"\TeX{} and \LaTeX"#), "TeX and LaTeX");
+    assert_eq!(text(r#"\unknown{x}"#), r#"\unknownx"#);
+}
+
+#[test]
+fn ligatures() {
+    assert_eq!(text("pages 1--2 --- or"), "pages 1–2 — or");
+    assert_eq!(text("``quoted'' and `single'"), "“quoted” and ‘single'");
+    assert_eq!(text("non~breaking   space"), "non breaking space");
+}
+
+#[test]
+fn math() {
+    assert_eq!(text(r#"$\alpha$-helix"#), "α-helix");
+    assert_eq!(text(r#"$x^2 + y_{i} \leq 10^{-3}$"#), "x² + yᵢ ≤ 10⁻³");
+    assert_eq!(text(r#"$\mathbb{R}^n$ and $\sqrt{2}$"#), "ℝⁿ and √2");
+    assert_eq!(text(r#"$O(n \log n)$"#), "O(n log n)");
+    assert_eq!(text(r#"$a^{\beta}$"#), "a^β");
+}
+
+#[test]
+fn arguments() {
+    assert_eq!(text(r#"\emph{Foo} \textbf{Bar}"#), "Foo Bar");
+    assert_eq!(
+        text(r#"1\textsuperscript{st} and H\textsubscript{2}O"#),
+        "1ˢᵗ and H₂O"
+    );
+    assert_eq!(
+        text(r#"\url{https://example.com/~user/a_b}"#),
+        "https://example.com/~user/a_b"
+    );
+    assert_eq!(text(r#"\href{https://example.com}{Example}"#), "Example");
+    assert_eq!(text(r#"\MakeUppercase{abc}"#), "ABC");
+}
+
+#[test]
+fn protected() {
+    let output = convert(r#"The {NASA} mission to {\"O}land with $\alpha$"#);
+    assert_eq!(output.text, "The NASA mission to Öland with α");
+    assert_eq!(output.protected, vec![4..8, 32..34]);
+    assert!(output.is_protected(5));
+    assert!(!output.is_protected(20));
+}
+
+#[test]
+fn protected_nested() {
+    let output = convert(r#"{The {Bayes} Rule} of {a}"#);
+    assert_eq!(output.text, "The Bayes Rule of a");
+    assert_eq!(output.protected, vec![0..14, 18..19]);
+}
+
+#[test]
+fn verbatim() {
+    assert_eq!(unescape(r#"10.1000/a\_b\%c"#), "10.1000/a_b%c");
+    assert_eq!(
+        unescape(r#"https://example.com/~user"#),
+        "https://example.com/~user"
+    );
+}
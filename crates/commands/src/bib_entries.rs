@@ -0,0 +1,551 @@
+use base_db::{Document, DocumentData};
+use chrono::Datelike;
+use citeproc::field::{
+    author::AuthorFieldData,
+    date::DateFieldData,
+    text::{strip_doi_prefix, TextFieldData},
+};
+use rowan::{ast::AstNode, TextRange};
+use rustc_hash::FxHashMap;
+use syntax::{
+    bibtex::{self, HasComma, HasName, HasType, HasValue},
+    latex,
+};
+use thiserror::Error;
+
+/// A text edit that is produced by one of the commands working on BibTeX entries.
+#[derive(Debug)]
+pub struct EntryEdit<'a> {
+    pub document: &'a Document,
+    pub range: TextRange,
+    pub new_text: String,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum SortOrder {
+    Key,
+    Type,
+    Year,
+    Author,
+}
+
+/// Sorts the entries of a BibTeX document. Strings, preambles and comments stay where they are
+/// and the entries are rearranged between them. Entries that compare equal keep their order.
+pub fn sort_entries(document: &Document, order: SortOrder) -> Option<Vec<EntryEdit<'_>>> {
+    let data = document.data.as_bib()?;
+    let root = bibtex::Root::cast(data.root_node())?;
+    let entries: Vec<_> = root.entries().collect();
+
+    let mut sorted: Vec<_> = entries
+        .iter()
+        .map(|entry| (sort_key(entry, order), entry))
+        .collect();
+
+    sorted.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    let edits = entries
+        .iter()
+        .zip(sorted)
+        .filter(|(old, (_, new))| old.syntax() != new.syntax())
+        .map(|(old, (_, new))| EntryEdit {
+            document,
+            range: old.syntax().text_range(),
+            new_text: new.syntax().text().to_string(),
+        })
+        .collect();
+
+    Some(edits)
+}
+
+fn sort_key(entry: &bibtex::Entry, order: SortOrder) -> (bool, String, i32, String) {
+    let key = entry
+        .name_token()
+        .map(|token| token.text().to_lowercase())
+        .unwrap_or_default();
+
+    match order {
+        SortOrder::Key => (false, String::new(), 0, key),
+        SortOrder::Type => {
+            let ty = entry
+                .type_token()
+                .map(|token| token.text().to_lowercase())
+                .unwrap_or_default();
+
+            (false, ty, 0, key)
+        }
+        SortOrder::Year => {
+            let year = year(entry);
+            (year.is_none(), String::new(), year.unwrap_or_default(), key)
+        }
+        SortOrder::Author => {
+            let author = first_author(entry);
+            let year = year(entry).unwrap_or_default();
+            (author.is_none(), author.unwrap_or_default(), year, key)
+        }
+    }
+}
+
+fn field_value(entry: &bibtex::Entry, name: &str) -> Option<bibtex::Value> {
+    entry
+        .fields()
+        .find(|field| {
+            field
+                .name_token()
+                .map_or(false, |token| token.text().eq_ignore_ascii_case(name))
+        })
+        .and_then(|field| field.value())
+}
+
+fn year(entry: &bibtex::Entry) -> Option<i32> {
+    ["date", "year"]
+        .iter()
+        .filter_map(|name| field_value(entry, name))
+        .filter_map(|value| DateFieldData::parse(&value))
+        .find_map(|date| match date {
+            DateFieldData::Date(date) => Some(date.year()),
+            DateFieldData::Year(year) => Some(year),
+            DateFieldData::Month(_) => None,
+            DateFieldData::Other(text) => {
+                let digits: String = text
+                    .trim_start()
+                    .chars()
+                    .take_while(char::is_ascii_digit)
+                    .collect();
+
+                Some(digits)
+                    .filter(|digits| digits.len() == 4)
+                    .and_then(|digits| digits.parse().ok())
+            }
+        })
+}
+
+fn first_author(entry: &bibtex::Entry) -> Option<String> {
+    let value = field_value(entry, "author").or_else(|| field_value(entry, "editor"))?;
+    let data = AuthorFieldData::parse(&value)?;
+    let name = data.authors.first()?;
+    Some(name.surname().to_lowercase())
+}
+
+/// An entry that is likely a duplicate of other entries.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct DuplicateEntry<'a> {
+    pub document: &'a Document,
+    pub key: String,
+    pub range: TextRange,
+}
+
+/// Groups the entries of the given BibTeX documents that likely describe the same work.
+/// Two entries are considered duplicates if they have the same DOI or if their titles
+/// and years are equal after removing LaTeX markup, punctuation and case.
+pub fn find_duplicates<'a>(
+    documents: impl IntoIterator<Item = &'a Document>,
+) -> Vec<Vec<DuplicateEntry<'a>>> {
+    let mut entries = Vec::new();
+    let mut fingerprints = Vec::new();
+    for document in documents {
+        let Some(data) = document.data.as_bib() else { continue };
+        let Some(root) = bibtex::Root::cast(data.root_node()) else { continue };
+        for entry in root.entries() {
+            let Some(key) = entry.name_token() else { continue };
+            fingerprints.push(fingerprint(&entry));
+            entries.push(DuplicateEntry {
+                document,
+                key: key.text().into(),
+                range: key.text_range(),
+            });
+        }
+    }
+
+    let mut parents: Vec<_> = (0..entries.len()).collect();
+    let mut owners = FxHashMap::default();
+    for (index, fingerprint) in fingerprints.into_iter().enumerate() {
+        for value in fingerprint {
+            let owner = *owners.entry(value).or_insert(index);
+            let (a, b) = (
+                find_root(&mut parents, owner),
+                find_root(&mut parents, index),
+            );
+            parents[a.max(b)] = a.min(b);
+        }
+    }
+
+    let mut groups: Vec<Vec<DuplicateEntry>> = Vec::new();
+    let mut group_indices = FxHashMap::default();
+    for (index, entry) in entries.into_iter().enumerate() {
+        let root = find_root(&mut parents, index);
+        let group = *group_indices.entry(root).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+
+        groups[group].push(entry);
+    }
+
+    groups.retain(|group| group.len() > 1);
+    groups
+}
+
+fn find_root(parents: &mut [usize], mut index: usize) -> usize {
+    while parents[index] != index {
+        parents[index] = parents[parents[index]];
+        index = parents[index];
+    }
+
+    index
+}
+
+fn fingerprint(entry: &bibtex::Entry) -> Vec<String> {
+    let mut values = Vec::new();
+    let doi = field_value(entry, "doi")
+        .and_then(|value| TextFieldData::parse_verbatim(&value))
+        .map(|data| normalize_doi(&data.text))
+        .filter(|doi| !doi.is_empty());
+
+    if let Some(doi) = doi {
+        values.push(format!("doi:{doi}"));
+    }
+
+    let title: Option<String> = field_value(entry, "title")
+        .and_then(|value| TextFieldData::parse(&value))
+        .map(|data| {
+            data.text
+                .chars()
+                .filter(|c| c.is_alphanumeric())
+                .flat_map(char::to_lowercase)
+                .collect()
+        })
+        .filter(|title: &String| !title.is_empty());
+
+    if let Some(title) = title {
+        let year = year(entry).map(|year| year.to_string()).unwrap_or_default();
+        values.push(format!("title:{title}:{year}"));
+    }
+
+    values
+}
+
+fn normalize_doi(doi: &str) -> String {
+    let doi = doi.trim();
+    strip_doi_prefix(doi).unwrap_or(doi).to_lowercase()
+}
+
+#[derive(Debug, Error)]
+pub enum MergeError {
+    #[error("An entry cannot be merged with itself")]
+    SameEntry,
+
+    #[error("Entry \"{0}\" not found")]
+    EntryNotFound(String),
+}
+
+/// Merges the entry `remove` into the entry `keep`. Fields that only exist in the removed
+/// entry are appended to the kept entry, the removed entry is deleted and all citations
+/// of the removed key in the given documents are replaced with the kept key.
+pub fn merge_entries<'a>(
+    documents: &[&'a Document],
+    keep: &str,
+    remove: &str,
+) -> Result<Vec<EntryEdit<'a>>, MergeError> {
+    if keep == remove {
+        return Err(MergeError::SameEntry);
+    }
+
+    let find = |key: &str| {
+        documents
+            .iter()
+            .filter_map(|document| {
+                let data = document.data.as_bib()?;
+                let entry = bibtex::Root::cast(data.root_node())?.find_entry(key)?;
+                Some((*document, entry))
+            })
+            .next()
+            .ok_or_else(|| MergeError::EntryNotFound(key.into()))
+    };
+
+    let (keep_document, keep_entry) = find(keep)?;
+    let (remove_document, remove_entry) = find(remove)?;
+
+    let mut edits = Vec::new();
+    edits.extend(append_fields(keep_document, &keep_entry, &remove_entry));
+    edits.push(EntryEdit {
+        document: remove_document,
+        range: entry_range(&remove_entry),
+        new_text: String::new(),
+    });
+
+    for document in documents {
+        let DocumentData::Tex(data) = &document.data else { continue };
+        edits.extend(
+            data.root_node()
+                .descendants()
+                .filter_map(latex::Citation::cast)
+                .filter_map(|citation| citation.key_list())
+                .flat_map(|list| list.keys())
+                .filter(|key| key.to_string() == remove)
+                .map(|key| EntryEdit {
+                    document,
+                    range: latex::small_range(&key),
+                    new_text: keep.into(),
+                }),
+        );
+    }
+
+    Ok(edits)
+}
+
+/// Returns the range of an entry together with the whitespace that follows it.
+fn entry_range(entry: &bibtex::Entry) -> TextRange {
+    let range = entry.syntax().text_range();
+    entry
+        .syntax()
+        .next_sibling_or_token()
+        .and_then(|element| element.into_token())
+        .filter(|token| token.kind() == bibtex::JUNK && token.text().trim().is_empty())
+        .map_or(range, |token| range.cover(token.text_range()))
+}
+
+fn append_fields<'a>(
+    document: &'a Document,
+    target: &bibtex::Entry,
+    source: &bibtex::Entry,
+) -> Option<EntryEdit<'a>> {
+    let existing: Vec<_> = target
+        .fields()
+        .filter_map(|field| field.name_token())
+        .map(|token| token.text().to_lowercase())
+        .collect();
+
+    let fields: Vec<_> = source
+        .fields()
+        .filter_map(|field| {
+            let name = field.name_token()?;
+            let value = field.value()?;
+            if existing.contains(&name.text().to_lowercase()) {
+                return None;
+            }
+
+            Some(format!("{} = {}", name.text(), value.syntax().text()))
+        })
+        .collect();
+
+    if fields.is_empty() {
+        return None;
+    }
+
+    let last_field = target.fields().last();
+    let indent = last_field
+        .as_ref()
+        .and_then(|field| field.syntax().prev_sibling_or_token())
+        .and_then(|element| element.into_token())
+        .filter(|token| token.kind() == bibtex::WHITESPACE)
+        .and_then(|token| token.text().rsplit('\n').next().map(String::from))
+        .unwrap_or_else(|| String::from("  "));
+
+    let (anchor, has_comma) = match &last_field {
+        Some(field) => (field.syntax().text_range(), field.comma_token().is_some()),
+        None => match target.comma_token() {
+            Some(comma) => (comma.text_range(), true),
+            None => (target.name_token()?.text_range(), false),
+        },
+    };
+
+    let new_text = fields
+        .iter()
+        .map(|field| {
+            if has_comma {
+                format!("\n{indent}{field},")
+            } else {
+                format!(",\n{indent}{field}")
+            }
+        })
+        .collect();
+
+    Some(EntryEdit {
+        document,
+        range: TextRange::empty(anchor.end()),
+        new_text,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use test_utils::fixture::Fixture;
+
+    use super::{find_duplicates, merge_entries, sort_entries, EntryEdit, SortOrder};
+
+    fn apply(text: &str, mut edits: Vec<&EntryEdit>) -> String {
+        let mut text = text.to_string();
+        edits.sort_by_key(|edit| std::cmp::Reverse(edit.range.start()));
+        for edit in edits {
+            text.replace_range(std::ops::Range::<usize>::from(edit.range), &edit.new_text);
+        }
+
+        text
+    }
+
+    fn sort(input: &str, order: SortOrder) -> String {
+        let fixture = Fixture::parse(input);
+        let document = fixture.workspace.iter().next().unwrap();
+        let edits = sort_entries(document, order).unwrap();
+        apply(&document.text, edits.iter().collect())
+    }
+
+    #[test]
+    fn test_sort_by_key() {
+        let output = sort(
+            r#"
+%! main.bib
+@string{foo = {Foo}}
+@article{c, title = {C}}
+@book{A, title = {A}}
+% comment
+@misc{b, title = {B}}"#,
+            SortOrder::Key,
+        );
+
+        assert_eq!(
+            output,
+            "@string{foo = {Foo}}\n@book{A, title = {A}}\n@misc{b, title = {B}}\n% comment\n@article{c, title = {C}}\n"
+        );
+    }
+
+    #[test]
+    fn test_sort_by_type() {
+        let output = sort(
+            r#"
+%! main.bib
+@misc{a}
+@book{b}
+@article{c}"#,
+            SortOrder::Type,
+        );
+
+        assert_eq!(output, "@article{c}\n@book{b}\n@misc{a}\n");
+    }
+
+    #[test]
+    fn test_sort_by_year() {
+        let output = sort(
+            r#"
+%! main.bib
+@misc{a}
+@misc{b, year = 2001}
+@misc{c, date = {1999-05-01}}"#,
+            SortOrder::Year,
+        );
+
+        assert_eq!(
+            output,
+            "@misc{c, date = {1999-05-01}}\n@misc{b, year = 2001}\n@misc{a}\n"
+        );
+    }
+
+    #[test]
+    fn test_sort_by_author() {
+        let output = sort(
+            r#"
+%! main.bib
+@misc{a, author = {Zuse, Konrad}}
+@misc{b}
+@misc{c, editor = {M{\"u}ller, Hans}}
+@misc{d, author = {Knuth, Donald and Zuse, Konrad}}"#,
+            SortOrder::Author,
+        );
+
+        assert_eq!(
+            output,
+            "@misc{d, author = {Knuth, Donald and Zuse, Konrad}}\n@misc{c, editor = {M{\\\"u}ller, Hans}}\n@misc{a, author = {Zuse, Konrad}}\n@misc{b}\n"
+        );
+    }
+
+    #[test]
+    fn test_find_duplicates() {
+        let fixture = Fixture::parse(
+            r#"
+%! main.bib
+@article{a, title = {The {\TeX}book}, year = 1984}
+@book{b, title = {The \TeX{}book.}, year = {1984}}
+@book{c, title = {The TeXbook}, year = 1986}
+@article{d, doi = {10.1145/359340.359342}}
+@article{e, doi = {https://doi.org/10.1145/359340.359342}}
+
+%! other.bib
+@book{f, title = {the texbook}, date = {1984-01-01}}"#,
+        );
+
+        let mut documents: Vec<_> = fixture.workspace.iter().collect();
+        documents.sort_by(|a, b| a.uri.cmp(&b.uri));
+
+        let keys: Vec<Vec<_>> = find_duplicates(documents)
+            .into_iter()
+            .map(|group| group.into_iter().map(|entry| entry.key).collect())
+            .collect();
+
+        assert_eq!(keys, vec![vec!["a", "b", "f"], vec!["d", "e"]]);
+    }
+
+    #[test]
+    fn test_merge_entries() {
+        let fixture = Fixture::parse(
+            r#"
+%! main.bib
+@article{foo,
+    author = {Foo Bar},
+    title = {Baz},
+}
+
+@article{foo2,
+    title = {Baz Qux},
+    year = 2020,
+    doi = {10.1000/xyz}
+}
+
+@misc{bar}
+
+%! main.tex
+\addbibresource{main.bib}
+\cite{foo2} \cite{foo, foo2}"#,
+        );
+
+        let documents: Vec<_> = fixture.workspace.iter().collect();
+        let edits = merge_entries(&documents, "foo", "foo2").unwrap();
+        let output: Vec<_> = fixture
+            .workspace
+            .iter()
+            .map(|document| {
+                let edits: Vec<_> = edits
+                    .iter()
+                    .filter(|edit| edit.document.uri == document.uri)
+                    .collect();
+
+                (
+                    document.uri.path().to_string(),
+                    apply(&document.text, edits),
+                )
+            })
+            .collect();
+
+        let expected_bib = "@article{foo,\n    author = {Foo Bar},\n    title = {Baz},\n    year = 2020,\n    doi = {10.1000/xyz},\n}\n\n@misc{bar}\n";
+        let expected_tex = "\\addbibresource{main.bib}\n\\cite{foo} \\cite{foo, foo}\n";
+        for (path, text) in output {
+            if path.ends_with(".bib") {
+                assert_eq!(text, expected_bib);
+            } else {
+                assert_eq!(text, expected_tex);
+            }
+        }
+    }
+
+    #[test]
+    fn test_merge_entries_missing() {
+        let fixture = Fixture::parse(
+            r#"
+%! main.bib
+@article{foo}"#,
+        );
+
+        let documents: Vec<_> = fixture.workspace.iter().collect();
+        assert!(merge_entries(&documents, "foo", "bar").is_err());
+        assert!(merge_entries(&documents, "foo", "foo").is_err());
+    }
+}
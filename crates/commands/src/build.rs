@@ -6,7 +6,7 @@ use std::{
 };
 
 use anyhow::Result;
-use base_db::Workspace;
+use base_db::{semantics::tex::MagicComments, Workspace};
 use bstr::io::BufReadExt;
 use crossbeam_channel::Sender;
 use thiserror::Error;
@@ -39,6 +39,23 @@ impl BuildCommand {
             return Err(BuildError::NotFound(uri.clone()));
         };
 
+        if let Some((project, _)) = workspace.tectonic(document) {
+            let Ok(working_dir) = project.dir.to_file_path() else {
+                return Err(BuildError::NotLocal(project.uri.clone()));
+            };
+
+            return Ok(Self {
+                program: "tectonic".into(),
+                args: vec![
+                    "-X".into(),
+                    "build".into(),
+                    "--keep-logs".into(),
+                    "--synctex".into(),
+                ],
+                working_dir,
+            });
+        }
+
         let document = workspace
             .parents(document)
             .into_iter()
@@ -49,9 +66,12 @@ impl BuildCommand {
             return Err(BuildError::NotLocal(document.uri.clone()));
         };
 
-        let config = &workspace.config().build;
+        let config = &workspace.config_for(&document.uri).build;
         let program = config.program.clone();
-        let args = replace_placeholders(&config.args, &[('f', path)]);
+        let mut args = replace_placeholders(&config.args, &[('f', path)]);
+        if let Some(data) = document.data.as_tex() {
+            apply_magic_comments(&program, &mut args, &data.semantics.magic_comments);
+        }
 
         let Ok(working_dir) = workspace.current_dir(&document.dir).to_file_path() else {
             return Err(BuildError::NotLocal(document.uri.clone()));
@@ -125,6 +145,69 @@ impl BuildCommand {
     }
 }
 
+const LATEXMK_ENGINE_FLAGS: &[&str] = &[
+    "-pdf",
+    "-pdfdvi",
+    "-pdfps",
+    "-pdflua",
+    "-pdfxe",
+    "-dvi",
+    "-ps",
+    "-lualatex",
+    "-xelatex",
+];
+
+/// Selects the engine and the bibliography processor of `latexmk` according to
+/// the `% !TEX program` and `% !BIB program` magic comments of the root document.
+/// The arguments of other build programs are left unchanged.
+fn apply_magic_comments(program: &str, args: &mut Vec<String>, magic: &MagicComments) {
+    if Path::new(program)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        != Some("latexmk")
+    {
+        return;
+    }
+
+    let is_valid_name = |name: &str| {
+        name.chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    };
+
+    if let Some(engine) = magic.program.as_ref().map(|span| span.text.as_str()) {
+        let flag = match engine.to_ascii_lowercase().as_str() {
+            "pdflatex" => Some("-pdf"),
+            "lualatex" => Some("-pdflua"),
+            "xelatex" => Some("-pdfxe"),
+            "latex" => Some("-pdfdvi"),
+            _ => None,
+        };
+
+        match flag {
+            Some(flag) => {
+                args.retain(|arg| !LATEXMK_ENGINE_FLAGS.contains(&arg.as_str()));
+                args.insert(0, flag.into());
+            }
+            None => log::warn!("Unsupported TeX program in magic comment: {engine}"),
+        };
+    }
+
+    if let Some(bib_program) = magic.bib_program.as_ref().map(|span| span.text.as_str()) {
+        if is_valid_name(bib_program) {
+            let variable = if bib_program.contains("biber") {
+                "biber"
+            } else {
+                "bibtex"
+            };
+
+            args.insert(0, "-e".into());
+            args.insert(1, format!("${variable} = '{bib_program} %O %S'"));
+        } else {
+            log::warn!("Invalid BibTeX program in magic comment: {bib_program}");
+        }
+    }
+}
+
 fn track_output(
     output: impl Read + Send + 'static,
     sender: Sender<String>,
@@ -138,3 +221,69 @@ fn track_output(
         })
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use base_db::semantics::{tex::MagicComments, Span};
+    use rowan::TextRange;
+
+    use super::apply_magic_comments;
+
+    fn span(text: &str) -> Option<Span> {
+        Some(Span {
+            text: text.into(),
+            range: TextRange::default(),
+        })
+    }
+
+    fn default_args() -> Vec<String> {
+        ["-pdf", "-interaction=nonstopmode", "-synctex=1", "main.tex"]
+            .into_iter()
+            .map(String::from)
+            .collect()
+    }
+
+    #[test]
+    fn test_engine() {
+        let magic = MagicComments {
+            program: span("LuaLaTeX"),
+            ..MagicComments::default()
+        };
+
+        let mut args = default_args();
+        apply_magic_comments("latexmk", &mut args, &magic);
+        assert_eq!(
+            args,
+            vec![
+                "-pdflua",
+                "-interaction=nonstopmode",
+                "-synctex=1",
+                "main.tex"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bib_program() {
+        let magic = MagicComments {
+            bib_program: span("biber"),
+            ..MagicComments::default()
+        };
+
+        let mut args = default_args();
+        apply_magic_comments("latexmk", &mut args, &magic);
+        assert_eq!(&args[..3], &["-e", "$biber = 'biber %O %S'", "-pdf"]);
+    }
+
+    #[test]
+    fn test_other_program() {
+        let magic = MagicComments {
+            program: span("xelatex"),
+            ..MagicComments::default()
+        };
+
+        let mut args = default_args();
+        apply_magic_comments("tectonic", &mut args, &magic);
+        assert_eq!(args, default_args());
+    }
+}
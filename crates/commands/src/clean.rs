@@ -1,7 +1,11 @@
-use std::process::Stdio;
+use std::{
+    ffi::OsStr,
+    path::{Path, PathBuf},
+};
 
 use anyhow::Result;
 use base_db::{Document, Workspace};
+use distro::Language;
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
 pub enum CleanTarget {
@@ -9,49 +13,359 @@ pub enum CleanTarget {
     Artifacts,
 }
 
+/// Suffixes of the files that are generated next to the output file.
+static AUXILIARY_SUFFIXES: &[&str] = &[
+    ".acn",
+    ".acr",
+    ".alg",
+    ".aux",
+    ".bbl",
+    ".bcf",
+    ".blg",
+    ".brf",
+    ".fdb_latexmk",
+    ".fls",
+    ".glg",
+    ".glo",
+    ".gls",
+    ".glsdefs",
+    ".idx",
+    ".ilg",
+    ".ind",
+    ".ist",
+    ".loa",
+    ".lof",
+    ".log",
+    ".lol",
+    ".lot",
+    ".nav",
+    ".nlo",
+    ".nls",
+    ".out",
+    ".run.xml",
+    ".snm",
+    ".synctex",
+    ".synctex.gz",
+    ".synctex(busy)",
+    ".thm",
+    ".toc",
+    ".vrb",
+    ".xdy",
+    "-blx.aux",
+    "-blx.bib",
+];
+
+static ARTIFACT_EXTENSIONS: &[&str] = &["dvi", "pdf", "ps", "xdv"];
+
 #[derive(Debug)]
 pub struct CleanCommand {
-    executable: String,
-    args: Vec<String>,
+    files: Vec<PathBuf>,
 }
 
 impl CleanCommand {
     pub fn new(workspace: &Workspace, document: &Document, target: CleanTarget) -> Result<Self> {
-        let Some(path) = document.path.as_deref() else {
+        let mut files = Vec::new();
+        if let Some((project, manifest)) = workspace.tectonic(document) {
+            let Ok(project_dir) = project.dir.to_file_path() else {
+                anyhow::bail!("document '{}' is not a local file", project.uri)
+            };
+
+            for output in &manifest.outputs {
+                let dir = project_dir.join(output.build_dir());
+                Self::add_files(&mut files, &dir, &output.name, target);
+            }
+
+            return Ok(Self { files });
+        }
+
+        let document = workspace
+            .parents(document)
+            .into_iter()
+            .next()
+            .unwrap_or(document);
+
+        let Some(stem) = document.path
+            .as_deref()
+            .and_then(Path::file_stem)
+            .and_then(OsStr::to_str) else
+        {
             anyhow::bail!("document '{}' is not a local file", document.uri)
         };
 
         let dir = workspace.current_dir(&document.dir);
         let dir = workspace.output_dir(&dir).to_file_path().unwrap();
+        Self::add_files(&mut files, &dir, stem, target);
+        Ok(Self { files })
+    }
 
-        let flag = match target {
-            CleanTarget::Auxiliary => "-c",
-            CleanTarget::Artifacts => "-C",
-        };
+    /// Adds the output files of the document `stem` in `dir`, including the files
+    /// that are listed as outputs by the `.fls` file of the document.
+    fn add_files(files: &mut Vec<PathBuf>, dir: &Path, stem: &str, target: CleanTarget) {
+        files.extend(
+            AUXILIARY_SUFFIXES
+                .iter()
+                .map(|suffix| dir.join(format!("{stem}{suffix}"))),
+        );
+
+        if target == CleanTarget::Artifacts {
+            files.extend(
+                ARTIFACT_EXTENSIONS
+                    .iter()
+                    .map(|ext| dir.join(format!("{stem}.{ext}"))),
+            );
+        }
+
+        let recorder = std::fs::read_to_string(dir.join(format!("{stem}.fls")))
+            .map(|text| parser::parse_recorder(&text))
+            .unwrap_or_default();
 
-        let executable = String::from("latexmk");
-        let args = vec![
-            format!("-outdir={}", dir.display()),
-            String::from(flag),
-            path.display().to_string(),
-        ];
+        let project_dir = recorder.working_dir.as_deref().unwrap_or(dir);
+        for path in recorder.outputs {
+            if !(path.starts_with(dir) || path.starts_with(project_dir)) {
+                continue;
+            }
 
-        Ok(Self { executable, args })
+            if matches!(
+                Language::from_bibliography_path(&path),
+                Some(
+                    Language::Tex
+                        | Language::Bib
+                        | Language::CslJson
+                        | Language::Hayagriva
+                        | Language::Root
+                        | Language::Tectonic
+                )
+            ) {
+                continue;
+            }
+
+            let is_artifact = path
+                .extension()
+                .and_then(OsStr::to_str)
+                .map_or(false, |ext| ARTIFACT_EXTENSIONS.contains(&ext));
+
+            if (!is_artifact || target == CleanTarget::Artifacts) && !files.contains(&path) {
+                files.push(path);
+            }
+        }
     }
 
-    pub fn run(self) -> Result<()> {
-        log::debug!("Cleaning output files: {} {:?}", self.executable, self.args);
-        let result = std::process::Command::new(self.executable)
-            .args(self.args)
-            .stdin(Stdio::null())
-            .stdout(Stdio::null())
-            .stderr(Stdio::null())
-            .status();
+    /// Removes the output files and returns the paths of the files that were deleted.
+    pub fn run(self) -> Result<Vec<PathBuf>> {
+        let mut removed = Vec::new();
+        for path in self.files.into_iter().filter(|path| path.is_file()) {
+            match std::fs::remove_file(&path) {
+                Ok(()) => {
+                    log::debug!("Removed output file {}", path.display());
+                    removed.push(path);
+                }
+                Err(why) => log::warn!("Unable to remove {}: {why}", path.display()),
+            };
+        }
+
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use base_db::{Config, Owner, Workspace};
+    use distro::Language;
+
+    use super::{CleanCommand, CleanTarget};
 
-        if let Err(why) = result {
-            anyhow::bail!("failed to spawn process: {why}")
+    fn setup(files: &[&str]) -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        for path in files {
+            let path = dir.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "").unwrap();
         }
 
-        Ok(())
+        std::fs::write(dir.path().join("main.tex"), "\\documentclass{article}").unwrap();
+        dir
+    }
+
+    /// Cleans the outputs of `main.tex` and returns the removed files relative to `dir`.
+    fn clean(dir: &Path, output_dir: &str, target: CleanTarget) -> Vec<String> {
+        let mut config = Config::default();
+        config.build.output_dir = output_dir.into();
+
+        let mut workspace = Workspace::default();
+        workspace.set_config(config);
+        let path = dir.join("main.tex");
+        workspace.load(&path, Language::Tex, Owner::Server).unwrap();
+        let document = workspace.lookup_path(&path).unwrap();
+
+        let command = CleanCommand::new(&workspace, document, target).unwrap();
+        let mut removed: Vec<_> = command
+            .run()
+            .unwrap()
+            .into_iter()
+            .map(|path| relative_path(dir, &path))
+            .collect();
+
+        removed.sort();
+        removed
+    }
+
+    /// Returns the files in `dir` and its subdirectories relative to `root`.
+    fn list_files(root: &Path, dir: &Path) -> Vec<String> {
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(dir).unwrap().flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                files.extend(list_files(root, &path));
+            } else {
+                files.push(relative_path(root, &path));
+            }
+        }
+
+        files.sort();
+        files
+    }
+
+    fn relative_path(root: &Path, path: &Path) -> String {
+        let path = path.strip_prefix(root).unwrap_or(path);
+        path.to_string_lossy().replace('\\', "/")
+    }
+
+    #[test]
+    fn test_auxiliary() {
+        let dir = setup(&[
+            "main.aux",
+            "main.log",
+            "main.synctex.gz",
+            "main-blx.bib",
+            "main.pdf",
+            "other.aux",
+            "notes.txt",
+        ]);
+
+        assert_eq!(
+            clean(dir.path(), ".", CleanTarget::Auxiliary),
+            vec!["main-blx.bib", "main.aux", "main.log", "main.synctex.gz"]
+        );
+
+        assert_eq!(
+            list_files(dir.path(), dir.path()),
+            vec!["main.pdf", "main.tex", "notes.txt", "other.aux"]
+        );
+    }
+
+    #[test]
+    fn test_artifacts() {
+        let dir = setup(&["main.aux", "main.pdf", "main.xdv", "other.pdf"]);
+        assert_eq!(
+            clean(dir.path(), ".", CleanTarget::Artifacts),
+            vec!["main.aux", "main.pdf", "main.xdv"]
+        );
+
+        assert_eq!(
+            list_files(dir.path(), dir.path()),
+            vec!["main.tex", "other.pdf"]
+        );
+    }
+
+    #[test]
+    fn test_output_dir() {
+        let dir = setup(&["main.aux", "build/main.aux", "build/main.pdf"]);
+        assert_eq!(
+            clean(dir.path(), "build", CleanTarget::Auxiliary),
+            vec!["build/main.aux"]
+        );
+
+        assert_eq!(
+            list_files(dir.path(), dir.path()),
+            vec!["build/main.pdf", "main.aux", "main.tex"]
+        );
+    }
+
+    #[test]
+    fn test_tectonic() {
+        let dir = setup(&[
+            "src/index.tex",
+            "build/default/default.log",
+            "build/default/default.pdf",
+            "build/slides/slides.log",
+            "index.log",
+        ]);
+
+        let manifest = "[doc]\nname = \"foo\"\n\n\
+            [[output]]\nname = \"default\"\ntype = \"pdf\"\n\n\
+            [[output]]\nname = \"slides\"\ntype = \"pdf\"\n";
+
+        std::fs::write(dir.path().join("Tectonic.toml"), manifest).unwrap();
+
+        let mut workspace = Workspace::default();
+        let manifest_path = dir.path().join("Tectonic.toml");
+        let index_path = dir.path().join("src/index.tex");
+        workspace
+            .load(&manifest_path, Language::Tectonic, Owner::Server)
+            .unwrap();
+        workspace
+            .load(&index_path, Language::Tex, Owner::Server)
+            .unwrap();
+
+        let document = workspace.lookup_path(&index_path).unwrap();
+        let command = CleanCommand::new(&workspace, document, CleanTarget::Artifacts).unwrap();
+        let mut removed: Vec<_> = command
+            .run()
+            .unwrap()
+            .into_iter()
+            .map(|path| relative_path(dir.path(), &path))
+            .collect();
+
+        removed.sort();
+        assert_eq!(
+            removed,
+            vec![
+                "build/default/default.log",
+                "build/default/default.pdf",
+                "build/slides/slides.log"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_recorder_outputs() {
+        let outside = setup(&["outside.aux"]);
+        let dir = setup(&[
+            "chapter.aux",
+            "figures/plot.pdf",
+            "refs.bib",
+            "refs.json",
+            "data.csv",
+        ]);
+
+        let fls = format!(
+            "PWD {}\nINPUT main.tex\nINPUT refs.bib\nOUTPUT main.aux\nOUTPUT chapter.aux\n\
+             OUTPUT figures/plot.pdf\nOUTPUT data.csv\nOUTPUT main.tex\nOUTPUT refs.bib\n\
+             OUTPUT refs.json\nOUTPUT {}\n",
+            dir.path().display(),
+            outside.path().join("outside.aux").display(),
+        );
+
+        std::fs::write(dir.path().join("main.fls"), fls).unwrap();
+        assert_eq!(
+            clean(dir.path(), ".", CleanTarget::Auxiliary),
+            vec!["chapter.aux", "data.csv", "main.fls"]
+        );
+
+        assert_eq!(
+            list_files(dir.path(), dir.path()),
+            vec!["figures/plot.pdf", "main.tex", "refs.bib", "refs.json"]
+        );
+
+        assert!(outside.path().join("outside.aux").is_file());
+
+        let fls = format!("PWD {}\nOUTPUT figures/plot.pdf\n", dir.path().display());
+        std::fs::write(dir.path().join("main.fls"), fls).unwrap();
+        assert_eq!(
+            clean(dir.path(), ".", CleanTarget::Artifacts),
+            vec!["figures/plot.pdf", "main.fls"]
+        );
     }
 }
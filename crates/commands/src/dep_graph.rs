@@ -2,6 +2,7 @@ use std::io::Write;
 
 use anyhow::Result;
 use base_db::{graph, Document, Workspace};
+use distro::Language;
 use itertools::Itertools;
 use rustc_hash::FxHashMap;
 
@@ -44,10 +45,11 @@ pub fn show_dependency_graph(workspace: &Workspace) -> Result<String> {
     {
         let source = &documents[edge.source];
         let target = &documents[edge.target];
-        let label = edge
-            .weight
-            .as_ref()
-            .map_or("<artifact>", |weight| &weight.link.path.text);
+        let label = match &edge.weight {
+            Some(weight) => &weight.link.path.text,
+            None if edge.source.language == Language::Fls => "<recorded>",
+            None => "<artifact>",
+        };
 
         writeln!(&mut writer, "\t{source} -> {target} [label=\"{label}\"];")?;
     }
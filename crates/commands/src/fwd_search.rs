@@ -5,7 +5,7 @@ use std::{
 };
 
 use anyhow::Result;
-use base_db::Workspace;
+use base_db::{Document, Workspace};
 use thiserror::Error;
 use url::Url;
 
@@ -44,40 +44,15 @@ impl ForwardSearch {
         uri: &Url,
         line: Option<u32>,
     ) -> Result<Self, ForwardSearchError> {
-        let Some(config) = &workspace.config().synctex else {
+        let Some(config) = &workspace.config_for(uri).synctex else {
             return Err(ForwardSearchError::Unconfigured);
         };
 
-        let Some(child) = workspace.lookup(uri) else {
-            return Err(ForwardSearchError::TexNotFound(uri.clone()));
-        };
-
-        let parents = workspace.parents(child);
-        let parent = parents.into_iter().next().unwrap_or(child);
-        if parent.uri.scheme() != "file" {
-            return Err(ForwardSearchError::NotLocal(parent.uri.clone()));
-        }
-
-        let dir = workspace.current_dir(&parent.dir);
-        let dir = workspace.output_dir(&dir).to_file_path().unwrap();
-
+        let (child, pdf_path) = find_pdf(workspace, uri)?;
         let Some(tex_path) = &child.path else {
             return Err(ForwardSearchError::InvalidPath(child.uri.clone()));
         };
 
-        let Some(pdf_path) = parent.path
-            .as_deref()
-            .and_then(Path::file_stem)
-            .and_then(OsStr::to_str)
-            .map(|stem| dir.join(format!("{stem}.pdf"))) else 
-        {
-            return Err(ForwardSearchError::InvalidPath(parent.uri.clone()));
-        };
-
-        if !pdf_path.exists() {
-            return Err(ForwardSearchError::PdfNotFound(pdf_path));
-        }
-
         let tex_path = tex_path.to_string_lossy().into_owned();
         let pdf_path = pdf_path.to_string_lossy().into_owned();
         let line = line.unwrap_or(child.cursor.line);
@@ -93,6 +68,59 @@ impl ForwardSearch {
     }
 }
 
+pub(crate) fn find_pdf<'a>(
+    workspace: &'a Workspace,
+    uri: &Url,
+) -> Result<(&'a Document, PathBuf), ForwardSearchError> {
+    let Some(child) = workspace.lookup(uri) else {
+        return Err(ForwardSearchError::TexNotFound(uri.clone()));
+    };
+
+    if let Some((project, manifest)) = workspace.tectonic(child) {
+        let Ok(dir) = project.dir.to_file_path() else {
+            return Err(ForwardSearchError::NotLocal(project.uri.clone()));
+        };
+
+        let Some(output) = manifest.outputs.iter().find(|output| output.kind == "pdf") else {
+            return Err(ForwardSearchError::InvalidPath(project.uri.clone()));
+        };
+
+        let pdf_path = dir
+            .join(output.build_dir())
+            .join(format!("{}.pdf", output.name));
+
+        if !pdf_path.exists() {
+            return Err(ForwardSearchError::PdfNotFound(pdf_path));
+        }
+
+        return Ok((child, pdf_path));
+    }
+
+    let parents = workspace.parents(child);
+    let parent = parents.into_iter().next().unwrap_or(child);
+    if parent.uri.scheme() != "file" {
+        return Err(ForwardSearchError::NotLocal(parent.uri.clone()));
+    }
+
+    let dir = workspace.current_dir(&parent.dir);
+    let dir = workspace.output_dir(&dir).to_file_path().unwrap();
+
+    let Some(pdf_path) = parent.path
+        .as_deref()
+        .and_then(Path::file_stem)
+        .and_then(OsStr::to_str)
+        .map(|stem| dir.join(format!("{stem}.pdf"))) else
+    {
+        return Err(ForwardSearchError::InvalidPath(parent.uri.clone()));
+    };
+
+    if !pdf_path.exists() {
+        return Err(ForwardSearchError::PdfNotFound(pdf_path));
+    }
+
+    Ok((child, pdf_path))
+}
+
 impl ForwardSearch {
     pub fn run(self) -> Result<(), ForwardSearchError> {
         log::debug!("Executing forward search: {} {:?}", self.program, self.args);
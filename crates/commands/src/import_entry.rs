@@ -0,0 +1,590 @@
+use std::{
+    fmt,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use citeproc::field::{
+    text::{is_valid_isbn, strip_doi_prefix},
+    FieldError, FieldErrorKind,
+};
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+use rowan::{ast::AstNode, TextRange};
+use rustc_hash::FxHashSet;
+use syntax::bibtex::{self, HasName, HasValue};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ImportError {
+    #[error("\"{0}\" is not a DOI, arXiv identifier or ISBN")]
+    InvalidId(String),
+
+    #[error("No resolver is configured for {0}")]
+    Unconfigured(EntryId),
+
+    #[error("No entry found for {0}")]
+    NotFound(EntryId),
+
+    #[error("Unable to fetch {0}: {1}")]
+    Http(String, String),
+
+    #[error("Unable to fetch entries because curl is not installed")]
+    CurlNotFound,
+
+    #[error("Unable to read the entry database: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Unable to parse the entry database: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// The identifier of a publication that can be imported as a BibTeX entry.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum EntryId {
+    Doi(String),
+    Arxiv(String),
+    Isbn(String),
+}
+
+impl EntryId {
+    /// Parses a DOI (`10.1145/359340.359342`), an arXiv identifier (`2101.00001`, `hep-th/9901001`)
+    /// or an ISBN. Common prefixes like `doi:`, `arXiv:` or `https://doi.org/` are accepted.
+    pub fn parse(input: &str) -> Result<Self, ImportError> {
+        let text = input.trim();
+        let lower = text.to_ascii_lowercase();
+
+        if let Some(doi) = strip_doi_prefix(text) {
+            return Self::parse_doi(doi).ok_or_else(|| ImportError::InvalidId(input.into()));
+        }
+
+        for prefix in ["https://arxiv.org/abs/", "http://arxiv.org/abs/", "arxiv:"] {
+            if lower.starts_with(prefix) {
+                return Self::parse_arxiv(text[prefix.len()..].trim_start())
+                    .ok_or_else(|| ImportError::InvalidId(input.into()));
+            }
+        }
+
+        if lower.starts_with("isbn") {
+            let text = text[4..].trim_start_matches([':', ' ', '-']);
+            return Self::parse_isbn(text).ok_or_else(|| ImportError::InvalidId(input.into()));
+        }
+
+        Self::parse_doi(text)
+            .or_else(|| Self::parse_arxiv(text))
+            .or_else(|| Self::parse_isbn(text))
+            .ok_or_else(|| ImportError::InvalidId(input.into()))
+    }
+
+    fn parse_doi(text: &str) -> Option<Self> {
+        let (prefix, suffix) = text.split_once('/')?;
+        let registrant = prefix.strip_prefix("10.")?;
+        let valid = !registrant.is_empty()
+            && registrant.chars().all(|c| c.is_ascii_digit() || c == '.')
+            && !suffix.is_empty()
+            && !suffix.contains(char::is_whitespace);
+
+        valid.then(|| Self::Doi(text.into()))
+    }
+
+    fn parse_arxiv(text: &str) -> Option<Self> {
+        let id = text.rsplit_once('v').map_or(text, |(id, version)| {
+            if !version.is_empty() && version.chars().all(|c| c.is_ascii_digit()) {
+                id
+            } else {
+                text
+            }
+        });
+
+        let valid = match id.split_once('/') {
+            Some((archive, number)) => {
+                !archive.is_empty()
+                    && archive
+                        .chars()
+                        .all(|c| c.is_ascii_alphabetic() || c == '-' || c == '.')
+                    && number.len() == 7
+                    && number.chars().all(|c| c.is_ascii_digit())
+            }
+            None => id.split_once('.').map_or(false, |(month, number)| {
+                month.len() == 4
+                    && (4..=5).contains(&number.len())
+                    && month
+                        .chars()
+                        .chain(number.chars())
+                        .all(|c| c.is_ascii_digit())
+            }),
+        };
+
+        valid.then(|| Self::Arxiv(text.into()))
+    }
+
+    fn parse_isbn(text: &str) -> Option<Self> {
+        is_valid_isbn(text).then(|| Self::Isbn(text.into()))
+    }
+
+    /// The kind of the identifier (`doi`, `arxiv` or `isbn`).
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Doi(_) => "doi",
+            Self::Arxiv(_) => "arxiv",
+            Self::Isbn(_) => "isbn",
+        }
+    }
+
+    pub fn value(&self) -> &str {
+        match self {
+            Self::Doi(value) | Self::Arxiv(value) | Self::Isbn(value) => value,
+        }
+    }
+
+    /// Checks if a field of a BibTeX entry refers to this identifier.
+    fn matches(&self, field: &str, value: &str) -> bool {
+        let field = field.to_ascii_lowercase();
+        match self {
+            Self::Doi(doi) => field == "doi" && value.trim().eq_ignore_ascii_case(doi),
+            Self::Arxiv(id) => {
+                matches!(field.as_str(), "eprint" | "arxiv" | "arxivid")
+                    && value.trim().trim_start_matches("arXiv:") == id
+            }
+            Self::Isbn(isbn) => {
+                let normalize = |text: &str| {
+                    text.chars()
+                        .filter(|c| c.is_ascii_alphanumeric())
+                        .map(|c| c.to_ascii_uppercase())
+                        .collect::<String>()
+                };
+
+                field == "isbn" && normalize(value) == normalize(isbn)
+            }
+        }
+    }
+}
+
+impl fmt::Display for EntryId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Doi(doi) => write!(f, "DOI {doi}"),
+            Self::Arxiv(id) => write!(f, "arXiv:{id}"),
+            Self::Isbn(isbn) => write!(f, "ISBN {isbn}"),
+        }
+    }
+}
+
+/// Looks up the BibTeX source of a publication.
+pub trait EntryResolver: Send + Sync {
+    fn resolve(&self, id: &EntryId) -> Result<String, ImportError>;
+}
+
+/// Fetches entries from a web service using `curl` and content negotiation
+/// (`Accept: application/x-bibtex`).
+///
+/// The endpoint may contain the placeholders `{id}` and `{kind}`. Without an endpoint,
+/// DOIs and arXiv identifiers are resolved through `https://doi.org`.
+#[derive(Debug, Clone)]
+pub struct HttpResolver {
+    endpoint: Option<String>,
+}
+
+impl HttpResolver {
+    pub fn new(endpoint: Option<String>) -> Self {
+        Self { endpoint }
+    }
+
+    fn url(&self, id: &EntryId) -> Option<String> {
+        let encode = |text| utf8_percent_encode(text, ID_ENCODE_SET);
+        match (&self.endpoint, id) {
+            (Some(endpoint), _) => Some(
+                endpoint
+                    .replace("{id}", &encode(id.value()).to_string())
+                    .replace("{kind}", id.kind()),
+            ),
+            (None, EntryId::Doi(doi)) => Some(format!("https://doi.org/{}", encode(doi))),
+            (None, EntryId::Arxiv(arxiv)) => {
+                Some(format!("https://doi.org/10.48550/arXiv.{}", encode(arxiv)))
+            }
+            (None, EntryId::Isbn(_)) => None,
+        }
+    }
+}
+
+/// The characters of an identifier that are percent-encoded when it is inserted into a URL.
+/// Slashes are kept because they separate the prefix and the suffix of a DOI.
+const ID_ENCODE_SET: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'&')
+    .add(b'+')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'[')
+    .add(b']')
+    .add(b'\\')
+    .add(b'^')
+    .add(b'`')
+    .add(b'{')
+    .add(b'|')
+    .add(b'}');
+
+/// The number of seconds to wait for a connection to the web service.
+const CONNECT_TIMEOUT: &str = "10";
+
+/// The number of seconds after which a request is aborted.
+const MAX_TIME: &str = "30";
+
+impl EntryResolver for HttpResolver {
+    fn resolve(&self, id: &EntryId) -> Result<String, ImportError> {
+        let Some(url) = self.url(id) else {
+            return Err(ImportError::Unconfigured(id.clone()));
+        };
+
+        log::info!("Fetching {id} from {url}");
+        let output = Command::new("curl")
+            .args(["--silent", "--show-error", "--fail", "--location"])
+            .args(["--connect-timeout", CONNECT_TIMEOUT])
+            .args(["--max-time", MAX_TIME])
+            .args(["--header", "Accept: application/x-bibtex"])
+            .arg(&url)
+            .output()
+            .map_err(|why| match why.kind() {
+                std::io::ErrorKind::NotFound => ImportError::CurlNotFound,
+                _ => ImportError::Io(why),
+            })?;
+
+        if !output.status.success() {
+            let message = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            return Err(ImportError::Http(url, message));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+}
+
+/// Looks up entries in a local BibTeX file or in a JSON file containing an array of entries
+/// with the fields as properties and the special properties `ENTRYTYPE` and `ID`
+/// (the format written by `bibtexparser`).
+#[derive(Debug, Clone)]
+pub struct DatabaseResolver {
+    path: PathBuf,
+}
+
+impl DatabaseResolver {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    fn resolve_bibtex(&self, id: &EntryId, text: &str) -> Option<String> {
+        let root = bibtex::Root::cast(bibtex::SyntaxNode::new_root(parser::parse_bibtex(text)))?;
+        let entry = root.entries().find(|entry| {
+            entry.fields().any(|field| {
+                field
+                    .name_token()
+                    .zip(field.value())
+                    .map_or(false, |(name, value)| {
+                        let value = value.syntax().to_string();
+                        let value = value.trim_matches(|c| matches!(c, '{' | '}' | '"'));
+                        id.matches(name.text(), value)
+                    })
+            })
+        })?;
+
+        Some(entry.syntax().to_string())
+    }
+
+    fn resolve_json(&self, id: &EntryId, text: &str) -> Result<Option<String>, ImportError> {
+        let entries: Vec<serde_json::Map<String, serde_json::Value>> = serde_json::from_str(text)?;
+        let entry = entries.into_iter().find(|entry| {
+            entry.iter().any(|(name, value)| {
+                value
+                    .as_str()
+                    .map_or(false, |value| id.matches(name, value))
+            })
+        });
+
+        Ok(entry.map(|entry| {
+            let kind = entry.get("ENTRYTYPE").and_then(|value| value.as_str());
+            let key = entry.get("ID").and_then(|value| value.as_str());
+            let mut output = format!("@{}{{{},\n", kind.unwrap_or("misc"), key.unwrap_or(""));
+            for (name, value) in &entry {
+                if let Some(value) = value
+                    .as_str()
+                    .filter(|_| name != "ENTRYTYPE" && name != "ID")
+                {
+                    output.push_str(&format!("  {name} = {{{value}}},\n"));
+                }
+            }
+
+            output.push('}');
+            output
+        }))
+    }
+}
+
+impl EntryResolver for DatabaseResolver {
+    fn resolve(&self, id: &EntryId) -> Result<String, ImportError> {
+        let text = std::fs::read_to_string(&self.path)?;
+        let is_json = self
+            .path
+            .extension()
+            .map_or(false, |ext| ext.eq_ignore_ascii_case("json"));
+
+        let entry = if is_json {
+            self.resolve_json(id, &text)?
+        } else {
+            self.resolve_bibtex(id, &text)
+        };
+
+        entry.ok_or_else(|| ImportError::NotFound(id.clone()))
+    }
+}
+
+/// Returns the resolver that is configured by the user, i.e. a local database
+/// if there is one and a web service otherwise.
+pub fn entry_resolver(database: Option<&Path>, endpoint: Option<&str>) -> Box<dyn EntryResolver> {
+    match database {
+        Some(path) => Box::new(DatabaseResolver::new(path)),
+        None => Box::new(HttpResolver::new(endpoint.map(String::from))),
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ImportedEntry {
+    pub key: String,
+    pub text: String,
+}
+
+/// Resolves an entry and assigns a key that is not part of `existing_keys`.
+/// The key proposed by the resolver is kept if possible; a suffix (`a`, `b`, ...)
+/// is appended to resolve collisions. Page ranges like `12-15` are rewritten to `12--15`.
+pub fn import_entry(
+    id: &EntryId,
+    resolver: &dyn EntryResolver,
+    existing_keys: &FxHashSet<String>,
+) -> Result<ImportedEntry, ImportError> {
+    let text = resolver.resolve(id)?;
+    let root = bibtex::Root::cast(bibtex::SyntaxNode::new_root(parser::parse_bibtex(&text)))
+        .ok_or_else(|| ImportError::NotFound(id.clone()))?;
+
+    let entry = root
+        .entries()
+        .next()
+        .ok_or_else(|| ImportError::NotFound(id.clone()))?;
+
+    let start = entry.syntax().text_range().start();
+    let mut entry_text = entry.syntax().to_string();
+    let fields: Vec<_> = entry.fields().collect();
+    for (range, pages) in fields.iter().rev().filter_map(normalize_pages) {
+        let range = range - start;
+        entry_text.replace_range(std::ops::Range::<usize>::from(range), &pages);
+    }
+
+    let proposed: String = entry
+        .name_token()
+        .map(|token| token.text().to_string())
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || "_-:.".contains(*c))
+        .collect();
+
+    let base = if proposed.is_empty() {
+        format!("{}{}", id.kind(), id.value())
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .collect()
+    } else {
+        proposed
+    };
+
+    let key = unique_key(&base, existing_keys);
+    let text = match entry.name_token() {
+        Some(name) => {
+            let range: TextRange = name.text_range() - start;
+            let mut text = entry_text;
+            text.replace_range(std::ops::Range::<usize>::from(range), &key);
+            text
+        }
+        None => {
+            let brace = entry_text
+                .find(['{', '('])
+                .map_or(entry_text.len(), |i| i + 1);
+            let (head, tail) = entry_text.split_at(brace);
+            let comma = if tail.trim_start().starts_with(',') {
+                ""
+            } else {
+                ","
+            };
+            format!("{head}{key}{comma}{tail}")
+        }
+    };
+
+    Ok(ImportedEntry { key, text })
+}
+
+/// Returns the range of the value of a `pages` field and the value with ranges like `12--15`
+/// if it contains ranges that are separated by a single hyphen or a dash.
+fn normalize_pages(field: &bibtex::Field) -> Option<(TextRange, String)> {
+    if !field.name_token()?.text().eq_ignore_ascii_case("pages") {
+        return None;
+    }
+
+    match citeproc::check_field(field) {
+        Err(FieldError {
+            range,
+            kind: FieldErrorKind::InvalidPageRange(pages),
+        }) => Some((range, pages)),
+        _ => None,
+    }
+}
+
+/// Appends the suffixes `a`, `b`, ..., `z`, `aa`, `ab`, ... to `base` until the key is unique.
+pub fn unique_key(base: &str, existing_keys: &FxHashSet<String>) -> String {
+    if !existing_keys.contains(base) {
+        return base.into();
+    }
+
+    (0..)
+        .map(|mut index: usize| {
+            let mut suffix = Vec::new();
+            loop {
+                suffix.push(b'a' + (index % 26) as u8);
+                if index < 26 {
+                    break;
+                }
+
+                index = index / 26 - 1;
+            }
+
+            suffix.reverse();
+            format!("{base}{}", String::from_utf8(suffix).unwrap())
+        })
+        .find(|key| !existing_keys.contains(key))
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use rustc_hash::FxHashSet;
+
+    use super::{
+        import_entry, unique_key, DatabaseResolver, EntryId, EntryResolver, HttpResolver,
+        ImportError,
+    };
+
+    struct StaticResolver(&'static str);
+
+    impl EntryResolver for StaticResolver {
+        fn resolve(&self, _id: &EntryId) -> Result<String, ImportError> {
+            Ok(self.0.into())
+        }
+    }
+
+    #[test]
+    fn test_parse_id() {
+        let parse = |text| EntryId::parse(text).ok();
+        let doi = Some(EntryId::Doi("10.1145/359340.359342".into()));
+        assert_eq!(parse("10.1145/359340.359342"), doi);
+        assert_eq!(parse("https://doi.org/10.1145/359340.359342"), doi);
+        assert_eq!(parse("doi: 10.1145/359340.359342"), doi);
+
+        let arxiv = Some(EntryId::Arxiv("2101.00001v2".into()));
+        assert_eq!(parse("arXiv:2101.00001v2"), arxiv);
+        assert_eq!(parse("2101.00001v2"), arxiv);
+        assert_eq!(
+            parse("hep-th/9901001"),
+            Some(EntryId::Arxiv("hep-th/9901001".into()))
+        );
+
+        assert_eq!(
+            parse("ISBN 978-0-201-89683-1"),
+            Some(EntryId::Isbn("978-0-201-89683-1".into()))
+        );
+
+        assert_eq!(
+            parse("0-201-89683-4"),
+            Some(EntryId::Isbn("0-201-89683-4".into()))
+        );
+        assert_eq!(parse("978-0-201-89683-2"), None);
+        assert_eq!(parse("foo"), None);
+    }
+
+    #[test]
+    fn test_unique_key() {
+        let keys: FxHashSet<_> = ["foo", "fooa", "bar"]
+            .into_iter()
+            .map(String::from)
+            .collect();
+        assert_eq!(unique_key("baz", &keys), "baz");
+        assert_eq!(unique_key("foo", &keys), "foob");
+        assert_eq!(unique_key("bar", &keys), "bara");
+    }
+
+    #[test]
+    fn test_import_entry_collision() {
+        let resolver = StaticResolver("@article{Rivest_1978, title={A Method}, year={1978}}\n");
+        let id = EntryId::Doi("10.1145/359340.359342".into());
+        let keys = std::iter::once(String::from("Rivest_1978")).collect();
+        let entry = import_entry(&id, &resolver, &keys).unwrap();
+        assert_eq!(entry.key, "Rivest_1978a");
+        assert_eq!(
+            entry.text,
+            "@article{Rivest_1978a, title={A Method}, year={1978}}"
+        );
+    }
+
+    #[test]
+    fn test_import_entry_without_key() {
+        let resolver = StaticResolver("@book{, title = {Foo}}");
+        let id = EntryId::Isbn("0-201-89683-4".into());
+        let entry = import_entry(&id, &resolver, &FxHashSet::default()).unwrap();
+        assert_eq!(entry.key, "isbn0201896834");
+        assert_eq!(entry.text, "@book{isbn0201896834, title = {Foo}}");
+    }
+
+    #[test]
+    fn test_import_entry_pages() {
+        let resolver = StaticResolver("@article{foo, pages = {12-15}, title = {Foo}}");
+        let id = EntryId::Doi("10.1000/182".into());
+        let entry = import_entry(&id, &resolver, &FxHashSet::default()).unwrap();
+        assert_eq!(entry.text, "@article{foo, pages = {12--15}, title = {Foo}}");
+    }
+
+    #[test]
+    fn test_http_url() {
+        let doi = EntryId::Doi("10.1002/(SICI)1097-4571<693::AID>3.0.CO;2-0#x?y".into());
+        assert_eq!(
+            HttpResolver::new(None).url(&doi).as_deref(),
+            Some("https://doi.org/10.1002/(SICI)1097-4571%3C693::AID%3E3.0.CO;2-0%23x%3Fy")
+        );
+
+        let isbn = EntryId::Isbn("978 0 201 89683 1".into());
+        let resolver = HttpResolver::new(Some("https://example.com/?q={id}&type={kind}".into()));
+        assert_eq!(
+            resolver.url(&isbn).as_deref(),
+            Some("https://example.com/?q=978%200%20201%2089683%201&type=isbn")
+        );
+    }
+
+    #[test]
+    fn test_database_bibtex() {
+        let resolver = DatabaseResolver::new("refs.bib");
+        let text = "@book{a, isbn = {0201896834}}\n@book{b, isbn = {978-0-201-89683-1}}";
+        let id = EntryId::Isbn("9780201896831".into());
+        assert_eq!(
+            resolver.resolve_bibtex(&id, text).as_deref(),
+            Some("@book{b, isbn = {978-0-201-89683-1}}")
+        );
+    }
+
+    #[test]
+    fn test_database_json() {
+        let resolver = DatabaseResolver::new("refs.json");
+        let text =
+            r#"[{"ENTRYTYPE": "article", "ID": "foo", "doi": "10.1000/XYZ", "title": "Foo"}]"#;
+        let id = EntryId::Doi("10.1000/xyz".into());
+        assert_eq!(
+            resolver.resolve_json(&id, text).unwrap().as_deref(),
+            Some("@article{foo,\n  doi = {10.1000/XYZ},\n  title = {Foo},\n}")
+        );
+    }
+}
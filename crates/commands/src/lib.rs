@@ -1,14 +1,28 @@
+mod bib_entries;
 mod build;
 mod change_env;
 mod clean;
 mod dep_graph;
 mod fwd_search;
+mod import_entry;
 mod placeholders;
+mod synctex;
 
 pub use self::{
+    bib_entries::{
+        find_duplicates, merge_entries, sort_entries, DuplicateEntry, EntryEdit, MergeError,
+        SortOrder,
+    },
     build::{BuildCommand, BuildError},
     change_env::{change_environment, ChangeEnvironmentResult},
     clean::{CleanCommand, CleanTarget},
     dep_graph::show_dependency_graph,
     fwd_search::{ForwardSearch, ForwardSearchError},
+    import_entry::{
+        entry_resolver, import_entry, unique_key, DatabaseResolver, EntryId, EntryResolver,
+        HttpResolver, ImportError, ImportedEntry,
+    },
+    synctex::{
+        synctex_forward, synctex_inverse, Synctex, SynctexError, SynctexRect, SynctexSource,
+    },
 };
@@ -0,0 +1,394 @@
+use std::{
+    io::Read,
+    path::{Component, Path, PathBuf},
+};
+
+use base_db::Workspace;
+use flate2::read::GzDecoder;
+use rustc_hash::FxHashMap;
+use thiserror::Error;
+use url::Url;
+
+use crate::fwd_search::{find_pdf, ForwardSearchError};
+
+/// Number of scaled points in a PostScript point.
+const SP_PER_BP: f64 = 65781.76;
+
+#[derive(Debug, Error)]
+pub enum SynctexError {
+    #[error(transparent)]
+    Pdf(#[from] ForwardSearchError),
+
+    #[error("SyncTeX file for \"{0}\" not found")]
+    NotFound(PathBuf),
+
+    #[error("Unable to read SyncTeX file: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("SyncTeX file \"{0}\" is malformed")]
+    Malformed(PathBuf),
+}
+
+/// A rectangle on a PDF page in PostScript points.
+/// The origin is the top-left corner of the page.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct SynctexRect {
+    pub page: u32,
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SynctexSource {
+    pub path: PathBuf,
+
+    /// The zero-based line number.
+    pub line: u32,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum RecordKind {
+    Box,
+    Point,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+struct Record {
+    kind: RecordKind,
+    page: u32,
+    tag: u32,
+    line: u32,
+    h: i64,
+    v: i64,
+    width: i64,
+    height: i64,
+    depth: i64,
+}
+
+#[derive(Debug)]
+pub struct Synctex {
+    dir: PathBuf,
+    inputs: FxHashMap<u32, PathBuf>,
+    records: Vec<Record>,
+    magnification: f64,
+    unit: f64,
+    x_offset: f64,
+    y_offset: f64,
+}
+
+impl Synctex {
+    /// Loads the SyncTeX file that belongs to the given PDF file.
+    /// Both compressed (`.synctex.gz`) and uncompressed (`.synctex`) files are supported.
+    pub fn load(pdf_path: &Path) -> Result<Self, SynctexError> {
+        let compressed_path = pdf_path.with_extension("synctex.gz");
+        let plain_path = pdf_path.with_extension("synctex");
+
+        let mut text = String::new();
+        let path = if compressed_path.exists() {
+            let file = std::fs::File::open(&compressed_path)?;
+            GzDecoder::new(file).read_to_string(&mut text)?;
+            compressed_path
+        } else if plain_path.exists() {
+            text = std::fs::read_to_string(&plain_path)?;
+            plain_path
+        } else {
+            return Err(SynctexError::NotFound(pdf_path.to_path_buf()));
+        };
+
+        let dir = pdf_path.parent().unwrap_or(Path::new("")).to_path_buf();
+        Self::parse(&text, dir).ok_or(SynctexError::Malformed(path))
+    }
+
+    /// Parses the contents of an uncompressed SyncTeX file.
+    /// Relative input paths are resolved against `dir`.
+    pub fn parse(text: &str, dir: PathBuf) -> Option<Self> {
+        let mut lines = text.lines();
+        if !lines.next()?.starts_with("SyncTeX Version:") {
+            return None;
+        }
+
+        let mut synctex = Self {
+            dir,
+            inputs: FxHashMap::default(),
+            records: Vec::new(),
+            magnification: 1000.0,
+            unit: 1.0,
+            x_offset: 0.0,
+            y_offset: 0.0,
+        };
+
+        let mut page = None;
+        for line in lines {
+            if let Some(input) = line.strip_prefix("Input:") {
+                let (tag, path) = input.split_once(':')?;
+                let path = synctex.dir.join(path);
+                synctex.inputs.insert(tag.parse().ok()?, normalize(&path));
+            } else if let Some(value) = line.strip_prefix("Magnification:") {
+                synctex.magnification = value.trim().parse().ok()?;
+            } else if let Some(value) = line.strip_prefix("Unit:") {
+                synctex.unit = value.trim().parse().ok()?;
+            } else if let Some(value) = line.strip_prefix("X Offset:") {
+                synctex.x_offset = value.trim().parse().ok()?;
+            } else if let Some(value) = line.strip_prefix("Y Offset:") {
+                synctex.y_offset = value.trim().parse().ok()?;
+            } else if line.starts_with("Postamble:") {
+                break;
+            } else if let Some(number) = line.strip_prefix('{') {
+                page = number.parse().ok();
+            } else if line.starts_with('}') {
+                page = None;
+            } else if let Some(page) = page {
+                if let Some(record) = parse_record(line, page) {
+                    synctex.records.push(record);
+                }
+            }
+        }
+
+        Some(synctex)
+    }
+
+    /// Finds the area of the PDF that was produced by the given line of a TeX file.
+    pub fn forward(&self, tex_path: &Path, line: u32) -> Option<SynctexRect> {
+        let tex_path = normalize(tex_path);
+        let tag = self
+            .inputs
+            .iter()
+            .find(|(_, path)| **path == tex_path)
+            .map(|(tag, _)| *tag)?;
+
+        let line = line + 1;
+        let records = || self.records.iter().filter(|record| record.tag == tag);
+        let best_line = records()
+            .map(|record| record.line)
+            .filter(|&other| other >= line)
+            .min()
+            .or_else(|| records().map(|record| record.line).max())?;
+
+        let page = records()
+            .filter(|record| record.line == best_line)
+            .map(|record| record.page)
+            .min()?;
+
+        let (left, top, right, bottom) = records()
+            .filter(|record| record.line == best_line && record.page == page)
+            .map(|record| self.bounds(record))
+            .reduce(|a, b| (a.0.min(b.0), a.1.min(b.1), a.2.max(b.2), a.3.max(b.3)))?;
+
+        Some(SynctexRect {
+            page,
+            x: left,
+            y: top,
+            width: right - left,
+            height: bottom - top,
+        })
+    }
+
+    /// Finds the TeX source location that produced the given point of a PDF page.
+    pub fn inverse(&self, page: u32, x: f64, y: f64) -> Option<SynctexSource> {
+        let records = || {
+            self.records
+                .iter()
+                .filter(move |record| record.page == page && record.line > 0)
+                .filter(|record| self.inputs.contains_key(&record.tag))
+        };
+
+        let area = |record: &Record| {
+            let (left, top, right, bottom) = self.bounds(record);
+            (right - left) * (bottom - top)
+        };
+
+        let distance = |record: &Record| {
+            let (left, top, right, bottom) = self.bounds(record);
+            let dx = (left - x).max(x - right).max(0.0);
+            let dy = (top - y).max(y - bottom).max(0.0);
+            dx * dx + dy * dy
+        };
+
+        let record = records()
+            .filter(|record| record.kind == RecordKind::Box && distance(record) == 0.0)
+            .min_by(|a, b| area(a).total_cmp(&area(b)))
+            .or_else(|| records().min_by(|a, b| distance(a).total_cmp(&distance(b))))?;
+
+        Some(SynctexSource {
+            path: self.inputs[&record.tag].clone(),
+            line: record.line - 1,
+        })
+    }
+
+    fn bounds(&self, record: &Record) -> (f64, f64, f64, f64) {
+        let left = self.to_bp(record.h, self.x_offset);
+        let baseline = self.to_bp(record.v, self.y_offset);
+        let right = left + self.to_bp(record.width, 0.0);
+        let top = baseline - self.to_bp(record.height, 0.0);
+        let bottom = baseline + self.to_bp(record.depth, 0.0);
+        (left, top, right, bottom)
+    }
+
+    fn to_bp(&self, value: i64, offset: f64) -> f64 {
+        (value as f64 * self.unit + offset) * self.magnification / 1000.0 / SP_PER_BP
+    }
+}
+
+fn parse_record(line: &str, page: u32) -> Option<Record> {
+    let mut chars = line.chars();
+    let kind = match chars.next()? {
+        '[' | '(' | 'v' | 'h' => RecordKind::Box,
+        'x' | 'k' | 'g' | '$' => RecordKind::Point,
+        _ => return None,
+    };
+
+    let mut parts = chars.as_str().split(':');
+    let mut link = parts.next()?.split(',');
+    let tag = link.next()?.parse().ok()?;
+    let line = link.next()?.parse().ok()?;
+
+    let (h, v) = parts.next()?.split_once(',')?;
+    let h = h.parse().ok()?;
+    let v = v.parse().ok()?;
+
+    let mut size = parts
+        .next()
+        .unwrap_or_default()
+        .split(',')
+        .map(|value| value.parse().unwrap_or(0));
+
+    let width = size.next().unwrap_or(0);
+    let (height, depth) = match kind {
+        RecordKind::Box => (size.next().unwrap_or(0), size.next().unwrap_or(0)),
+        RecordKind::Point => (0, 0),
+    };
+
+    Some(Record {
+        kind,
+        page,
+        tag,
+        line,
+        h,
+        v,
+        width,
+        height,
+        depth,
+    })
+}
+
+fn normalize(path: &Path) -> PathBuf {
+    let mut result = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                result.pop();
+            }
+            _ => result.push(component),
+        };
+    }
+
+    result
+}
+
+/// Maps a line of a TeX document to the PDF file of its project.
+/// Returns the path of the PDF file and the area on the matching page.
+pub fn synctex_forward(
+    workspace: &Workspace,
+    uri: &Url,
+    line: u32,
+) -> Result<Option<(PathBuf, SynctexRect)>, SynctexError> {
+    let (child, pdf_path) = find_pdf(workspace, uri)?;
+    let Some(tex_path) = &child.path else {
+        return Err(ForwardSearchError::InvalidPath(child.uri.clone()).into());
+    };
+
+    let synctex = Synctex::load(&pdf_path)?;
+    Ok(synctex.forward(tex_path, line).map(|rect| (pdf_path, rect)))
+}
+
+pub fn synctex_inverse(
+    pdf_path: &Path,
+    page: u32,
+    x: f64,
+    y: f64,
+) -> Result<Option<SynctexSource>, SynctexError> {
+    let synctex = Synctex::load(pdf_path)?;
+    Ok(synctex.inverse(page, x, y))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use super::{Synctex, SynctexSource};
+
+    const TEXT: &str = r#"SyncTeX Version:1
+Input:1:/project/./main.tex
+Input:2:/project/chapter.tex
+Output:pdf
+Magnification:1000
+Unit:1
+X Offset:0
+Y Offset:0
+Content:
+!100
+{1
+[1,3:4736286,45873531:26851483,41279922,0
+(1,5:4736286,6736286:26851483,655359,0
+x1,5:4736286,6736286
+)
+(2,1:4736286,8736286:13107200,655359,0
+g2,1:5736286,8736286
+)
+]
+}1
+{2
+[1,7:4736286,45873531:26851483,41279922,0
+(1,7:4736286,4736286:26851483,655359,0
+)
+]
+}2
+Postamble:
+Count:10
+"#;
+
+    fn parse() -> Synctex {
+        Synctex::parse(TEXT, PathBuf::from("/project")).unwrap()
+    }
+
+    #[test]
+    fn test_forward() {
+        let rect = parse().forward(Path::new("/project/main.tex"), 4).unwrap();
+        assert_eq!(rect.page, 1);
+        assert!((rect.x - 72.0).abs() < 0.01);
+        assert!((rect.width - 408.19).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_forward_next_line() {
+        let rect = parse().forward(Path::new("/project/main.tex"), 5).unwrap();
+        assert_eq!(rect.page, 2);
+    }
+
+    #[test]
+    fn test_forward_unknown_file() {
+        assert_eq!(parse().forward(Path::new("/project/other.tex"), 0), None);
+    }
+
+    #[test]
+    fn test_inverse() {
+        let source = parse().inverse(1, 100.0, 130.0).unwrap();
+        assert_eq!(
+            source,
+            SynctexSource {
+                path: PathBuf::from("/project/chapter.tex"),
+                line: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_inverse_enclosing_box() {
+        let source = parse().inverse(2, 100.0, 75.0).unwrap();
+        assert_eq!(source.path, PathBuf::from("/project/main.tex"));
+        assert_eq!(source.line, 6);
+    }
+}
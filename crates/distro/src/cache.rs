@@ -0,0 +1,240 @@
+use std::{
+    env,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::{file_name_db::DistroFile, DistroKind, FileNameDB};
+
+/// Needs to be incremented whenever the format of the cache file changes.
+const CACHE_VERSION: u32 = 3;
+
+const CACHE_FILE_NAME: &str = "distro.json";
+
+/// Records the last modification of a file that the file name database depends on.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub(crate) struct Stamp {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+}
+
+impl Stamp {
+    pub fn new(path: PathBuf) -> Self {
+        let modified = std::fs::metadata(&path)
+            .and_then(|metadata| metadata.modified())
+            .ok();
+
+        Self { path, modified }
+    }
+}
+
+/// Identifies the installed distribution without running any of its programs.
+/// The root directories that `kpsewhich` reports depend on the executables
+/// and the `TEXMF*` environment variables.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub(crate) struct Installation {
+    executables: Vec<Stamp>,
+    variables: Vec<(String, String)>,
+}
+
+impl Installation {
+    /// Returns `None` if `latex` or `kpsewhich` cannot be found in the `PATH`.
+    pub fn detect() -> Option<Self> {
+        let executables = ["latex", "kpsewhich"]
+            .into_iter()
+            .map(|name| find_executable(name).map(Stamp::new))
+            .collect::<Option<_>>()?;
+
+        let mut variables: Vec<_> = env::vars()
+            .filter(|(name, _)| name.starts_with("TEXMF"))
+            .collect();
+
+        variables.sort();
+        Some(Self {
+            executables,
+            variables,
+        })
+    }
+}
+
+fn find_executable(name: &str) -> Option<PathBuf> {
+    let file_name = format!("{name}{}", env::consts::EXE_SUFFIX);
+    env::split_paths(&env::var_os("PATH")?)
+        .map(|dir| dir.join(&file_name))
+        .find(|path| path.is_file())
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct CacheFile {
+    version: u32,
+    installation: Installation,
+    pub kind: DistroKind,
+    pub root_dirs: Vec<PathBuf>,
+    pub config_files: Vec<PathBuf>,
+    stamps: Vec<Stamp>,
+    files: Vec<PathBuf>,
+}
+
+impl CacheFile {
+    pub fn new(
+        installation: Installation,
+        kind: DistroKind,
+        root_dirs: Vec<PathBuf>,
+        config_files: Vec<PathBuf>,
+        stamps: Vec<Stamp>,
+        file_name_db: &FileNameDB,
+    ) -> Self {
+        Self {
+            version: CACHE_VERSION,
+            installation,
+            kind,
+            root_dirs,
+            config_files,
+            stamps,
+            files: file_name_db
+                .iter()
+                .map(|(_, path)| path.to_path_buf())
+                .collect(),
+        }
+    }
+
+    /// Checks if none of the database and configuration files have changed
+    /// since the cache was written.
+    pub fn is_valid(&self, stamps: &[Stamp]) -> bool {
+        self.stamps == stamps
+    }
+
+    pub fn into_file_name_db(self) -> FileNameDB {
+        let files = self.files.into_iter().map(DistroFile).collect();
+        FileNameDB { files }
+    }
+}
+
+/// Loads the cache if it has been written by this version of the server
+/// for the same `installation`.
+pub(crate) fn load(cache_dir: &Path, installation: &Installation) -> Option<CacheFile> {
+    let data = std::fs::read(cache_dir.join(CACHE_FILE_NAME)).ok()?;
+    let cache: CacheFile = serde_json::from_slice(&data).ok()?;
+    (cache.version == CACHE_VERSION && cache.installation == *installation).then_some(cache)
+}
+
+pub(crate) fn store(cache_dir: &Path, cache: &CacheFile) -> Result<()> {
+    std::fs::create_dir_all(cache_dir)?;
+    std::fs::write(cache_dir.join(CACHE_FILE_NAME), serde_json::to_vec(cache)?)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use crate::{database_stamps, file_name_db::DistroFile, DistroKind, FileNameDB};
+
+    use super::{load, store, CacheFile, Installation, Stamp, CACHE_FILE_NAME};
+
+    fn installation(dir: &Path) -> Installation {
+        Installation {
+            executables: vec![Stamp::new(dir.join("bin/latex"))],
+            variables: vec![("TEXMFHOME".into(), "~/texmf".into())],
+        }
+    }
+
+    fn setup(dir: &Path) -> (PathBuf, Vec<Stamp>) {
+        let root_dir = dir.join("texmf-dist");
+        std::fs::create_dir_all(root_dir.join("web2c")).unwrap();
+        std::fs::write(root_dir.join("ls-R"), "./tex/latex/foo:\nfoo.sty\n").unwrap();
+        std::fs::write(root_dir.join("web2c/texmf.cnf"), "TEXMF = {$TEXMFDIST}\n").unwrap();
+
+        let file_name_db = FileNameDB {
+            files: [DistroFile(root_dir.join("tex/latex/foo/foo.sty"))]
+                .into_iter()
+                .collect(),
+        };
+
+        let root_dirs = vec![root_dir.clone()];
+        let config_files = vec![root_dir.join("web2c/texmf.cnf")];
+        let stamps = database_stamps(DistroKind::Texlive, &root_dirs, &config_files);
+        let cache = CacheFile::new(
+            installation(dir),
+            DistroKind::Texlive,
+            root_dirs,
+            config_files,
+            stamps.clone(),
+            &file_name_db,
+        );
+
+        store(&dir.join("cache"), &cache).unwrap();
+        (root_dir, stamps)
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let (root_dir, stamps) = setup(dir.path());
+
+        let cache = load(&dir.path().join("cache"), &installation(dir.path())).unwrap();
+        assert_eq!(cache.kind, DistroKind::Texlive);
+        assert_eq!(cache.root_dirs, vec![root_dir.clone()]);
+        assert!(cache.is_valid(&stamps));
+
+        let file_name_db = cache.into_file_name_db();
+        assert_eq!(
+            file_name_db.get("foo.sty"),
+            Some(root_dir.join("tex/latex/foo/foo.sty").as_path())
+        );
+    }
+
+    #[test]
+    fn test_database_changed() {
+        let dir = tempfile::tempdir().unwrap();
+        let (root_dir, _) = setup(dir.path());
+
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        std::fs::write(root_dir.join("ls-R"), "./tex/latex/bar:\nbar.sty\n").unwrap();
+
+        let cache = load(&dir.path().join("cache"), &installation(dir.path())).unwrap();
+        let stamps = database_stamps(cache.kind, &cache.root_dirs, &cache.config_files);
+        assert!(!cache.is_valid(&stamps));
+    }
+
+    #[test]
+    fn test_config_file_changed() {
+        let dir = tempfile::tempdir().unwrap();
+        let (root_dir, _) = setup(dir.path());
+
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        let config_file = root_dir.join("web2c/texmf.cnf");
+        std::fs::write(&config_file, "TEXMF = {$TEXMFHOME,$TEXMFDIST}\n").unwrap();
+
+        let cache = load(&dir.path().join("cache"), &installation(dir.path())).unwrap();
+        let stamps = database_stamps(cache.kind, &cache.root_dirs, &cache.config_files);
+        assert!(!cache.is_valid(&stamps));
+    }
+
+    #[test]
+    fn test_installation_changed() {
+        let dir = tempfile::tempdir().unwrap();
+        setup(dir.path());
+
+        let mut other = installation(dir.path());
+        other.variables.clear();
+        assert!(load(&dir.path().join("cache"), &other).is_none());
+    }
+
+    #[test]
+    fn test_version_changed() {
+        let dir = tempfile::tempdir().unwrap();
+        setup(dir.path());
+
+        let path = dir.path().join("cache").join(CACHE_FILE_NAME);
+        let mut cache: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        cache["version"] = 1.into();
+        std::fs::write(&path, serde_json::to_vec(&cache).unwrap()).unwrap();
+
+        assert!(load(&dir.path().join("cache"), &installation(dir.path())).is_none());
+    }
+}
@@ -9,7 +9,7 @@ use rustc_hash::FxHashSet;
 use crate::Language;
 
 #[derive(Debug)]
-pub struct DistroFile(PathBuf);
+pub struct DistroFile(pub(crate) PathBuf);
 
 impl DistroFile {
     pub fn path(&self) -> &Path {
@@ -43,7 +43,7 @@ impl Borrow<str> for DistroFile {
 
 #[derive(Debug, Default)]
 pub struct FileNameDB {
-    files: FxHashSet<DistroFile>,
+    pub(crate) files: FxHashSet<DistroFile>,
 }
 
 impl FileNameDB {
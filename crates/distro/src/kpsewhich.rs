@@ -12,6 +12,21 @@ pub fn root_directories() -> Result<Vec<PathBuf>> {
     Ok(directories)
 }
 
+/// Returns all `texmf.cnf` files that are read by `kpsewhich`.
+/// They define the root directories unless the `TEXMF` variable is set in the environment.
+pub fn config_files() -> Result<Vec<PathBuf>> {
+    let output = Command::new("kpsewhich")
+        .args(["-all", "texmf.cnf"])
+        .output()?;
+
+    let files = String::from_utf8(output.stdout)?
+        .lines()
+        .map(PathBuf::from)
+        .collect();
+
+    Ok(files)
+}
+
 fn run(args: impl IntoIterator<Item = impl AsRef<OsStr>>) -> Result<String> {
     let output = Command::new("kpsewhich").args(args).output()?;
 
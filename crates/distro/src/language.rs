@@ -4,8 +4,11 @@ use std::path::Path;
 pub enum Language {
     Tex,
     Bib,
+    CslJson,
+    Hayagriva,
     Aux,
     Log,
+    Fls,
     Root,
     Tectonic,
 }
@@ -13,7 +16,10 @@ pub enum Language {
 impl Language {
     pub fn from_path(path: &Path) -> Option<Self> {
         let name = path.file_name()?;
-        if name.eq_ignore_ascii_case(".texlabroot") || name.eq_ignore_ascii_case("texlabroot") {
+        if name.eq_ignore_ascii_case(".texlabroot")
+            || name.eq_ignore_ascii_case("texlabroot")
+            || name.eq_ignore_ascii_case("texlab.toml")
+        {
             return Some(Self::Root);
         }
 
@@ -23,14 +29,27 @@ impl Language {
 
         let extname = path.extension()?.to_str()?;
         match extname.to_lowercase().as_str() {
-            "tex" | "sty" | "cls" | "def" | "lco" | "rnw" => Some(Self::Tex),
+            "tex" | "ltx" | "sty" | "cls" | "def" | "lco" | "rnw" => Some(Self::Tex),
             "bib" | "bibtex" => Some(Self::Bib),
             "aux" => Some(Self::Aux),
             "log" => Some(Self::Log),
+            "fls" => Some(Self::Fls),
             _ => None,
         }
     }
 
+    /// Determines the language of a file that is referenced as a bibliography.
+    /// In contrast to [`Language::from_path`], this also recognizes CSL-JSON and Hayagriva files,
+    /// which cannot be told apart from other JSON or YAML files by their name alone.
+    pub fn from_bibliography_path(path: &Path) -> Option<Self> {
+        let extname = path.extension()?.to_str()?;
+        match extname.to_lowercase().as_str() {
+            "json" => Some(Self::CslJson),
+            "yml" | "yaml" => Some(Self::Hayagriva),
+            _ => Self::from_path(path),
+        }
+    }
+
     pub fn from_id(id: &str) -> Option<Self> {
         match id {
             "tex" | "latex" => Some(Self::Tex),
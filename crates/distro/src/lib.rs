@@ -1,16 +1,23 @@
+mod cache;
 mod file_name_db;
 mod kpsewhich;
 mod language;
 mod miktex;
 mod texlive;
 
-use std::process::{Command, Stdio};
+use std::{
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
 
 use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use self::cache::{CacheFile, Installation, Stamp};
 
 pub use self::{file_name_db::FileNameDB, language::Language};
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
 pub enum DistroKind {
     Texlive,
     Miktex,
@@ -32,6 +39,31 @@ pub struct Distro {
 
 impl Distro {
     pub fn detect() -> Result<Self> {
+        Self::detect_impl(None)
+    }
+
+    /// Detects the installed distribution like [`Distro::detect`].
+    /// The file name database is cached in `cache_dir` together with the root directories
+    /// of the distribution. As long as the executables, the `TEXMF*` environment variables,
+    /// the `texmf.cnf` files and the database files do not change,
+    /// neither `latex` nor `kpsewhich` are run.
+    pub fn detect_cached(cache_dir: &Path) -> Result<Self> {
+        Self::detect_impl(Some(cache_dir))
+    }
+
+    fn detect_impl(cache_dir: Option<&Path>) -> Result<Self> {
+        let installation = cache_dir.and_then(|_| Installation::detect());
+        if let Some((cache_dir, installation)) = cache_dir.zip(installation.as_ref()) {
+            if let Some(cache) = cache::load(cache_dir, installation) {
+                let stamps = database_stamps(cache.kind, &cache.root_dirs, &cache.config_files);
+                if cache.is_valid(&stamps) {
+                    let kind = cache.kind;
+                    let file_name_db = cache.into_file_name_db();
+                    return Ok(Self { kind, file_name_db });
+                }
+            }
+        }
+
         let kind = match Command::new("latex").arg("--version").output() {
             Ok(output) => {
                 let stdout = String::from_utf8_lossy(&output.stdout);
@@ -58,18 +90,57 @@ impl Distro {
             }
         };
 
-        let file_name_db = match kind {
-            DistroKind::Texlive => {
-                let root_dirs = kpsewhich::root_directories()?;
-                FileNameDB::parse(&root_dirs, &mut texlive::read_database)?
+        let mut reader: ReadDatabase = match kind {
+            DistroKind::Texlive => texlive::read_database,
+            DistroKind::Miktex => miktex::read_database,
+            DistroKind::Tectonic | DistroKind::Unknown => {
+                let file_name_db = FileNameDB::default();
+                return Ok(Self { kind, file_name_db });
             }
-            DistroKind::Miktex => {
-                let root_dirs = kpsewhich::root_directories()?;
-                FileNameDB::parse(&root_dirs, &mut miktex::read_database)?
-            }
-            DistroKind::Tectonic | DistroKind::Unknown => FileNameDB::default(),
         };
 
+        let root_dirs = kpsewhich::root_directories()?;
+        let file_name_db = FileNameDB::parse(&root_dirs, &mut reader)?;
+        if let Some((cache_dir, installation)) = cache_dir.zip(installation) {
+            let config_files = kpsewhich::config_files().unwrap_or_default();
+            let stamps = database_stamps(kind, &root_dirs, &config_files);
+            let cache = CacheFile::new(
+                installation,
+                kind,
+                root_dirs,
+                config_files,
+                stamps,
+                &file_name_db,
+            );
+
+            if let Err(why) = cache::store(cache_dir, &cache) {
+                log::warn!("Unable to cache the file name database: {why}");
+            }
+        }
+
         Ok(Self { kind, file_name_db })
     }
 }
+
+/// Records the modification times of the root directories, their database files
+/// and the `texmf.cnf` files.
+fn database_stamps(
+    kind: DistroKind,
+    root_dirs: &[PathBuf],
+    config_files: &[PathBuf],
+) -> Vec<Stamp> {
+    let database_files = match kind {
+        DistroKind::Texlive => texlive::database_files,
+        DistroKind::Miktex => miktex::database_files,
+        DistroKind::Tectonic | DistroKind::Unknown => return Vec::new(),
+    };
+
+    root_dirs
+        .iter()
+        .flat_map(|dir| std::iter::once(dir.clone()).chain(database_files(dir)))
+        .chain(config_files.iter().cloned())
+        .map(Stamp::new)
+        .collect()
+}
+
+type ReadDatabase = fn(&Path) -> Result<Vec<PathBuf>>;
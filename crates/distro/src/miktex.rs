@@ -14,18 +14,20 @@ const FNDB_TABLE_POINTER_OFFSET: u32 = 4 * FNDB_WORD_SIZE;
 const FNDB_TABLE_SIZE_OFFSET: u32 = 6 * FNDB_WORD_SIZE;
 const FNDB_ENTRY_SIZE: u32 = 4 * FNDB_WORD_SIZE;
 
-pub(super) fn read_database(directory: &Path) -> Result<Vec<PathBuf>> {
-    let database_directory = directory.join(DATABASE_PATH);
-    if !database_directory.exists() {
-        return Ok(Vec::new());
-    }
+pub(super) fn database_files(directory: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(directory.join(DATABASE_PATH)) else { return Vec::new() };
+    entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(OsStr::to_str) == Some("fndb-5"))
+        .collect()
+}
 
+pub(super) fn read_database(directory: &Path) -> Result<Vec<PathBuf>> {
     let mut database = Vec::new();
-    for file in fs::read_dir(database_directory)?.filter_map(Result::ok) {
-        if file.path().extension().and_then(OsStr::to_str) == Some("fndb-5") {
-            let bytes = fs::read(file.path())?;
-            database.extend(parse_database(&bytes).context("parsing kpsewhich database")?);
-        }
+    for file in database_files(directory) {
+        let bytes = fs::read(file)?;
+        database.extend(parse_database(&bytes).context("parsing kpsewhich database")?);
     }
 
     Ok(database)
@@ -8,6 +8,10 @@ use anyhow::Result;
 
 const DATABASE_PATH: &str = "ls-R";
 
+pub(super) fn database_files(directory: &Path) -> Vec<PathBuf> {
+    vec![directory.join(DATABASE_PATH)]
+}
+
 pub(super) fn read_database(directory: &Path) -> Result<Vec<PathBuf>> {
     let file = directory.join(DATABASE_PATH);
     if !file.is_file() {
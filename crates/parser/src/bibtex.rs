@@ -181,7 +181,11 @@ fn accent(mut ptr: TokenPtr<ContentToken>) -> TokenPtr<ContentToken> {
         ptr.expect(ContentToken::Whitespace);
     }
 
-    ptr.expect(ContentToken::Word);
+    if ptr.at(ContentToken::CommandName) {
+        ptr = command(ptr);
+    } else {
+        ptr.expect(ContentToken::Word);
+    }
 
     if group {
         ptr.expect(ContentToken::Whitespace);
@@ -1,11 +1,17 @@
-use std::{cmp::Ordering, path::PathBuf};
+use std::{
+    cmp::Ordering,
+    path::{Path, PathBuf},
+};
 
+use distro::Language;
 use once_cell::sync::Lazy;
 use regex::{Match, Regex};
-use syntax::{BuildError, BuildErrorLevel, BuildLog};
+use syntax::{BuildError, BuildErrorLevel, BuildEvent, BuildLog};
 
 const MAX_LINE_LENGTH: usize = 79;
 
+const MAX_ERROR_CONTEXT: usize = 16;
+
 static PACKAGE_MESSAGE_REGEX: Lazy<Regex> =
     Lazy::new(|| Regex::new("^\\([a-zA-Z_\\-]+\\)\\s*(?P<msg>.*)$").unwrap());
 
@@ -24,6 +30,21 @@ static BAD_BOX_REGEX: Lazy<Regex> = Lazy::new(|| {
     Regex::new("(?P<msg>(Ov|Und)erfull \\\\[hv]box[^\r\n]*lines? (?P<line>\\d+)[^\r\n]*)").unwrap()
 });
 
+static FILE_START_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new("^\\((?P<file>[^\r\n()]+\\.[a-zA-Z]+)").unwrap());
+
+static PAGE_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new("^\\[(?P<page>\\d+)(\\]|\\{|<|\\s|$)").unwrap());
+
+static TEX_ERROR_LINE_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new("^l\\.(?P<line>\\d+)( (\\.\\.\\.)?(?P<hint>.+))?$").unwrap());
+
+static WARNING_START_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new("(LaTeX|Package [a-zA-Z_\\-]+) Warning: ").unwrap());
+
+static WARNING_LINE_REGEX: Lazy<Regex> =
+    Lazy::new(|| Regex::new("on input line (?P<line>\\d+)\\.").unwrap());
+
 pub fn parse_build_log(log: &str) -> BuildLog {
     let log = prepare_log(log);
     let mut ranges: Vec<FileRange> = FILE_REGEX
@@ -173,9 +194,233 @@ impl PartialOrd for FileRange {
     }
 }
 
+/// Parses the output of a running TeX engine line by line.
+///
+/// Unlike `parse_build_log`, this parser does not need the complete log
+/// and can report events while the build is still in progress.
+#[derive(Debug, Default)]
+pub struct BuildLogParser {
+    buffer: String,
+    files: Vec<Option<PathBuf>>,
+    error: Option<PendingError>,
+    warning: Option<PendingWarning>,
+}
+
+#[derive(Debug)]
+struct PendingError {
+    path: Option<PathBuf>,
+    message: String,
+    context: usize,
+}
+
+#[derive(Debug)]
+struct PendingWarning {
+    path: Option<PathBuf>,
+    message: String,
+}
+
+impl BuildLogParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, line: &str) -> Vec<BuildEvent> {
+        let mut events = Vec::new();
+        let line = line.trim_end_matches(['\r', '\n']);
+        self.buffer.push_str(line);
+        if line.chars().count() != MAX_LINE_LENGTH {
+            let line = std::mem::take(&mut self.buffer);
+            self.process(&line, &mut events);
+        }
+
+        events
+    }
+
+    pub fn finish(mut self) -> Vec<BuildEvent> {
+        let mut events = Vec::new();
+        if !self.buffer.is_empty() {
+            let line = std::mem::take(&mut self.buffer);
+            self.process(&line, &mut events);
+        }
+
+        if let Some(warning) = self.warning.take() {
+            Self::emit_warning(warning, &mut events);
+        }
+
+        if let Some(error) = self.error.take() {
+            Self::emit_error(error, None, &mut events);
+        }
+
+        events
+    }
+
+    fn process(&mut self, line: &str, events: &mut Vec<BuildEvent>) {
+        if let Some(mut warning) = self.warning.take() {
+            if let Some(captures) = PACKAGE_MESSAGE_REGEX.captures(line) {
+                warning.message.push(' ');
+                warning.message.push_str(&captures["msg"]);
+                self.warning = Some(warning);
+                return;
+            }
+
+            Self::emit_warning(warning, events);
+        }
+
+        if let Some(mut error) = self.error.take() {
+            if let Some(captures) = TEX_ERROR_LINE_REGEX.captures(line) {
+                Self::emit_error(error, Some(captures), events);
+                return;
+            }
+
+            if error.context == 0 {
+                if let Some(captures) = PACKAGE_MESSAGE_REGEX.captures(line) {
+                    error.message.push(' ');
+                    error.message.push_str(&captures["msg"]);
+                    self.error = Some(error);
+                    return;
+                }
+            }
+
+            if !line.starts_with("! ") && error.context < MAX_ERROR_CONTEXT {
+                error.context += 1;
+                self.error = Some(error);
+                return;
+            }
+
+            Self::emit_error(error, None, events);
+        }
+
+        if let Some(message) = line.strip_prefix("! ") {
+            self.error = Some(PendingError {
+                path: self.current_file(),
+                message: message.to_owned(),
+                context: 0,
+            });
+
+            return;
+        }
+
+        if line.starts_with("This is ") {
+            self.files.clear();
+        }
+
+        if let Some(start) = WARNING_START_REGEX.find(line).map(|result| result.start()) {
+            self.scan(&line[..start], events);
+            self.warning = Some(PendingWarning {
+                path: self.current_file(),
+                message: line[start..].to_owned(),
+            });
+
+            return;
+        }
+
+        if let Some(captures) = BAD_BOX_REGEX.captures(line) {
+            if let Some(path) = self.current_file() {
+                events.push(BuildEvent::Diagnostic(BuildError {
+                    relative_path: path,
+                    level: BuildErrorLevel::Warning,
+                    message: captures["msg"].to_owned(),
+                    hint: None,
+                    line: captures["line"]
+                        .parse::<u32>()
+                        .ok()
+                        .map(|line| line.saturating_sub(1)),
+                }));
+            }
+        }
+
+        self.scan(line, events);
+    }
+
+    fn scan(&mut self, line: &str, events: &mut Vec<BuildEvent>) {
+        for (index, c) in line.char_indices() {
+            match c {
+                '(' => {
+                    let path = FILE_START_REGEX
+                        .captures(&line[index..])
+                        .map(|captures| PathBuf::from(&captures["file"]))
+                        .filter(|path| is_input_file(path));
+
+                    if let Some(path) = &path {
+                        events.push(BuildEvent::FileOpened(path.clone()));
+                    }
+
+                    self.files.push(path);
+                }
+                ')' => {
+                    self.files.pop();
+                }
+                '[' => {
+                    if let Some(page) = PAGE_REGEX
+                        .captures(&line[index..])
+                        .and_then(|captures| captures["page"].parse().ok())
+                    {
+                        events.push(BuildEvent::PageShipped(page));
+                    }
+                }
+                _ => {}
+            };
+        }
+    }
+
+    fn current_file(&self) -> Option<PathBuf> {
+        self.files.iter().rev().find_map(Clone::clone)
+    }
+
+    fn emit_warning(warning: PendingWarning, events: &mut Vec<BuildEvent>) {
+        let Some(relative_path) = warning.path else { return };
+        let line = WARNING_LINE_REGEX
+            .captures(&warning.message)
+            .and_then(|captures| captures["line"].parse::<u32>().ok())
+            .map(|line| line.saturating_sub(1));
+
+        events.push(BuildEvent::Diagnostic(BuildError {
+            relative_path,
+            level: BuildErrorLevel::Warning,
+            message: warning.message,
+            hint: None,
+            line,
+        }));
+    }
+
+    fn emit_error(
+        error: PendingError,
+        captures: Option<regex::Captures>,
+        events: &mut Vec<BuildEvent>,
+    ) {
+        let Some(relative_path) = error.path else { return };
+        let line = captures
+            .as_ref()
+            .and_then(|captures| captures["line"].parse::<u32>().ok())
+            .map(|line| line.saturating_sub(1));
+
+        let hint = captures
+            .as_ref()
+            .and_then(|captures| captures.name("hint"))
+            .map(|hint| hint.as_str().trim().to_owned());
+
+        events.push(BuildEvent::Diagnostic(BuildError {
+            relative_path,
+            level: BuildErrorLevel::Error,
+            message: error.message,
+            hint,
+            line,
+        }));
+    }
+}
+
+/// Checks whether errors can be attributed to a file that appears in the log,
+/// i.e. whether it is a TeX document or a generated bibliography.
+fn is_input_file(path: &Path) -> bool {
+    Language::from_path(path) == Some(Language::Tex)
+        || path
+            .extension()
+            .map_or(false, |extension| extension.eq_ignore_ascii_case("bbl"))
+}
+
 #[cfg(test)]
 mod tests {
-    use super::parse_build_log;
+    use super::{parse_build_log, BuildLogParser};
 
     #[test]
     fn test_parse() {
@@ -184,4 +429,15 @@ mod tests {
             insta::assert_debug_snapshot!(parse_build_log(&text));
         });
     }
+
+    #[test]
+    fn test_parse_incremental() {
+        insta::glob!("test_data/build_log/*.txt", |path| {
+            let text = std::fs::read_to_string(path).unwrap().replace("\r\n", "\n");
+            let mut parser = BuildLogParser::new();
+            let mut events: Vec<_> = text.lines().flat_map(|line| parser.push(line)).collect();
+            events.extend(parser.finish());
+            insta::assert_debug_snapshot!(events);
+        });
+    }
 }
@@ -1,6 +1,6 @@
 use rustc_hash::FxHashSet;
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct SyntaxConfig {
     pub math_environments: FxHashSet<String>,
     pub enum_environments: FxHashSet<String>,
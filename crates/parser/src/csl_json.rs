@@ -0,0 +1,217 @@
+use rowan::{TextRange, TextSize};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use syntax::csl::{Bibliography, Date, Item, Name};
+
+/// Parses a CSL-JSON bibliography, which is an array of items.
+/// Items without an `id` are skipped and parsing stops at the first syntax error.
+pub fn parse_csl_json(text: &str) -> Bibliography {
+    let mut items = Vec::new();
+    let mut scanner = Scanner { text, pos: 0 };
+    if scanner.eat('[') && !scanner.eat(']') {
+        loop {
+            scanner.skip_whitespace();
+            let start = scanner.pos;
+            let Some(fields) = scanner.object() else { break };
+            let full_range = scanner.range(start);
+            items.extend(convert_item(fields, full_range));
+            if !scanner.eat(',') {
+                break;
+            }
+        }
+    }
+
+    Bibliography { items }
+}
+
+type Field = (String, TextRange, Value);
+
+/// Splits the JSON text into objects and values while keeping track of their positions.
+/// The values themselves are parsed with `serde_json`.
+struct Scanner<'a> {
+    text: &'a str,
+    pos: usize,
+}
+
+impl<'a> Scanner<'a> {
+    fn skip_whitespace(&mut self) {
+        let rest = &self.text[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_whitespace();
+        if self.text[self.pos..].starts_with(c) {
+            self.pos += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn range(&self, start: usize) -> TextRange {
+        TextRange::new(
+            TextSize::from(start as u32),
+            TextSize::from(self.pos as u32),
+        )
+    }
+
+    fn value<T: DeserializeOwned>(&mut self) -> Option<(T, TextRange)> {
+        self.skip_whitespace();
+        let start = self.pos;
+        let mut stream = serde_json::Deserializer::from_str(&self.text[start..]).into_iter();
+        let value = stream.next()?.ok()?;
+        self.pos += stream.byte_offset();
+        Some((value, self.range(start)))
+    }
+
+    fn object(&mut self) -> Option<Vec<Field>> {
+        if !self.eat('{') {
+            return None;
+        }
+
+        let mut fields = Vec::new();
+        if self.eat('}') {
+            return Some(fields);
+        }
+
+        loop {
+            let (name, _) = self.value::<String>()?;
+            if !self.eat(':') {
+                return None;
+            }
+
+            let (value, range) = self.value::<Value>()?;
+            fields.push((name, range, value));
+            if self.eat('}') {
+                return Some(fields);
+            }
+
+            if !self.eat(',') {
+                return None;
+            }
+        }
+    }
+}
+
+fn convert_item(fields: Vec<Field>, full_range: TextRange) -> Option<Item> {
+    let (key, key_range) = fields.iter().find_map(|(name, range, value)| {
+        if name != "id" {
+            return None;
+        }
+
+        match value {
+            Value::String(key) => {
+                let quote = TextSize::from(1);
+                Some((
+                    key.clone(),
+                    TextRange::new(range.start() + quote, range.end() - quote),
+                ))
+            }
+            Value::Number(key) => Some((key.to_string(), *range)),
+            _ => None,
+        }
+    })?;
+
+    let mut item = Item {
+        key,
+        key_range,
+        full_range,
+        kind: String::from("document"),
+        text: Default::default(),
+        names: Default::default(),
+        dates: Default::default(),
+    };
+
+    for (name, _, value) in fields {
+        match (name.as_str(), value) {
+            ("id", _) => {}
+            ("type", Value::String(kind)) => item.kind = kind,
+            (_, Value::String(text)) if !text.is_empty() => {
+                item.text.insert(name, text);
+            }
+            (_, Value::Number(number)) => {
+                item.text.insert(name, number.to_string());
+            }
+            (_, Value::Array(names)) => {
+                let names: Vec<_> = names.iter().filter_map(convert_name).collect();
+                if !names.is_empty() {
+                    item.names.insert(name, names);
+                }
+            }
+            (_, Value::Object(date)) => {
+                if let Some(date) = convert_date(&date) {
+                    item.dates.insert(name, date);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Some(item)
+}
+
+fn convert_name(value: &Value) -> Option<Name> {
+    let get = |name| value.get(name).and_then(Value::as_str);
+    if let Some(literal) = get("literal") {
+        return Some(Name {
+            family: literal.into(),
+            given: None,
+        });
+    }
+
+    let family = match get("non-dropping-particle") {
+        Some(particle) => format!("{particle} {}", get("family")?),
+        None => get("family")?.into(),
+    };
+
+    let given = match (get("given"), get("dropping-particle")) {
+        (Some(given), Some(particle)) => Some(format!("{given} {particle}")),
+        (given, particle) => given.or(particle).map(String::from),
+    };
+
+    Some(Name { family, given })
+}
+
+fn convert_date(value: &serde_json::Map<String, Value>) -> Option<Date> {
+    let parts: Vec<_> = value
+        .get("date-parts")
+        .and_then(|parts| parts.get(0))
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .map(|part| match part {
+            Value::Number(number) => number.as_i64(),
+            Value::String(text) => text.trim().parse().ok(),
+            _ => None,
+        })
+        .collect();
+
+    if let Some(Some(year)) = parts.first() {
+        let part = |index: usize| parts.get(index).copied().flatten().map(|part| part as u32);
+        return Some(Date::Parts {
+            year: *year as i32,
+            month: part(1),
+            day: part(2),
+        });
+    }
+
+    value
+        .get("literal")
+        .or_else(|| value.get("raw"))
+        .and_then(Value::as_str)
+        .map(|text| Date::Literal(text.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_csl_json;
+
+    #[test]
+    fn test_parse() {
+        insta::glob!("test_data/csl_json/*.json", |path| {
+            let text = std::fs::read_to_string(path).unwrap().replace("\r\n", "\n");
+            insta::assert_debug_snapshot!(parse_csl_json(&text));
+        });
+    }
+}
@@ -0,0 +1,400 @@
+use rowan::{TextRange, TextSize};
+use syntax::csl::{Bibliography, Date, Item, Name};
+use yaml_rust2::{
+    parser::{MarkedEventReceiver, Parser},
+    scanner::Marker,
+    Event,
+};
+
+/// Parses a Hayagriva bibliography, which maps the keys of the entries to their fields.
+/// The fields are converted to the closest CSL-JSON variables. The fields of the `parent`
+/// entry, like the title of a journal, are used if the entry does not have them.
+pub fn parse_hayagriva(text: &str) -> Bibliography {
+    let mut builder = TreeBuilder::default();
+    let mut parser = Parser::new(text.chars());
+    if parser.load(&mut builder, false).is_err() {
+        builder.close_all();
+    }
+
+    let offsets: Vec<_> = text
+        .char_indices()
+        .map(|(offset, _)| offset)
+        .chain(std::iter::once(text.len()))
+        .collect();
+
+    let offset = |index: usize| offsets.get(index).copied().unwrap_or(text.len());
+
+    let mut items = Vec::new();
+    let Some(NodeKind::Mapping(entries)) = builder.root.map(|root| root.kind) else {
+        return Bibliography { items };
+    };
+
+    for (key, value) in entries {
+        let NodeKind::Scalar(name) = &key.kind else { continue };
+        let NodeKind::Mapping(fields) = &value.kind else { continue };
+
+        let start = offset(key.start);
+        let key_start = if text[start..].starts_with(['"', '\'']) {
+            start + 1
+        } else {
+            start
+        };
+
+        let end = start + text[start..offset(value.end)].trim_end().len();
+        let mut item = Item {
+            key: name.clone(),
+            key_range: range(key_start, key_start + name.len()),
+            full_range: range(start, end),
+            kind: String::from("document"),
+            text: Default::default(),
+            names: Default::default(),
+            dates: Default::default(),
+        };
+
+        convert_entry(&mut item, fields);
+        items.push(item);
+    }
+
+    Bibliography { items }
+}
+
+fn range(start: usize, end: usize) -> TextRange {
+    TextRange::new(TextSize::from(start as u32), TextSize::from(end as u32))
+}
+
+#[derive(Debug)]
+struct Node {
+    start: usize,
+    end: usize,
+    kind: NodeKind,
+}
+
+#[derive(Debug)]
+enum NodeKind {
+    Scalar(String),
+    Sequence(Vec<Node>),
+    Mapping(Vec<(Node, Node)>),
+}
+
+impl Node {
+    fn as_str(&self) -> Option<&str> {
+        match &self.kind {
+            NodeKind::Scalar(text) => Some(text),
+            NodeKind::Sequence(_) | NodeKind::Mapping(_) => None,
+        }
+    }
+
+    fn get(&self, name: &str) -> Option<&Node> {
+        match &self.kind {
+            NodeKind::Mapping(fields) => find(fields, name),
+            NodeKind::Scalar(_) | NodeKind::Sequence(_) => None,
+        }
+    }
+
+    /// Returns the text of a scalar or the `value` of a formattable string.
+    fn text(&self) -> Option<&str> {
+        self.as_str()
+            .or_else(|| self.get("value").and_then(Node::as_str))
+            .filter(|text| !text.is_empty() && *text != "~")
+    }
+
+    /// Returns the elements of a sequence or the node itself.
+    fn elements(&self) -> &[Node] {
+        match &self.kind {
+            NodeKind::Sequence(nodes) => nodes,
+            NodeKind::Scalar(_) | NodeKind::Mapping(_) => std::slice::from_ref(self),
+        }
+    }
+}
+
+fn find<'a>(fields: &'a [(Node, Node)], name: &str) -> Option<&'a Node> {
+    fields
+        .iter()
+        .find(|(key, _)| key.as_str() == Some(name))
+        .map(|(_, value)| value)
+}
+
+struct Frame {
+    start: usize,
+    is_mapping: bool,
+    children: Vec<Node>,
+}
+
+/// Builds a tree of nodes that remember their positions in the source text.
+/// The positions of `yaml_rust2` are character indices.
+#[derive(Default)]
+struct TreeBuilder {
+    stack: Vec<Frame>,
+    root: Option<Node>,
+}
+
+impl TreeBuilder {
+    fn push(&mut self, node: Node) {
+        match self.stack.last_mut() {
+            Some(frame) => frame.children.push(node),
+            None => {
+                self.root.get_or_insert(node);
+            }
+        }
+    }
+
+    fn close(&mut self, end: usize) {
+        let Some(frame) = self.stack.pop() else { return };
+        let kind = if frame.is_mapping {
+            let mut children = frame.children.into_iter();
+            let mut fields = Vec::new();
+            while let (Some(key), Some(value)) = (children.next(), children.next()) {
+                fields.push((key, value));
+            }
+
+            NodeKind::Mapping(fields)
+        } else {
+            NodeKind::Sequence(frame.children)
+        };
+
+        self.push(Node {
+            start: frame.start,
+            end,
+            kind,
+        });
+    }
+
+    fn close_all(&mut self) {
+        while let Some(end) = self
+            .stack
+            .last()
+            .map(|frame| frame.children.last().map_or(frame.start, |node| node.end))
+        {
+            self.close(end);
+        }
+    }
+}
+
+impl MarkedEventReceiver for TreeBuilder {
+    fn on_event(&mut self, event: Event, mark: Marker) {
+        let index = mark.index();
+        match event {
+            Event::MappingStart(..) | Event::SequenceStart(..) => {
+                self.stack.push(Frame {
+                    start: index,
+                    is_mapping: matches!(event, Event::MappingStart(..)),
+                    children: Vec::new(),
+                });
+            }
+            Event::MappingEnd | Event::SequenceEnd => self.close(index),
+            Event::Scalar(text, _, _, _) => {
+                let end = index + text.chars().count();
+                self.push(Node {
+                    start: index,
+                    end,
+                    kind: NodeKind::Scalar(text),
+                });
+            }
+            Event::Alias(_) => {
+                self.push(Node {
+                    start: index,
+                    end: index,
+                    kind: NodeKind::Scalar(String::new()),
+                });
+            }
+            Event::Nothing
+            | Event::StreamStart
+            | Event::StreamEnd
+            | Event::DocumentStart
+            | Event::DocumentEnd => {}
+        }
+    }
+}
+
+fn convert_entry(item: &mut Item, fields: &[(Node, Node)]) {
+    let parent = find(fields, "parent").and_then(|parent| parent.elements().first());
+    let parent_fields: &[(Node, Node)] = match parent.map(|parent| &parent.kind) {
+        Some(NodeKind::Mapping(fields)) => fields,
+        _ => &[],
+    };
+
+    let kind = find(fields, "type")
+        .and_then(Node::as_str)
+        .unwrap_or_default();
+    let parent_kind = find(parent_fields, "type")
+        .and_then(Node::as_str)
+        .unwrap_or_default();
+
+    item.kind = String::from(csl_type(kind, parent_kind));
+
+    for (name, csl_name) in [
+        ("title", "title"),
+        ("publisher", "publisher"),
+        ("location", "publisher-place"),
+        ("organization", "publisher"),
+        ("edition", "edition"),
+        ("volume", "volume"),
+        ("volume-total", "number-of-volumes"),
+        ("issue", "issue"),
+        ("page-range", "page"),
+        ("page-total", "number-of-pages"),
+        ("genre", "genre"),
+        ("language", "language"),
+        ("note", "note"),
+        ("abstract", "abstract"),
+        ("doi", "DOI"),
+        ("isbn", "ISBN"),
+        ("issn", "ISSN"),
+    ] {
+        let value =
+            find(fields, name).or_else(|| find(parent_fields, name).filter(|_| name != "title"));
+        if let Some(text) = value.and_then(Node::text) {
+            item.text
+                .entry(csl_name.into())
+                .or_insert_with(|| text.into());
+        }
+    }
+
+    if let Some(publisher) = find(fields, "publisher").or_else(|| find(parent_fields, "publisher"))
+    {
+        if let Some(name) = publisher.get("name").and_then(Node::text) {
+            item.text.insert("publisher".into(), name.into());
+        }
+
+        if let Some(location) = publisher.get("location").and_then(Node::text) {
+            item.text.insert("publisher-place".into(), location.into());
+        }
+    }
+
+    if let Some(title) = find(parent_fields, "title").and_then(Node::text) {
+        item.text.insert("container-title".into(), title.into());
+    }
+
+    if let Some(serial) = find(fields, "serial-number") {
+        for (name, csl_name) in [("doi", "DOI"), ("isbn", "ISBN"), ("issn", "ISSN")] {
+            if let Some(text) = serial.get(name).and_then(Node::text) {
+                item.text.insert(csl_name.into(), text.into());
+            }
+        }
+
+        if let Some(text) = serial.as_str().filter(|text| !text.is_empty()) {
+            item.text.insert("number".into(), text.into());
+        }
+    }
+
+    if let Some(url) = find(fields, "url") {
+        if let Some(text) = url.text() {
+            item.text.insert("URL".into(), text.into());
+        }
+
+        if let Some(date) = url.get("date").and_then(Node::as_str).and_then(parse_date) {
+            item.dates.insert("accessed".into(), date);
+        }
+    }
+
+    for (name, csl_name) in [
+        ("author", "author"),
+        ("editor", "editor"),
+        ("translator", "translator"),
+    ] {
+        let value = find(fields, name).or_else(|| {
+            find(parent_fields, name).filter(|_| name == "editor" && item.kind == "chapter")
+        });
+
+        let names: Vec<_> = value
+            .into_iter()
+            .flat_map(Node::elements)
+            .filter_map(convert_name)
+            .collect();
+
+        if !names.is_empty() {
+            item.names.insert(csl_name.into(), names);
+        }
+    }
+
+    let date = find(fields, "date").or_else(|| find(parent_fields, "date"));
+    if let Some(date) = date.and_then(Node::as_str).and_then(parse_date) {
+        item.dates.insert("issued".into(), date);
+    }
+}
+
+fn convert_name(node: &Node) -> Option<Name> {
+    if let Some(text) = node.as_str() {
+        let (family, given) = match text.split_once(',') {
+            Some((family, given)) => (family.trim(), Some(given.trim())),
+            None => (text.trim(), None),
+        };
+
+        return Some(Name {
+            family: family.into(),
+            given: given.filter(|given| !given.is_empty()).map(String::from),
+        });
+    }
+
+    let family = node.get("name").and_then(Node::as_str)?;
+    let family = match node.get("prefix").and_then(Node::as_str) {
+        Some(prefix) => format!("{prefix} {family}"),
+        None => family.into(),
+    };
+
+    let given = node
+        .get("given-name")
+        .and_then(Node::as_str)
+        .map(String::from);
+
+    Some(Name { family, given })
+}
+
+fn parse_date(text: &str) -> Option<Date> {
+    let mut parts = text.trim().splitn(3, '-');
+    let Some(Ok(year)) = parts.next().map(str::parse) else {
+        return Some(Date::Literal(text.into()));
+    };
+
+    let mut part = || parts.next().and_then(|part| part.parse().ok());
+    Some(Date::Parts {
+        year,
+        month: part(),
+        day: part(),
+    })
+}
+
+/// Maps the Hayagriva entry types to the closest CSL item type.
+fn csl_type(kind: &str, parent_kind: &str) -> &'static str {
+    match kind.to_ascii_lowercase().as_str() {
+        "article" => match parent_kind.to_ascii_lowercase().as_str() {
+            "periodical" => "article-journal",
+            "newspaper" => "article-newspaper",
+            "proceedings" | "conference" => "paper-conference",
+            "blog" => "post-weblog",
+            _ => "article",
+        },
+        "book" | "anthology" | "proceedings" | "reference" => "book",
+        "chapter" | "anthos" => "chapter",
+        "entry" => "entry-encyclopedia",
+        "report" => "report",
+        "thesis" => "thesis",
+        "web" | "blog" => "webpage",
+        "periodical" => "periodical",
+        "newspaper" => "article-newspaper",
+        "patent" => "patent",
+        "repository" => "software",
+        "manuscript" => "manuscript",
+        "conference" => "paper-conference",
+        "case" => "legal_case",
+        "legislation" => "legislation",
+        "video" => "motion_picture",
+        "audio" => "song",
+        "artwork" => "graphic",
+        "thread" => "post",
+        _ => "document",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_hayagriva;
+
+    #[test]
+    fn test_parse() {
+        insta::glob!("test_data/hayagriva/*.yml", |path| {
+            let text = std::fs::read_to_string(path).unwrap().replace("\r\n", "\n");
+            insta::assert_debug_snapshot!(parse_hayagriva(&text));
+        });
+    }
+}
@@ -1,7 +1,10 @@
 mod lexer;
 
-use rowan::{GreenNode, GreenNodeBuilder};
-use syntax::latex::SyntaxKind::{self, *};
+use rowan::{GreenNode, GreenNodeBuilder, TextRange, TextSize};
+use syntax::latex::{
+    self,
+    SyntaxKind::{self, *},
+};
 
 use crate::SyntaxConfig;
 
@@ -82,6 +85,41 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// Parses the first `len` bytes of `text` as a single node of the given kind.
+    /// Returns `None` if the node would span different tokens in the whole document.
+    fn reparse(
+        text: &'a str,
+        len: usize,
+        kind: SyntaxKind,
+        config: &SyntaxConfig,
+    ) -> Option<GreenNode> {
+        let mut parser = Self {
+            lexer: Lexer::with_lookahead(text, len, config)?,
+            builder: GreenNodeBuilder::new(),
+        };
+
+        let has_lookahead = len < text.len();
+        match (kind, parser.peek()?) {
+            (CURLY_GROUP, Token::LCurly) => parser.curly_group(),
+            (ENVIRONMENT, Token::CommandName(CommandName::BeginEnvironment)) => {
+                parser.environment();
+            }
+            (_, Token::CommandName(CommandName::Section(level))) if section_kind(level) == kind => {
+                parser.section(level);
+            }
+            _ => return None,
+        };
+
+        // The parser only looks one token ahead, so the node is guaranteed to be the same
+        // if it stops right before the token that follows it in the document.
+        let remaining = std::iter::from_fn(|| parser.lexer.eat()).count();
+        if remaining != usize::from(has_lookahead) {
+            return None;
+        }
+
+        Some(parser.builder.finish())
+    }
+
     pub fn parse(mut self) -> GreenNode {
         self.builder.start_node(ROOT.into());
         self.preamble();
@@ -553,17 +591,7 @@ impl<'a> Parser<'a> {
     }
 
     fn section(&mut self, level: SectionLevel) {
-        let node_kind = match level {
-            SectionLevel::Part => PART,
-            SectionLevel::Chapter => CHAPTER,
-            SectionLevel::Section => SECTION,
-            SectionLevel::Subsection => SUBSECTION,
-            SectionLevel::Subsubsection => SUBSUBSECTION,
-            SectionLevel::Paragraph => PARAGRAPH,
-            SectionLevel::Subparagraph => SUBPARAGRAPH,
-        };
-
-        self.builder.start_node(node_kind.into());
+        self.builder.start_node(section_kind(level).into());
         self.eat();
         self.trivia();
 
@@ -1148,17 +1176,96 @@ impl<'a> Parser<'a> {
     }
 }
 
+fn section_kind(level: SectionLevel) -> SyntaxKind {
+    match level {
+        SectionLevel::Part => PART,
+        SectionLevel::Chapter => CHAPTER,
+        SectionLevel::Section => SECTION,
+        SectionLevel::Subsection => SUBSECTION,
+        SectionLevel::Subsubsection => SUBSUBSECTION,
+        SectionLevel::Paragraph => PARAGRAPH,
+        SectionLevel::Subparagraph => SUBPARAGRAPH,
+    }
+}
+
 pub fn parse_latex(text: &str, config: &SyntaxConfig) -> GreenNode {
     Parser::new(text, config).parse()
 }
 
+/// Updates the syntax tree of a document after replacing `delete` with `insert`.
+///
+/// Only the smallest group, environment or section that encloses the edit is reparsed.
+/// If the edit changes the structure of the surrounding nodes (e.g. by unbalancing a group),
+/// the whole document is parsed again. `text` refers to the text after the edit.
+pub fn reparse_latex(
+    green: &GreenNode,
+    text: &str,
+    delete: TextRange,
+    insert: &str,
+    config: &SyntaxConfig,
+) -> GreenNode {
+    try_reparse(green, text, delete, insert, config).unwrap_or_else(|| parse_latex(text, config))
+}
+
+fn try_reparse(
+    green: &GreenNode,
+    text: &str,
+    delete: TextRange,
+    insert: &str,
+    config: &SyntaxConfig,
+) -> Option<GreenNode> {
+    let root = latex::SyntaxNode::new_root(green.clone());
+    if root.text_range().end() < delete.end() {
+        return None;
+    }
+
+    // Environment definitions do not allow nested environments,
+    // so the edit must be handled by one of their ancestors.
+    let ancestors: Vec<_> = root.covering_element(delete).ancestors().collect();
+    let skip = ancestors
+        .iter()
+        .rposition(|node| node.kind() == ENVIRONMENT_DEFINITION)
+        .map_or(0, |index| index + 1);
+
+    let insert_len = TextSize::of(insert);
+    for node in ancestors.into_iter().skip(skip) {
+        let range = node.text_range();
+        if range.start() >= delete.start() || delete.end() >= range.end() {
+            continue;
+        }
+
+        // These commands parse their arguments differently than a normal group.
+        if node.parent().map_or(false, |parent| {
+            matches!(
+                parent.kind(),
+                COMMAND_DEFINITION | MATH_OPERATOR | GRAPHICS_PATH
+            )
+        }) {
+            continue;
+        }
+
+        let start = usize::from(range.start());
+        let len = usize::from(range.len() - delete.len() + insert_len);
+        if let Some(green) = text
+            .get(start..)
+            .and_then(|text| Parser::reparse(text, len, node.kind(), config))
+        {
+            return Some(node.replace_with(green));
+        }
+    }
+
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use syntax::latex;
 
+    use rowan::{TextRange, TextSize};
+
     use crate::SyntaxConfig;
 
-    use super::parse_latex;
+    use super::{parse_latex, reparse_latex, try_reparse};
 
     #[test]
     fn test_parse() {
@@ -1168,4 +1275,87 @@ mod tests {
             insta::assert_debug_snapshot!(root);
         });
     }
+
+    /// Applies the edit to `old` and checks that the incremental result matches a full parse.
+    /// Returns whether the tree could be updated without a full parse.
+    fn check_reparse(old: &str, delete: &str, insert: &str) -> bool {
+        let config = SyntaxConfig::default();
+        let start = TextSize::try_from(old.find(delete).unwrap()).unwrap();
+        let delete = TextRange::at(start, TextSize::of(delete));
+
+        let mut text = old.to_string();
+        text.replace_range(std::ops::Range::<usize>::from(delete), insert);
+
+        let green = parse_latex(old, &config);
+        let expected = parse_latex(&text, &config);
+        assert_eq!(
+            reparse_latex(&green, &text, delete, insert, &config),
+            expected
+        );
+
+        try_reparse(&green, &text, delete, insert, &config).is_some()
+    }
+
+    const TEXT: &str = r#"\documentclass{article}
+\newcommand{\foo}{\textbf{bar}}
+\begin{document}
+\section{Foo}
+Lorem \textbf{ipsum} dolor.
+\begin{itemize}
+    \item Bar
+\end{itemize}
+\subsection{Bar}
+Baz
+\end{document}
+"#;
+
+    #[test]
+    fn test_reparse_group() {
+        assert!(check_reparse(TEXT, "ipsum", "ipsum sit amet"));
+    }
+
+    #[test]
+    fn test_reparse_environment() {
+        assert!(check_reparse(TEXT, "Bar\n\\end", "Baz\n\\end"));
+    }
+
+    #[test]
+    fn test_reparse_section() {
+        assert!(check_reparse(TEXT, "Baz", "\\textit{Baz}"));
+    }
+
+    #[test]
+    fn test_reparse_trailing_text() {
+        assert!(check_reparse(TEXT, "} dolor", "}, dolor"));
+    }
+
+    #[test]
+    fn test_reparse_unbalanced_group() {
+        assert!(!check_reparse(TEXT, "ipsum", "ip}sum"));
+    }
+
+    #[test]
+    fn test_reparse_new_section() {
+        check_reparse(TEXT, "Baz", "\\section{Baz}");
+    }
+
+    #[test]
+    fn test_reparse_comment() {
+        check_reparse(TEXT, "ipsum", "ip%sum");
+    }
+
+    #[test]
+    fn test_reparse_end_environment() {
+        check_reparse(TEXT, "Bar\n", "Bar\n\\end{itemize}\n");
+    }
+
+    #[test]
+    fn test_reparse_unclosed_verbatim() {
+        check_reparse(TEXT, "ipsum", "\\verb|ipsum");
+    }
+
+    #[test]
+    fn test_reparse_command_definition() {
+        assert!(check_reparse(TEXT, "bar", "baz"));
+    }
 }
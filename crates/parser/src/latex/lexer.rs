@@ -15,11 +15,37 @@ pub struct Lexer<'a> {
 
 impl<'a> Lexer<'a> {
     pub fn new(input: &'a str, config: &SyntaxConfig) -> Self {
-        let mut tokens = tokenize(input, config);
+        let mut tokens: Vec<_> = tokenize(input, config).collect();
         tokens.reverse();
         Self { tokens }
     }
 
+    /// Tokenizes the first `len` bytes of `input` followed by a single lookahead token.
+    /// Returns `None` if `len` does not fall on a token boundary.
+    pub fn with_lookahead(input: &'a str, len: usize, config: &SyntaxConfig) -> Option<Self> {
+        let mut tokens = Vec::new();
+        let mut offset = 0;
+        for (kind, text) in tokenize(input, config) {
+            if offset > len {
+                return None;
+            }
+
+            tokens.push((kind, text));
+            if offset == len {
+                break;
+            }
+
+            offset += text.len();
+        }
+
+        if offset != len {
+            return None;
+        }
+
+        tokens.reverse();
+        Some(Self { tokens })
+    }
+
     pub fn peek(&self) -> Option<Token> {
         self.tokens.last().map(|(kind, _)| *kind)
     }
@@ -48,7 +74,13 @@ impl<'a> Lexer<'a> {
     }
 }
 
-fn tokenize<'a>(input: &'a str, config: &SyntaxConfig) -> Vec<(Token, &'a str)> {
+fn tokenize<'a, 'b>(
+    input: &'a str,
+    config: &'b SyntaxConfig,
+) -> impl Iterator<Item = (Token, &'a str)> + 'b
+where
+    'a: 'b,
+{
     let mut lexer = Token::lexer(input);
     std::iter::from_fn(move || {
         let kind = lexer.next()?.unwrap();
@@ -63,5 +95,4 @@ fn tokenize<'a>(input: &'a str, config: &SyntaxConfig) -> Vec<(Token, &'a str)>
             (kind, text)
         }
     })
-    .collect()
 }
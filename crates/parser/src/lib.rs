@@ -1,6 +1,21 @@
 mod bibtex;
 mod build_log;
 mod config;
+mod csl_json;
+mod hayagriva;
 mod latex;
+mod project_config;
+mod recorder;
+mod tectonic;
 
-pub use self::{bibtex::parse_bibtex, build_log::parse_build_log, config::*, latex::parse_latex};
+pub use self::{
+    bibtex::parse_bibtex,
+    build_log::{parse_build_log, BuildLogParser},
+    config::*,
+    csl_json::parse_csl_json,
+    hayagriva::parse_hayagriva,
+    latex::{parse_latex, reparse_latex},
+    project_config::parse_project_config,
+    recorder::parse_recorder,
+    tectonic::parse_tectonic,
+};
@@ -0,0 +1,78 @@
+use serde::Deserialize;
+use syntax::ProjectConfig;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ProjectFile {
+    root_document: Option<String>,
+    aux_directory: Option<String>,
+    build: BuildSection,
+    syntax: SyntaxSection,
+    diagnostics: DiagnosticsSection,
+    citation: CitationSection,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct BuildSection {
+    executable: Option<String>,
+    args: Option<Vec<String>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct SyntaxSection {
+    math_environments: Vec<String>,
+    enum_environments: Vec<String>,
+    verbatim_environments: Vec<String>,
+    citation_commands: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct DiagnosticsSection {
+    allowed_patterns: Vec<String>,
+    ignored_patterns: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct CitationSection {
+    style: Option<String>,
+    locales: Option<String>,
+    key_pattern: Option<String>,
+}
+
+/// Parses a project configuration file (`texlab.toml` or `.texlabroot`).
+/// An empty file is a valid configuration without any settings.
+pub fn parse_project_config(text: &str) -> Option<ProjectConfig> {
+    let file: ProjectFile = toml::from_str(text).ok()?;
+    Some(ProjectConfig {
+        root_document: file.root_document,
+        aux_directory: file.aux_directory,
+        build_executable: file.build.executable,
+        build_args: file.build.args,
+        math_environments: file.syntax.math_environments,
+        enum_environments: file.syntax.enum_environments,
+        verbatim_environments: file.syntax.verbatim_environments,
+        citation_commands: file.syntax.citation_commands,
+        allowed_patterns: file.diagnostics.allowed_patterns,
+        ignored_patterns: file.diagnostics.ignored_patterns,
+        citation_style: file.citation.style,
+        citation_locales: file.citation.locales,
+        citation_key_pattern: file.citation.key_pattern,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_project_config;
+
+    #[test]
+    fn test_parse() {
+        insta::glob!("test_data/project_config/*.toml", |path| {
+            let text = std::fs::read_to_string(path).unwrap().replace("\r\n", "\n");
+            insta::assert_debug_snapshot!(parse_project_config(&text));
+        });
+    }
+}
@@ -0,0 +1,55 @@
+use std::path::{Component, PathBuf};
+
+use syntax::Recorder;
+
+/// Parses a `.fls` file that was written by a TeX engine invoked with `-recorder`.
+/// Relative paths are resolved against the working directory of the engine.
+/// Duplicate entries are removed.
+pub fn parse_recorder(text: &str) -> Recorder {
+    let mut recorder = Recorder::default();
+    for line in text.lines() {
+        let Some((kind, path)) = line.split_once(' ') else { continue };
+        match kind {
+            "PWD" => {
+                recorder.working_dir = Some(PathBuf::from(path));
+            }
+            "INPUT" | "OUTPUT" => {
+                let path = match &recorder.working_dir {
+                    Some(dir) => dir.join(path),
+                    None => PathBuf::from(path),
+                };
+
+                let path: PathBuf = path
+                    .components()
+                    .filter(|component| *component != Component::CurDir)
+                    .collect();
+
+                let paths = if kind == "INPUT" {
+                    &mut recorder.inputs
+                } else {
+                    &mut recorder.outputs
+                };
+
+                if !paths.contains(&path) {
+                    paths.push(path);
+                }
+            }
+            _ => {}
+        };
+    }
+
+    recorder
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_recorder;
+
+    #[test]
+    fn test_parse() {
+        insta::glob!("test_data/recorder/*.txt", |path| {
+            let text = std::fs::read_to_string(path).unwrap().replace("\r\n", "\n");
+            insta::assert_debug_snapshot!(parse_recorder(&text));
+        });
+    }
+}
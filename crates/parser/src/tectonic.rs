@@ -0,0 +1,77 @@
+use serde::Deserialize;
+use syntax::{TectonicManifest, TectonicOutput};
+
+#[derive(Debug, Deserialize)]
+struct Manifest {
+    doc: Doc,
+    #[serde(default)]
+    output: Vec<Output>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Doc {
+    name: String,
+    bundle: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Output {
+    name: String,
+    #[serde(rename = "type")]
+    kind: String,
+    tex_format: Option<String>,
+    preamble: Option<String>,
+    index: Option<String>,
+    postamble: Option<String>,
+    inputs: Option<Vec<toml::Value>>,
+}
+
+/// Parses a `Tectonic.toml` manifest of the V2 command line interface.
+/// The inputs of an output are relative to the `src` directory of the project.
+/// Inline inputs are skipped because they do not refer to a file.
+pub fn parse_tectonic(text: &str) -> Option<TectonicManifest> {
+    let manifest: Manifest = toml::from_str(text).ok()?;
+    let outputs = manifest
+        .output
+        .into_iter()
+        .map(|output| {
+            let inputs = match output.inputs {
+                Some(inputs) => inputs
+                    .into_iter()
+                    .filter_map(|input| input.as_str().map(String::from))
+                    .collect(),
+                None => vec![
+                    output.preamble.unwrap_or_else(|| "_preamble.tex".into()),
+                    output.index.unwrap_or_else(|| "index.tex".into()),
+                    output.postamble.unwrap_or_else(|| "_postamble.tex".into()),
+                ],
+            };
+
+            TectonicOutput {
+                name: output.name,
+                kind: output.kind,
+                tex_format: output.tex_format.unwrap_or_else(|| "latex".into()),
+                inputs,
+            }
+        })
+        .collect();
+
+    Some(TectonicManifest {
+        name: manifest.doc.name,
+        bundle: manifest.doc.bundle,
+        outputs,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_tectonic;
+
+    #[test]
+    fn test_parse() {
+        insta::glob!("test_data/tectonic/*.toml", |path| {
+            let text = std::fs::read_to_string(path).unwrap().replace("\r\n", "\n");
+            insta::assert_debug_snapshot!(parse_tectonic(&text));
+        });
+    }
+}
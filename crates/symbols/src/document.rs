@@ -7,9 +7,10 @@ use crate::Symbol;
 
 pub fn document_symbols(workspace: &Workspace, document: &Document) -> Vec<Symbol> {
     let project = workspace.project(document);
+    let config = workspace.config_for(&document.uri);
     let mut symbols = match &document.data {
         DocumentData::Tex(data) => {
-            let builder = tex::SymbolBuilder::new(&project, workspace.config());
+            let builder = tex::SymbolBuilder::new(&project, config);
             builder.visit(&data.root_node())
         }
         DocumentData::Bib(data) => {
@@ -19,13 +20,15 @@ pub fn document_symbols(workspace: &Workspace, document: &Document) -> Vec<Symbo
                 .filter_map(|node| builder.visit(&node))
                 .collect()
         }
-        DocumentData::Aux(_)
+        DocumentData::Csl(_)
+        | DocumentData::Aux(_)
         | DocumentData::Log(_)
-        | DocumentData::Root
-        | DocumentData::Tectonic => Vec::new(),
+        | DocumentData::Fls(_)
+        | DocumentData::Root(_)
+        | DocumentData::Tectonic(_) => Vec::new(),
     };
 
-    filter_symbols(&mut symbols, &workspace.config().symbols);
+    filter_symbols(&mut symbols, &config.symbols);
     symbols
 }
 
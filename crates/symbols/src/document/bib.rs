@@ -1,6 +1,10 @@
-use base_db::data::{BibtexEntryType, BibtexEntryTypeCategory};
+use base_db::{
+    data::{BibtexEntryType, BibtexEntryTypeCategory},
+    semantics::Span,
+};
+use citeproc::field::text::TextFieldData;
 use rowan::ast::AstNode;
-use syntax::bibtex::{self, HasName, HasType};
+use syntax::bibtex::{self, HasName, HasType, HasValue};
 
 use crate::{Symbol, SymbolKind};
 
@@ -45,13 +49,28 @@ impl SymbolBuilder {
         Some(Symbol {
             name: key.text().into(),
             kind: SymbolKind::Entry(category),
-            label: None,
+            label: self.visit_title(entry),
             full_range: entry.syntax().text_range(),
             selection_range: key.text_range(),
             children,
         })
     }
 
+    fn visit_title(&self, entry: &bibtex::Entry) -> Option<Span> {
+        let value = entry
+            .fields()
+            .find(|field| {
+                field
+                    .name_token()
+                    .map_or(false, |name| name.text().eq_ignore_ascii_case("title"))
+            })?
+            .value()?;
+
+        let text = TextFieldData::parse(&value)?.text;
+        let range = value.syntax().text_range();
+        Some(Span { text, range })
+    }
+
     fn visit_field(&self, field: &bibtex::Field) -> Option<Symbol> {
         let name = field.name_token()?;
         Some(Symbol::new_simple(
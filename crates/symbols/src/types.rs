@@ -68,7 +68,19 @@ impl Symbol {
             SymbolKind::Entry(BibtexEntryTypeCategory::String) => {
                 vec![&self.name, "bibtex", "string"]
             }
-            SymbolKind::Entry(_) => vec![&self.name, "bibtex", "entry"],
+            SymbolKind::Entry(_) => {
+                let mut keywords = vec![&self.name, "bibtex", "entry"];
+                if let Some(title) = &self.label {
+                    keywords.extend(
+                        title
+                            .text
+                            .split_whitespace()
+                            .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric())),
+                    );
+                }
+
+                keywords
+            }
             SymbolKind::Field => vec![&self.name, "bibtex", "field"],
         }
     }
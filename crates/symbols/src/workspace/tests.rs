@@ -97,3 +97,16 @@ fn filter_bibtex() {
     let fixture = Fixture::parse(FIXTURE);
     assert_debug_snapshot!(workspace_symbols(&fixture.workspace, "bibtex"));
 }
+
+#[test]
+fn filter_bibtex_title() {
+    let fixture = Fixture::parse(
+        r#"
+%! main.bib
+@article{foo, title = {Die {Gr\"o{\ss}e} des $\alpha$-Zerfalls}}
+
+@article{bar, title = {Bar}}"#,
+    );
+
+    assert_debug_snapshot!(workspace_symbols(&fixture.workspace, "größe"));
+}
@@ -0,0 +1,39 @@
+use std::collections::BTreeMap;
+
+use rowan::TextRange;
+
+/// A bibliography in CSL-JSON or Hayagriva format.
+/// The items of both formats are described with the variables of CSL-JSON.
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Default)]
+pub struct Bibliography {
+    pub items: Vec<Item>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct Item {
+    pub key: String,
+    pub key_range: TextRange,
+    pub full_range: TextRange,
+
+    /// The CSL item type, e.g. `article-journal`.
+    pub kind: String,
+    pub text: BTreeMap<String, String>,
+    pub names: BTreeMap<String, Vec<Name>>,
+    pub dates: BTreeMap<String, Date>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct Name {
+    pub family: String,
+    pub given: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub enum Date {
+    Parts {
+        year: i32,
+        month: Option<u32>,
+        day: Option<u32>,
+    },
+    Literal(String),
+}
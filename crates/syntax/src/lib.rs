@@ -1,4 +1,5 @@
 pub mod bibtex;
+pub mod csl;
 pub mod latex;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, PartialOrd, Ord, Hash)]
@@ -21,6 +22,60 @@ pub struct BuildLog {
     pub errors: Vec<BuildError>,
 }
 
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Default)]
+pub struct Recorder {
+    pub working_dir: Option<std::path::PathBuf>,
+    pub inputs: Vec<std::path::PathBuf>,
+    pub outputs: Vec<std::path::PathBuf>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Default)]
+pub struct TectonicManifest {
+    pub name: String,
+    pub bundle: Option<String>,
+    pub outputs: Vec<TectonicOutput>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct TectonicOutput {
+    pub name: String,
+    pub kind: String,
+    pub tex_format: String,
+    pub inputs: Vec<String>,
+}
+
+impl TectonicOutput {
+    /// The directory relative to the manifest that contains the files of the output,
+    /// e.g. `build/default/default.pdf` and `build/default/default.log`.
+    pub fn build_dir(&self) -> String {
+        format!("build/{}/", self.name)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash, Default)]
+pub struct ProjectConfig {
+    pub root_document: Option<String>,
+    pub aux_directory: Option<String>,
+    pub build_executable: Option<String>,
+    pub build_args: Option<Vec<String>>,
+    pub math_environments: Vec<String>,
+    pub enum_environments: Vec<String>,
+    pub verbatim_environments: Vec<String>,
+    pub citation_commands: Vec<String>,
+    pub allowed_patterns: Vec<String>,
+    pub ignored_patterns: Vec<String>,
+    pub citation_style: Option<String>,
+    pub citation_locales: Option<String>,
+    pub citation_key_pattern: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub enum BuildEvent {
+    FileOpened(std::path::PathBuf),
+    Diagnostic(BuildError),
+    PageShipped(u32),
+}
+
 #[macro_export]
 macro_rules! match_ast {
     (match $node:ident { $($tt:tt)* }) => { $crate::match_ast!(match ($node) { $($tt)* }) };
@@ -3,6 +3,7 @@ use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use distro::Language;
 use lsp_types::{ClientCapabilities, Position, Url};
 use parser::{parse_latex, SyntaxConfig};
+use rowan::{TextRange, TextSize};
 
 const CODE: &str = include_str!("../../../texlab.tex");
 
@@ -36,6 +37,26 @@ fn criterion_benchmark(c: &mut Criterion) {
             )
         });
     });
+
+    c.bench_function("LaTeX/Edit", |b| {
+        let uri = Url::parse("http://example.com/texlab.tex").unwrap();
+        let mut workspace = Workspace::default();
+        workspace.open(
+            uri.clone(),
+            CODE.to_string(),
+            Language::Tex,
+            Owner::Client,
+            LineCol { line: 0, col: 0 },
+        );
+
+        let offset = TextSize::try_from(CODE.find("texlab [FLAGS]").unwrap()).unwrap();
+        let insert = TextRange::empty(offset);
+        let delete = TextRange::at(offset, TextSize::from(1));
+        b.iter(|| {
+            workspace.edit(&uri, insert, "x");
+            workspace.edit(&uri, delete, "");
+        });
+    });
 }
 
 criterion_group!(benches, criterion_benchmark);
@@ -0,0 +1,76 @@
+mod citation_key;
+mod field_value;
+
+use base_db::{Document, Project, Workspace};
+use lsp_types::{ClientCapabilities, CodeAction, Position, Range, Url};
+use rowan::TextRange;
+use serde::{Deserialize, Serialize};
+
+use crate::util::{capabilities::ClientCapabilitiesExt, line_index_ext::LineIndexExt};
+
+pub fn find_all(
+    workspace: &Workspace,
+    uri: &Url,
+    range: Range,
+    client_capabilities: &ClientCapabilities,
+) -> Option<Vec<CodeAction>> {
+    let document = workspace.lookup(uri)?;
+    let range = document.line_index.offset_lsp_range(range);
+    let project = workspace.project(document);
+
+    let mut builder = CodeActionBuilder {
+        workspace,
+        document,
+        project,
+        range,
+        resolve_edits: client_capabilities.has_code_action_resolve_support(),
+        actions: Vec::new(),
+    };
+
+    field_value::find_actions(&mut builder);
+    citation_key::find_actions(&mut builder);
+    Some(builder.actions)
+}
+
+/// Computes the edit of a code action that has been returned without one.
+pub fn resolve(workspace: &Workspace, mut action: CodeAction) -> CodeAction {
+    let Some(data) = action
+        .data
+        .clone()
+        .and_then(|data| serde_json::from_value::<CodeActionData>(data).ok()) else { return action };
+
+    let (uri, position) = match &data {
+        CodeActionData::RegenerateKey { uri, position } => (uri, *position),
+        CodeActionData::RegenerateAllKeys { uri } => (uri, Position::default()),
+    };
+
+    let Some(document) = workspace.lookup(uri) else { return action };
+    let offset = document.line_index.offset_lsp(position);
+    let builder = CodeActionBuilder {
+        workspace,
+        document,
+        project: workspace.project(document),
+        range: TextRange::empty(offset),
+        resolve_edits: false,
+        actions: Vec::new(),
+    };
+
+    action.edit = citation_key::resolve(&builder, &data);
+    action
+}
+
+struct CodeActionBuilder<'a> {
+    workspace: &'a Workspace,
+    document: &'a Document,
+    project: Project<'a>,
+    range: TextRange,
+    resolve_edits: bool,
+    actions: Vec<CodeAction>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum CodeActionData {
+    RegenerateKey { uri: Url, position: Position },
+    RegenerateAllKeys { uri: Url },
+}
@@ -0,0 +1,220 @@
+use std::collections::HashMap;
+
+use base_db::DocumentData;
+use citeproc::key::{generate_key, DEFAULT_KEY_PATTERN};
+use lsp_types::{CodeAction, CodeActionKind, TextEdit, WorkspaceEdit};
+use rowan::{ast::AstNode, TextRange, TextSize};
+use rustc_hash::{FxHashMap, FxHashSet};
+use syntax::{
+    bibtex::{self, HasName},
+    latex,
+};
+
+use crate::util::line_index_ext::LineIndexExt;
+
+use super::{CodeActionBuilder, CodeActionData};
+
+/// Offers to regenerate the key of the entry under the cursor and the keys of all entries
+/// of the document from the configured key pattern. Citations of the renamed entries
+/// are updated in the entire project.
+///
+/// If the client can resolve code actions, the edits are only computed
+/// once an action has been selected.
+pub(super) fn find_actions(builder: &mut CodeActionBuilder) -> Option<()> {
+    let data = builder.document.data.as_bib()?;
+    let root = bibtex::Root::cast(data.root_node())?;
+    let entries = find_entries(&root);
+    let selected = find_selected(&entries, builder.range.start());
+
+    if let Some(entry) = selected {
+        let renames = regenerate_keys(builder, std::slice::from_ref(entry));
+        if let Some(rename) = renames.first() {
+            let title = format!("Regenerate citation key: {}", rename.new_key);
+            let data = CodeActionData::RegenerateKey {
+                uri: builder.document.uri.clone(),
+                position: builder
+                    .document
+                    .line_index
+                    .line_col_lsp(builder.range.start()),
+            };
+
+            push_action(builder, title, data, &renames);
+        }
+    }
+
+    if entries.len() > 1 || selected.is_none() {
+        let title = String::from("Regenerate all citation keys");
+        let data = CodeActionData::RegenerateAllKeys {
+            uri: builder.document.uri.clone(),
+        };
+
+        if builder.resolve_edits {
+            push_action(builder, title, data, &[]);
+        } else {
+            let renames = regenerate_keys(builder, &entries);
+            if !renames.is_empty() {
+                push_action(builder, title, data, &renames);
+            }
+        }
+    }
+
+    Some(())
+}
+
+/// Computes the edit of an action that has been returned by `find_actions`.
+pub(super) fn resolve(builder: &CodeActionBuilder, data: &CodeActionData) -> Option<WorkspaceEdit> {
+    let bib = builder.document.data.as_bib()?;
+    let root = bibtex::Root::cast(bib.root_node())?;
+    let entries = find_entries(&root);
+    let renames = match data {
+        CodeActionData::RegenerateKey { .. } => {
+            let entry = find_selected(&entries, builder.range.start())?;
+            regenerate_keys(builder, std::slice::from_ref(entry))
+        }
+        CodeActionData::RegenerateAllKeys { .. } => regenerate_keys(builder, &entries),
+    };
+
+    Some(create_edit(builder, &renames))
+}
+
+fn find_entries(root: &bibtex::Root) -> Vec<bibtex::Entry> {
+    root.entries()
+        .filter(|entry| entry.name_token().is_some())
+        .collect()
+}
+
+fn find_selected(entries: &[bibtex::Entry], offset: TextSize) -> Option<&bibtex::Entry> {
+    entries
+        .iter()
+        .find(|entry| entry.syntax().text_range().contains_inclusive(offset))
+}
+
+struct KeyRename {
+    range: TextRange,
+    old_key: String,
+    new_key: String,
+}
+
+/// Generates the new keys of `entries` in order. A key that is already used by another entry
+/// of the project is made unique by appending a suffix.
+fn regenerate_keys(builder: &CodeActionBuilder, entries: &[bibtex::Entry]) -> Vec<KeyRename> {
+    let ranges: FxHashSet<_> = entries
+        .iter()
+        .map(|entry| entry.syntax().text_range())
+        .collect();
+
+    let bibliography: Vec<_> = builder
+        .project
+        .documents
+        .iter()
+        .filter_map(|document| document.data.as_bib())
+        .filter_map(|data| bibtex::Root::cast(data.root_node()))
+        .collect();
+
+    let mut taken = FxHashSet::default();
+    for document in &builder.project.documents {
+        let Some(data) = document.data.as_bib() else { continue };
+        let is_current = document.uri == builder.document.uri;
+        for entry in data.root_node().children().filter_map(bibtex::Entry::cast) {
+            if is_current && ranges.contains(&entry.syntax().text_range()) {
+                continue;
+            }
+
+            if let Some(token) = entry.name_token() {
+                taken.insert(token.text().to_string());
+            }
+        }
+    }
+
+    let config = &builder.workspace.config_for(&builder.document.uri).citation;
+    let pattern = config.key_pattern.as_deref().unwrap_or(DEFAULT_KEY_PATTERN);
+
+    let mut renames = Vec::new();
+    for entry in entries {
+        let Some(token) = entry.name_token() else { continue };
+        let old_key = token.text().to_string();
+        let new_key = generate_key(entry, &bibliography, pattern)
+            .map_or_else(|| old_key.clone(), |key| commands::unique_key(&key, &taken));
+
+        taken.insert(new_key.clone());
+        if new_key != old_key {
+            renames.push(KeyRename {
+                range: token.text_range(),
+                old_key,
+                new_key,
+            });
+        }
+    }
+
+    renames
+}
+
+fn push_action(
+    builder: &mut CodeActionBuilder,
+    title: String,
+    data: CodeActionData,
+    renames: &[KeyRename],
+) {
+    let (edit, data) = if builder.resolve_edits {
+        (None, serde_json::to_value(data).ok())
+    } else {
+        (Some(create_edit(builder, renames)), None)
+    };
+
+    builder.actions.push(CodeAction {
+        title,
+        kind: Some(CodeActionKind::REFACTOR_REWRITE),
+        edit,
+        data,
+        ..CodeAction::default()
+    });
+}
+
+fn create_edit(builder: &CodeActionBuilder, renames: &[KeyRename]) -> WorkspaceEdit {
+    let mut new_keys = FxHashMap::default();
+    for rename in renames {
+        new_keys
+            .entry(rename.old_key.as_str())
+            .or_insert(rename.new_key.as_str());
+    }
+
+    let mut changes = HashMap::new();
+    for document in &builder.project.documents {
+        let edits: Vec<_> = match &document.data {
+            DocumentData::Tex(data) => data
+                .root_node()
+                .descendants()
+                .filter_map(latex::Citation::cast)
+                .filter_map(|citation| citation.key_list())
+                .flat_map(|keys| keys.keys())
+                .filter_map(|key| {
+                    let new_key = new_keys.get(key.to_string().as_str())?;
+                    let range = document
+                        .line_index
+                        .line_col_lsp_range(latex::small_range(&key));
+                    Some(TextEdit::new(range, new_key.to_string()))
+                })
+                .collect(),
+            DocumentData::Bib(_) if document.uri == builder.document.uri => renames
+                .iter()
+                .map(|rename| {
+                    let range = document.line_index.line_col_lsp_range(rename.range);
+                    TextEdit::new(range, rename.new_key.clone())
+                })
+                .collect(),
+            DocumentData::Bib(_)
+            | DocumentData::Csl(_)
+            | DocumentData::Aux(_)
+            | DocumentData::Log(_)
+            | DocumentData::Fls(_)
+            | DocumentData::Root(_)
+            | DocumentData::Tectonic(_) => continue,
+        };
+
+        if !edits.is_empty() {
+            changes.insert(document.uri.clone(), edits);
+        }
+    }
+
+    WorkspaceEdit::new(changes)
+}
@@ -0,0 +1,41 @@
+use base_db::diagnostics::ErrorCode;
+use lsp_types::{CodeAction, CodeActionKind, TextEdit, WorkspaceEdit};
+
+use crate::util::{diagnostics::create_diagnostic, line_index_ext::LineIndexExt};
+
+use super::CodeActionBuilder;
+
+/// Offers to correct the BibTeX field values under the cursor that have been reported
+/// as invalid if the correction is unambiguous.
+pub(super) fn find_actions(builder: &mut CodeActionBuilder) -> Option<()> {
+    let document = builder.document;
+    for diagnostic in &document.diagnostics {
+        if diagnostic.range.intersect(builder.range).is_none() {
+            continue;
+        }
+
+        let (title, new_text) = match &diagnostic.code {
+            ErrorCode::InvalidMonth(Some(month)) => {
+                (format!("Replace with \"{month}\""), month.to_string())
+            }
+            ErrorCode::InvalidPageRange(pages) => {
+                (format!("Replace with \"{pages}\""), pages.clone())
+            }
+            ErrorCode::DoiWithPrefix(doi) => (String::from("Remove DOI prefix"), doi.clone()),
+            _ => continue,
+        };
+
+        let range = document.line_index.line_col_lsp_range(diagnostic.range);
+        let changes = [(document.uri.clone(), vec![TextEdit::new(range, new_text)])];
+        builder.actions.push(CodeAction {
+            title,
+            kind: Some(CodeActionKind::QUICKFIX),
+            diagnostics: Some(vec![create_diagnostic(document, diagnostic)]),
+            edit: Some(WorkspaceEdit::new(changes.into_iter().collect())),
+            is_preferred: Some(true),
+            ..CodeAction::default()
+        });
+    }
+
+    Some(())
+}
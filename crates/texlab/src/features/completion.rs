@@ -14,15 +14,22 @@ mod import;
 mod include;
 mod label;
 mod matcher;
+mod string_ref;
 mod theorem;
 mod tikz_library;
 mod user_command;
 mod user_environment;
 
+use std::sync::Arc;
+
 use base_db::Workspace;
 use lsp_types::{ClientCapabilities, ClientInfo, CompletionList, Position, Url};
+use once_cell::unsync::OnceCell;
 
-use crate::{features::completion::builder::CompletionBuilder, util::cursor::CursorContext};
+use crate::{
+    features::completion::builder::CompletionBuilder,
+    util::{components::Component, cursor::CursorContext, packages::PACKAGE_INDEX},
+};
 
 pub const COMPLETION_LIMIT: usize = 50;
 
@@ -34,10 +41,12 @@ pub fn complete(
     client_info: Option<&ClientInfo>,
 ) -> Option<CompletionList> {
     let context = CursorContext::new(workspace, uri, position, ())?;
+    let packages = Packages::default();
     let mut builder = CompletionBuilder::new(&context, client_capabilities, client_info);
     log::debug!("[Completion] Cursor: {:?}", context.cursor);
     entry_type::complete(&context, &mut builder);
     field::complete(&context, &mut builder);
+    string_ref::complete(&context, &mut builder);
     argument::complete(&context, &mut builder);
     citation::complete(&context, &mut builder);
     import::complete(&context, &mut builder);
@@ -48,11 +57,28 @@ pub fn complete(
     include::complete(&context, &mut builder);
     label::complete(&context, &mut builder);
     tikz_library::complete(&context, &mut builder);
-    component_environment::complete(&context, &mut builder);
+    component_environment::complete(&context, &packages, &mut builder);
     theorem::complete(&context, &mut builder);
     user_environment::complete(&context, &mut builder);
     begin_snippet::complete(&context, &mut builder);
-    component_command::complete(&context, &mut builder);
+    component_command::complete(&context, &packages, &mut builder);
     user_command::complete(&context, &mut builder);
     Some(builder.finish())
 }
+
+/// The packages of the distribution that are loaded by the project.
+/// They are only looked up if a completion provider needs them.
+#[derive(Default)]
+pub struct Packages(OnceCell<Vec<Arc<Component>>>);
+
+impl Packages {
+    pub fn get(&self, context: &CursorContext) -> &[Arc<Component>] {
+        self.0.get_or_init(|| {
+            PACKAGE_INDEX.linked_components(
+                context.workspace,
+                &context.document.uri,
+                &context.project,
+            )
+        })
+    }
+}
@@ -2,6 +2,7 @@ use base_db::{
     data::{BibtexEntryType, BibtexEntryTypeCategory, BibtexFieldType},
     Document, MatchingAlgo,
 };
+use citeproc::field::text::TextFieldData;
 use fuzzy_matcher::skim::SkimMatcherV2;
 use itertools::Itertools;
 use lsp_types::{
@@ -14,8 +15,8 @@ use rowan::{ast::AstNode, TextRange, TextSize};
 use serde::{Deserialize, Serialize};
 use smol_str::SmolStr;
 use syntax::{
-    bibtex::{self, HasName, HasType},
-    latex,
+    bibtex::{self, HasName, HasType, HasValue},
+    csl, latex,
 };
 
 use crate::util::{
@@ -50,7 +51,12 @@ impl<'a> CompletionBuilder<'a> {
         client_info: Option<&'a ClientInfo>,
     ) -> Self {
         let items = Vec::new();
-        let matcher: Box<dyn Matcher> = match context.workspace.config().completion.matcher {
+        let matcher: Box<dyn Matcher> = match context
+            .workspace
+            .config_for(&context.document.uri)
+            .completion
+            .matcher
+        {
             MatchingAlgo::Skim => Box::<SkimMatcherV2>::default(),
             MatchingAlgo::SkimIgnoreCase => Box::new(SkimMatcherV2::default().ignore_case()),
             MatchingAlgo::Prefix => Box::new(matcher::Prefix),
@@ -184,16 +190,82 @@ impl<'a> CompletionBuilder<'a> {
         entry: &bibtex::Entry,
     ) -> Option<()> {
         let key = entry.name_token()?.to_string();
+        let ty = entry.type_token()?;
 
-        let category = BibtexEntryType::find(&entry.type_token()?.text()[1..])
+        let category = BibtexEntryType::find(&ty.text()[1..])
             .map_or(BibtexEntryTypeCategory::Misc, |ty| ty.category);
 
-        let code = entry.syntax().text().to_string();
+        let fields = entry
+            .fields()
+            .filter_map(|field| {
+                let name = field.name_token()?;
+                let value = field
+                    .value()
+                    .and_then(|value| TextFieldData::parse(&value))
+                    .map(|data| data.text)
+                    .unwrap_or_default();
+
+                Some(format!("{} {}", name.text(), value))
+            })
+            .join(" ");
+
+        let code = format!("{} {} {}", ty.text(), key, fields);
+        self.push_citation(range, document, key, &code, category)
+    }
+
+    pub fn citation_item(
+        &mut self,
+        range: TextRange,
+        document: &'a Document,
+        item: &csl::Item,
+    ) -> Option<()> {
+        let category = match item.kind.as_str() {
+            "article" | "article-journal" | "article-magazine" | "article-newspaper" => {
+                BibtexEntryTypeCategory::Article
+            }
+            "thesis" => BibtexEntryTypeCategory::Thesis,
+            "book" | "report" | "periodical" => BibtexEntryTypeCategory::Book,
+            "chapter" | "paper-conference" | "entry" | "entry-dictionary"
+            | "entry-encyclopedia" => BibtexEntryTypeCategory::Part,
+            _ => BibtexEntryTypeCategory::Misc,
+        };
+
+        let names = item.names.iter().map(|(name, values)| {
+            let values = values
+                .iter()
+                .map(|value| match &value.given {
+                    Some(given) => format!("{} {}", value.family, given),
+                    None => value.family.clone(),
+                })
+                .join(" ");
+
+            format!("{name} {values}")
+        });
+
+        let fields = item
+            .text
+            .iter()
+            .map(|(name, value)| format!("{name} {value}"))
+            .chain(names)
+            .join(" ");
+
+        let code = format!("{} {} {}", item.kind, item.key, fields);
+        self.push_citation(range, document, item.key.clone(), &code, category)
+    }
+
+    fn push_citation(
+        &mut self,
+        range: TextRange,
+        document: &'a Document,
+        key: String,
+        code: &str,
+        category: BibtexEntryTypeCategory,
+    ) -> Option<()> {
         let filter_text = format!(
             "{} {}",
             key,
             WHITESPACE_REGEX
-                .replace_all(&code.replace(['{', '}', ',', '='], " "), " ")
+                .replace_all(&code.replace(',', " "), " ")
                 .trim(),
         );
 
@@ -388,6 +460,18 @@ impl<'a> CompletionBuilder<'a> {
         Some(())
     }
 
+    pub fn string_ref(&mut self, range: TextRange, name: String, value: String) -> Option<()> {
+        let score = self.matcher.score(&name, &self.text_pattern)?;
+        self.items.push(Item {
+            range,
+            data: Data::StringRef { name, value },
+            preselect: false,
+            score,
+        });
+
+        Some(())
+    }
+
     pub fn tikz_library(&mut self, range: TextRange, name: &'a str) -> Option<()> {
         let score = self.matcher.score(name, &self.text_pattern)?;
         self.items.push(Item {
@@ -621,6 +705,13 @@ impl<'a> CompletionBuilder<'a> {
                     ..CompletionItem::new_simple(name.into(), detail)
                 }
             }
+            Data::StringRef { name, value } => CompletionItem {
+                label: name.clone(),
+                kind: Some(Structure::Entry(BibtexEntryTypeCategory::String).completion_kind()),
+                detail: Some(value),
+                text_edit: Some(TextEdit::new(range, name).into()),
+                ..CompletionItem::default()
+            },
             Data::TikzLibrary { name } => CompletionItem {
                 label: name.into(),
                 kind: Some(Structure::TikzLibrary.completion_kind()),
@@ -742,6 +833,10 @@ enum Data<'a> {
     UserEnvironment {
         name: &'a str,
     },
+    StringRef {
+        name: String,
+        value: String,
+    },
     TikzLibrary {
         name: &'a str,
     },
@@ -767,6 +862,7 @@ impl<'db> Data<'db> {
             Self::Label { name, .. } => name,
             Self::UserCommand { name } => name,
             Self::UserEnvironment { name } => name,
+            Self::StringRef { name, .. } => name,
             Self::TikzLibrary { name } => name,
         }
     }
@@ -27,10 +27,23 @@ pub fn complete<'db>(
 
     check_citation(context).or_else(|| check_acronym(context))?;
     for document in &context.project.documents {
-        let DocumentData::Bib(data) = &document.data else { continue };
-
-        for entry in data.root_node().children().filter_map(bibtex::Entry::cast) {
-            builder.citation(range, document, &entry);
+        match &document.data {
+            DocumentData::Bib(data) => {
+                for entry in data.root_node().children().filter_map(bibtex::Entry::cast) {
+                    builder.citation(range, document, &entry);
+                }
+            }
+            DocumentData::Csl(data) => {
+                for item in &data.bibliography.items {
+                    builder.citation_item(range, document, item);
+                }
+            }
+            DocumentData::Tex(_)
+            | DocumentData::Aux(_)
+            | DocumentData::Log(_)
+            | DocumentData::Fls(_)
+            | DocumentData::Root(_)
+            | DocumentData::Tectonic(_) => {}
         }
     }
 
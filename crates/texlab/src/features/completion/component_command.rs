@@ -1,14 +1,22 @@
+use std::sync::Arc;
+
 use crate::util::{components::COMPONENT_DATABASE, cursor::CursorContext};
 
-use super::builder::CompletionBuilder;
+use super::{builder::CompletionBuilder, Packages};
 
 pub fn complete<'db>(
     context: &'db CursorContext,
+    packages: &'db Packages,
     builder: &mut CompletionBuilder<'db>,
 ) -> Option<()> {
     let range = context.cursor.command_range(context.offset)?;
 
-    for component in COMPONENT_DATABASE.linked_components(&context.project) {
+    let components = COMPONENT_DATABASE
+        .linked_components(&context.project)
+        .into_iter()
+        .chain(packages.get(context).iter().map(Arc::as_ref));
+
+    for component in components {
         for command in &component.commands {
             builder.component_command(
                 range,
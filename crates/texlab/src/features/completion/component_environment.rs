@@ -1,14 +1,22 @@
+use std::sync::Arc;
+
 use crate::util::{components::COMPONENT_DATABASE, cursor::CursorContext};
 
-use super::builder::CompletionBuilder;
+use super::{builder::CompletionBuilder, Packages};
 
 pub fn complete<'db>(
     context: &'db CursorContext,
+    packages: &'db Packages,
     builder: &mut CompletionBuilder<'db>,
 ) -> Option<()> {
     let range = context.find_environment_name()?;
 
-    for component in COMPONENT_DATABASE.linked_components(&context.project) {
+    let components = COMPONENT_DATABASE
+        .linked_components(&context.project)
+        .into_iter()
+        .chain(packages.get(context).iter().map(Arc::as_ref));
+
+    for component in components {
         for name in &component.environments {
             builder.component_environment(range, name, &component.file_names);
         }
@@ -94,8 +94,7 @@ fn current_dir(
     let parent = context
         .workspace
         .parents(context.document)
-        .iter()
-        .next()
+        .first()
         .map_or(context.document, Clone::clone);
 
     let path = context
@@ -24,7 +24,7 @@ pub fn complete<'db>(
             .iter()
             .filter(|label| label.kind == LabelKind::Definition)
         {
-            match render_label(context.workspace, &context.project, label) {
+            match render_label(context.workspace, &context.project, document, label) {
                 Some(rendered_label) => {
                     let kind = match &rendered_label.object {
                         RenderedObject::Section { .. } => Structure::Section,
@@ -0,0 +1,45 @@
+use citeproc::field::{date::MONTH_MACROS, text::TextFieldData};
+use rowan::ast::AstNode;
+use syntax::bibtex::{self, HasName, HasValue};
+
+use crate::util::cursor::CursorContext;
+
+use super::builder::CompletionBuilder;
+
+pub fn complete<'db>(
+    context: &'db CursorContext,
+    builder: &mut CompletionBuilder<'db>,
+) -> Option<()> {
+    let name = context
+        .cursor
+        .as_bib()
+        .filter(|token| token.kind() == bibtex::NAME)?;
+
+    bibtex::Literal::cast(name.parent()?)?;
+    let range = name.text_range();
+
+    let bibliography: Vec<_> = context
+        .project
+        .documents
+        .iter()
+        .filter_map(|document| document.data.as_bib())
+        .filter_map(|data| bibtex::Root::cast(data.root_node()))
+        .collect();
+
+    for string in bibliography.iter().flat_map(|root| root.strings()) {
+        let Some(name) = string.name_token() else { continue };
+        let value = string
+            .value()
+            .and_then(|value| TextFieldData::parse_with(&value, &bibliography))
+            .map(|data| data.text)
+            .unwrap_or_default();
+
+        builder.string_ref(range, name.text().into(), value);
+    }
+
+    for (name, month) in MONTH_MACROS {
+        builder.string_ref(range, name.into(), month.into());
+    }
+
+    Some(())
+}
@@ -24,6 +24,18 @@ pub(super) fn goto_definition<'a>(
     let origin_selection_range = latex::small_range(&key);
 
     for document in &context.project.documents {
+        if let DocumentData::Csl(data) = &document.data {
+            let items = &data.bibliography.items;
+            if let Some(item) = items.iter().find(|item| item.key == word.text()) {
+                return Some(vec![DefinitionResult {
+                    origin_selection_range,
+                    target: document,
+                    target_selection_range: item.key_range,
+                    target_range: item.full_range,
+                }]);
+            }
+        }
+
         let DocumentData::Bib(data) = &document.data else { continue };
 
         for entry in data.root_node().children().filter_map(bibtex::Entry::cast) {
@@ -9,7 +9,7 @@ use super::DefinitionResult;
 pub(super) fn goto_definition<'a>(
     context: &CursorContext<'a>,
 ) -> Option<Vec<DefinitionResult<'a>>> {
-    let DocumentData::Bib(data) = &context.document.data else { return None };
+    let DocumentData::Bib(_) = &context.document.data else { return None };
 
     let key = context
         .cursor
@@ -20,16 +20,36 @@ pub(super) fn goto_definition<'a>(
 
     let origin_selection_range = key.text_range();
 
-    data.root_node()
-        .children()
-        .filter_map(bibtex::StringDef::cast)
-        .find_map(|string| {
-            let string_name = string.name_token().filter(|k| k.text() == key.text())?;
-            Some(vec![DefinitionResult {
-                origin_selection_range,
-                target: context.document,
-                target_selection_range: string_name.text_range(),
-                target_range: string.syntax().text_range(),
-            }])
-        })
+    // The strings of the current document take precedence over the other documents.
+    let documents = std::iter::once(context.document).chain(
+        context
+            .project
+            .documents
+            .iter()
+            .copied()
+            .filter(|document| document.uri != context.document.uri),
+    );
+
+    for document in documents {
+        let Some(data) = document.data.as_bib() else { continue };
+        for string in data
+            .root_node()
+            .children()
+            .filter_map(bibtex::StringDef::cast)
+        {
+            if let Some(name) = string
+                .name_token()
+                .filter(|k| k.text().eq_ignore_ascii_case(key.text()))
+            {
+                return Some(vec![DefinitionResult {
+                    origin_selection_range,
+                    target: document,
+                    target_selection_range: name.text_range(),
+                    target_range: string.syntax().text_range(),
+                }]);
+            }
+        }
+    }
+
+    None
 }
@@ -39,10 +39,12 @@ pub fn find_all(workspace: &Workspace, uri: &Url) -> Option<Vec<FoldingRange>> {
                 .map(|node| create_range(line_index.line_col_lsp_range(node.text_range())))
                 .collect()
         }
-        DocumentData::Aux(_)
+        DocumentData::Csl(_)
+        | DocumentData::Aux(_)
         | DocumentData::Log(_)
-        | DocumentData::Root
-        | DocumentData::Tectonic => {
+        | DocumentData::Fls(_)
+        | DocumentData::Root(_)
+        | DocumentData::Tectonic(_) => {
             return None;
         }
     };
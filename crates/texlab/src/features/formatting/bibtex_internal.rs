@@ -10,17 +10,8 @@ pub fn format_bibtex_internal(
     document: &Document,
     options: &FormattingOptions,
 ) -> Option<Vec<TextEdit>> {
-    let mut indent = String::new();
-
-    if options.insert_spaces {
-        for _ in 0..options.tab_size {
-            indent.push(' ');
-        }
-    } else {
-        indent.push('\t');
-    }
-
-    let line_length = workspace.config().formatting.line_length;
+    let indent = indent(options);
+    let line_length = workspace.config_for(&document.uri).formatting.line_length;
 
     let line_index = &document.line_index;
 
@@ -48,6 +39,35 @@ pub fn format_bibtex_internal(
     Some(edits)
 }
 
+/// Formats the source of a single entry that is not part of a document yet, e.g. an imported entry.
+pub fn format_bibtex_entry(
+    text: &str,
+    options: &FormattingOptions,
+    line_length: usize,
+) -> Option<String> {
+    let line_index = LineIndex::new(text);
+    let root = bibtex::SyntaxNode::new_root(parser::parse_bibtex(text));
+    let node = root.children().find(|node| node.kind() == bibtex::ENTRY)?;
+
+    let mut formatter = Formatter::new(indent(options), options.tab_size, line_length, &line_index);
+    formatter.visit_node(node);
+    Some(formatter.output)
+}
+
+fn indent(options: &FormattingOptions) -> String {
+    let mut indent = String::new();
+
+    if options.insert_spaces {
+        for _ in 0..options.tab_size {
+            indent.push(' ');
+        }
+    } else {
+        indent.push('\t');
+    }
+
+    indent
+}
+
 struct Formatter<'a> {
     indent: String,
     tab_size: u32,
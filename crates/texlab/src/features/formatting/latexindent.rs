@@ -15,7 +15,7 @@ pub fn format_with_latexindent(
     workspace: &Workspace,
     document: &Document,
 ) -> Option<Vec<TextEdit>> {
-    let config = workspace.config();
+    let config = workspace.config_for(&document.uri);
     let target_dir = tempdir().ok()?;
     let source_dir = workspace.current_dir(&document.dir).to_file_path().ok()?;
 
@@ -2,7 +2,7 @@ use lsp_types::MarkupKind;
 use rowan::ast::AstNode;
 use syntax::bibtex;
 
-use crate::util::cursor::CursorContext;
+use crate::util::{citations::CITATION_STYLES, cursor::CursorContext};
 
 use super::HoverResult;
 
@@ -13,10 +13,24 @@ pub(super) fn find_hover(context: &CursorContext) -> Option<HoverResult> {
         .or_else(|| context.find_entry_key())?;
 
     let value = context.project.documents.iter().find_map(|document| {
+        if let Some(data) = document.data.as_csl() {
+            let item = data
+                .bibliography
+                .items
+                .iter()
+                .find(|item| item.key == key)?;
+            return CITATION_STYLES.render_item(context.workspace, &context.document.uri, item);
+        }
+
         let data = document.data.as_bib()?;
         let root = bibtex::Root::cast(data.root_node())?;
         let entry = root.find_entry(&key)?;
-        citeproc::render(&entry)
+        CITATION_STYLES.render(
+            context.workspace,
+            &context.project,
+            &context.document.uri,
+            &entry,
+        )
     })?;
 
     Some(HoverResult {
@@ -14,10 +14,17 @@ pub(super) fn find_hover(context: &CursorContext) -> Option<HoverResult> {
         .project
         .documents
         .iter()
-        .filter_map(|document| document.data.as_tex())
-        .flat_map(|data| data.semantics.labels.iter())
-        .find(|label| label.kind == LabelKind::Definition && label.name.text == name_text)
-        .and_then(|label| render_label(context.workspace, &context.project, label))
+        .filter_map(|document| Some((*document, document.data.as_tex()?)))
+        .flat_map(|(document, data)| {
+            data.semantics
+                .labels
+                .iter()
+                .map(move |label| (document, label))
+        })
+        .find(|(_, label)| label.kind == LabelKind::Definition && label.name.text == name_text)
+        .and_then(|(document, label)| {
+            render_label(context.workspace, &context.project, document, label)
+        })
         .map(|label| HoverResult {
             range,
             value: label.reference(),
@@ -1,42 +1,35 @@
-use base_db::DocumentData;
-use citeproc::field::text::TextFieldData;
+use citeproc::field::{
+    date::month_macro,
+    text::{find_string, TextFieldData},
+};
 use lsp_types::MarkupKind;
 use rowan::ast::AstNode;
-use syntax::bibtex::{self, HasName, HasValue};
+use syntax::bibtex;
 
 use crate::util::cursor::CursorContext;
 
 use super::HoverResult;
 
 pub(super) fn find_hover(context: &CursorContext) -> Option<HoverResult> {
-    let DocumentData::Bib(data) = &context.document.data else { return None };
+    let root = bibtex::Root::cast(context.document.data.as_bib()?.root_node())?;
+    let (name, range) = context.find_string_name()?;
 
-    let name = context
-        .cursor
-        .as_bib()
-        .filter(|token| token.kind() == bibtex::NAME)
-        .filter(|token| {
-            let parent = token.parent().unwrap();
-            bibtex::Value::can_cast(parent.kind()) || bibtex::StringDef::can_cast(parent.kind())
-        })?;
+    let bibliography: Vec<_> = context
+        .project
+        .documents
+        .iter()
+        .filter_map(|document| document.data.as_bib())
+        .filter_map(|data| bibtex::Root::cast(data.root_node()))
+        .collect();
 
-    for string in data
-        .root_node()
-        .children()
-        .filter_map(bibtex::StringDef::cast)
-    {
-        if string
-            .name_token()
-            .map_or(false, |token| token.text() == name.text())
-        {
-            let value = TextFieldData::parse(&string.value()?)?.text;
-            return Some(HoverResult {
-                range: name.text_range(),
-                value,
-                value_kind: MarkupKind::PlainText,
-            });
-        }
-    }
+    let value = match find_string(&root, &bibliography, &name) {
+        Some(value) => TextFieldData::parse_with(&value, &bibliography)?.text,
+        None => month_macro(&name)?.to_string(),
+    };
 
-    None
+    Some(HoverResult {
+        range,
+        value,
+        value_kind: MarkupKind::PlainText,
+    })
 }
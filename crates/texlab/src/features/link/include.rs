@@ -4,8 +4,7 @@ pub(super) fn find_links(builder: &mut LinkBuilder) -> Option<()> {
     let parent = *builder
         .workspace
         .parents(builder.document)
-        .iter()
-        .next()
+        .first()
         .unwrap_or(&builder.document);
 
     let graph = base_db::graph::Graph::new(builder.workspace, parent);
@@ -44,11 +44,25 @@ pub(super) fn find_all_references<'a>(
                         results.push(ReferenceResult { document, range });
                     });
             }
+            DocumentData::Csl(data) if context.params.include_declaration => {
+                data.bibliography
+                    .items
+                    .iter()
+                    .filter(|item| item.key == key_text)
+                    .for_each(|item| {
+                        results.push(ReferenceResult {
+                            document,
+                            range: item.key_range,
+                        });
+                    });
+            }
             DocumentData::Bib(_)
+            | DocumentData::Csl(_)
             | DocumentData::Aux(_)
             | DocumentData::Log(_)
-            | DocumentData::Root
-            | DocumentData::Tectonic => {}
+            | DocumentData::Fls(_)
+            | DocumentData::Root(_)
+            | DocumentData::Tectonic(_) => {}
         };
     }
 
@@ -1,4 +1,3 @@
-use base_db::DocumentData;
 use lsp_types::ReferenceContext;
 use rowan::ast::AstNode;
 use syntax::bibtex::{self, HasName};
@@ -11,32 +10,26 @@ pub(super) fn find_all_references<'a>(
     context: &CursorContext<'a, &ReferenceContext>,
     results: &mut Vec<ReferenceResult<'a>>,
 ) -> Option<()> {
-    let name_text = context
-        .cursor
-        .as_bib()
-        .filter(|token| token.kind() == bibtex::NAME)
-        .filter(|token| {
-            let parent = token.parent().unwrap();
-            bibtex::Value::can_cast(parent.kind()) || bibtex::StringDef::can_cast(parent.kind())
-        })?
-        .text();
+    context.document.data.as_bib()?;
+    let (name_text, _) = context.find_string_name()?;
 
-    let DocumentData::Bib(data) = &context.document.data else { return None };
-
-    for node in data.root_node().descendants() {
-        if let Some(name) = bibtex::StringDef::cast(node.clone())
-            .and_then(|string| string.name_token())
-            .filter(|name| context.params.include_declaration && name.text() == name_text)
-            .or_else(|| {
-                bibtex::Value::cast(node)
-                    .and_then(|token| token.syntax().first_token())
-                    .filter(|name| name.text() == name_text)
-            })
-        {
-            results.push(ReferenceResult {
-                document: context.document,
-                range: name.text_range(),
-            });
+    for document in &context.project.documents {
+        let Some(data) = document.data.as_bib() else { continue };
+        for node in data.root_node().descendants() {
+            if let Some(name) = bibtex::StringDef::cast(node.clone())
+                .and_then(|string| string.name_token())
+                .filter(|name| context.params.include_declaration && name.text().eq_ignore_ascii_case(&name_text))
+                .or_else(|| {
+                    bibtex::Literal::cast(node)
+                        .and_then(|literal| literal.name_token())
+                        .filter(|name| name.text().eq_ignore_ascii_case(&name_text))
+                })
+            {
+                results.push(ReferenceResult {
+                    document,
+                    range: name.text_range(),
+                });
+            }
         }
     }
 
@@ -1,6 +1,7 @@
 mod command;
 mod entry;
 mod label;
+mod string;
 
 use base_db::{Document, Workspace};
 use lsp_types::{Position, Range, TextEdit, Url, WorkspaceEdit};
@@ -13,7 +14,8 @@ pub fn prepare_rename_all(workspace: &Workspace, uri: &Url, position: Position)
     let context = CursorContext::new(workspace, uri, position, ())?;
     let range = entry::prepare_rename(&context)
         .or_else(|| label::prepare_rename(&context))
-        .or_else(|| command::prepare_rename(&context))?;
+        .or_else(|| command::prepare_rename(&context))
+        .or_else(|| string::prepare_rename(&context))?;
 
     Some(context.document.line_index.line_col_lsp_range(range))
 }
@@ -27,7 +29,8 @@ pub fn rename_all(
     let context = CursorContext::new(workspace, uri, position, Params { new_name })?;
     let result = entry::rename(&context)
         .or_else(|| label::rename(&context))
-        .or_else(|| command::rename(&context))?;
+        .or_else(|| command::rename(&context))
+        .or_else(|| string::rename(&context))?;
 
     let changes = result
         .changes
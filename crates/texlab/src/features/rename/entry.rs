@@ -59,10 +59,25 @@ pub(super) fn rename<'a>(context: &CursorContext<'a, Params>) -> Option<RenameRe
 
                 changes.insert(*document, edits);
             }
+            DocumentData::Csl(data) => {
+                let edits: Vec<_> = data
+                    .bibliography
+                    .items
+                    .iter()
+                    .filter(|item| item.key == key_text)
+                    .map(|item| Indel {
+                        delete: item.key_range,
+                        insert: context.params.new_name.clone(),
+                    })
+                    .collect();
+
+                changes.insert(*document, edits);
+            }
             DocumentData::Aux(_)
             | DocumentData::Log(_)
-            | DocumentData::Root
-            | DocumentData::Tectonic => {}
+            | DocumentData::Fls(_)
+            | DocumentData::Root(_)
+            | DocumentData::Tectonic(_) => {}
         };
     }
 
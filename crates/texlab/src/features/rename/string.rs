@@ -0,0 +1,54 @@
+use rowan::{ast::AstNode, TextRange};
+use rustc_hash::FxHashMap;
+use syntax::bibtex::{self, HasName};
+
+use crate::util::cursor::CursorContext;
+
+use super::{Indel, Params, RenameResult};
+
+pub(super) fn prepare_rename<T>(context: &CursorContext<T>) -> Option<TextRange> {
+    let (name, range) = context.find_string_name()?;
+
+    // Built-in macros like the month abbreviations cannot be renamed.
+    context
+        .project
+        .documents
+        .iter()
+        .filter_map(|document| document.data.as_bib())
+        .flat_map(|data| data.root_node().children())
+        .filter_map(bibtex::StringDef::cast)
+        .filter_map(|string| string.name_token())
+        .find(|token| token.text().eq_ignore_ascii_case(&name))?;
+
+    Some(range)
+}
+
+pub(super) fn rename<'a>(context: &CursorContext<'a, Params>) -> Option<RenameResult<'a>> {
+    prepare_rename(context)?;
+    let (name, _) = context.find_string_name()?;
+
+    let mut changes = FxHashMap::default();
+    for document in &context.project.documents {
+        let Some(data) = document.data.as_bib() else { continue };
+        let edits: Vec<_> = data
+            .root_node()
+            .descendants()
+            .filter_map(|node| {
+                bibtex::StringDef::cast(node.clone())
+                    .and_then(|string| string.name_token())
+                    .or_else(|| bibtex::Literal::cast(node)?.name_token())
+            })
+            .filter(|token| token.text().eq_ignore_ascii_case(&name))
+            .map(|token| Indel {
+                delete: token.text_range(),
+                insert: context.params.new_name.clone(),
+            })
+            .collect();
+
+        if !edits.is_empty() {
+            changes.insert(*document, edits);
+        }
+    }
+
+    Some(RenameResult { changes })
+}
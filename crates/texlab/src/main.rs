@@ -1,10 +1,17 @@
-use std::{fs::OpenOptions, io, path::PathBuf};
+use std::{
+    fs::OpenOptions,
+    io,
+    path::{Component, Path, PathBuf},
+};
 
 use anyhow::Result;
-use clap::{ArgAction, Parser};
+use clap::{ArgAction, Args, Parser, Subcommand};
 use log::LevelFilter;
 use lsp_server::Connection;
-use texlab::Server;
+use texlab::{
+    util::ipc::{send_inverse_search, InverseSearchParams},
+    Server,
+};
 
 /// An implementation of the Language Server Protocol for LaTeX
 #[derive(Debug, Parser)]
@@ -21,12 +28,39 @@ struct Opts {
     /// Write the logging output to FILE
     #[clap(long, name = "FILE", value_parser)]
     log_file: Option<PathBuf>,
+
+    #[clap(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Opens a location in the editor that is connected to a running server
+    InverseSearch(InverseSearchOpts),
+}
+
+#[derive(Debug, Args)]
+struct InverseSearchOpts {
+    /// The TeX file to open
+    #[clap(short, long, value_parser)]
+    input: PathBuf,
+
+    /// The one-based line number
+    #[clap(short, long)]
+    line: u32,
 }
 
 fn main() -> Result<()> {
-    let opts = Opts::parse();
+    let mut opts = Opts::parse();
+    let command = opts.command.take();
     setup_logger(opts);
 
+    if let Some(Command::InverseSearch(opts)) = command {
+        let path = normalize_path(&std::env::current_dir()?.join(opts.input));
+        let line = opts.line.saturating_sub(1);
+        return send_inverse_search(&InverseSearchParams { path, line });
+    }
+
     let (connection, threads) = Connection::stdio();
     Server::new(connection).run()?;
     threads.join()?;
@@ -34,6 +68,23 @@ fn main() -> Result<()> {
     Ok(())
 }
 
+/// Removes the `.` and `..` components of an absolute path without resolving symbolic links
+/// so that the path can be compared with the workspace folders of the running servers.
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut result = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                result.pop();
+            }
+            _ => result.push(component),
+        };
+    }
+
+    result
+}
+
 fn setup_logger(opts: Opts) {
     let verbosity_level = if !opts.quiet {
         match opts.verbosity {
@@ -1,50 +1,68 @@
 mod dispatch;
 mod extensions;
+mod import;
 pub mod options;
 mod progress;
 
 use std::{
     collections::HashMap,
-    path::PathBuf,
-    sync::{atomic::AtomicI32, Arc},
+    path::{Path, PathBuf},
+    sync::Arc,
 };
 
 use anyhow::Result;
-use base_db::{util::LineCol, Config, Owner, Workspace};
+use base_db::{util::LineCol, Config, Document, Owner, Workspace};
 use commands::{BuildCommand, CleanCommand, CleanTarget, ForwardSearch};
 use crossbeam_channel::{Receiver, Sender};
 use distro::{Distro, Language};
 use lsp_server::{Connection, ErrorCode, Message, RequestId};
 use lsp_types::{notification::*, request::*, *};
 use parking_lot::{Mutex, RwLock};
+use parser::BuildLogParser;
 use rowan::ast::AstNode;
 use rustc_hash::{FxHashMap, FxHashSet};
 use serde::{de::DeserializeOwned, Serialize};
-use syntax::bibtex;
+use smol_str::SmolStr;
+use syntax::{bibtex, BuildError, BuildEvent};
 use threadpool::ThreadPool;
 
 use crate::{
     client::LspClient,
     features::{
+        code_action,
         completion::{self, builder::CompletionItemData},
         definition, folding, formatting, highlight, hover, inlay_hint, link, reference, rename,
         symbols,
     },
     util::{
-        self, capabilities::ClientCapabilitiesExt, components::COMPONENT_DATABASE,
-        line_index_ext::LineIndexExt, normalize_uri,
+        self,
+        capabilities::ClientCapabilitiesExt,
+        citations::CITATION_STYLES,
+        components::COMPONENT_DATABASE,
+        diagnostics::DiagnosticState,
+        ipc::{InverseSearchParams, IpcListener},
+        line_index_ext::LineIndexExt,
+        normalize_uri,
+        packages::{self, PACKAGE_INDEX},
     },
 };
 
 use self::{
     extensions::{
-        BuildParams, BuildRequest, BuildResult, BuildStatus, ForwardSearchRequest,
-        ForwardSearchResult, ForwardSearchStatus,
+        BuildLogEvent, BuildLogNotification, BuildLogParams, BuildParams, BuildRequest,
+        BuildResult, BuildStatus, DuplicateEntry, FindDuplicateEntriesParams, ForwardSearchRequest,
+        ForwardSearchResult, ForwardSearchStatus, ImportEntryParams, ImportEntryResult,
+        MergeEntriesParams, PinRootParams, SortEntriesOrder, SortEntriesParams,
+        SynctexForwardRequest, SynctexForwardResult, SynctexInverseParams, SynctexInverseRequest,
     },
+    import::ImportEntryTask,
     options::{Options, StartupOptions},
     progress::ProgressReporter,
 };
 
+/// The number of files that are added to the workspace at once while indexing.
+const INDEXING_BATCH_SIZE: usize = 64;
+
 #[derive(Debug)]
 enum InternalMessage {
     SetDistro(Distro),
@@ -53,6 +71,10 @@ enum InternalMessage {
     Diagnostics,
     ChktexResult(Url, Vec<lsp_types::Diagnostic>),
     ForwardSearch(Url, Option<Position>),
+    BuildLog(Url, Vec<BuildError>),
+    BuildFinished(Url),
+    InverseSearch(InverseSearchParams),
+    IndexingFinished,
 }
 
 pub struct Server {
@@ -64,9 +86,13 @@ pub struct Server {
     client_capabilities: Arc<ClientCapabilities>,
     client_info: Option<Arc<ClientInfo>>,
     chktex_diagnostics: FxHashMap<Url, Vec<Diagnostic>>,
+    build_logs: FxHashMap<Url, Vec<BuildError>>,
+    diagnostics: DiagnosticState,
+    versions: FxHashMap<Url, i32>,
     watcher: FileWatcher,
     pool: ThreadPool,
     pending_builds: Arc<Mutex<FxHashSet<u32>>>,
+    ipc_listener: Option<IpcListener>,
 }
 
 impl Server {
@@ -84,9 +110,13 @@ impl Server {
             client_capabilities: Default::default(),
             client_info: Default::default(),
             chktex_diagnostics: Default::default(),
+            build_logs: Default::default(),
+            diagnostics: Default::default(),
+            versions: Default::default(),
             watcher,
             pool: threadpool::Builder::new().build(),
             pending_builds: Default::default(),
+            ipc_listener: None,
         }
     }
 
@@ -163,15 +193,35 @@ impl Server {
             })),
             document_highlight_provider: Some(OneOf::Left(true)),
             document_formatting_provider: Some(OneOf::Left(true)),
+            code_action_provider: Some(CodeActionProviderCapability::Options(CodeActionOptions {
+                resolve_provider: Some(true),
+                ..CodeActionOptions::default()
+            })),
             execute_command_provider: Some(ExecuteCommandOptions {
                 commands: vec![
                     "texlab.cleanAuxiliary".into(),
                     "texlab.cleanArtifacts".into(),
                     "texlab.changeEnvironment".into(),
+                    "texlab.pinRoot".into(),
+                    "texlab.importEntry".into(),
+                    "texlab.sortEntries".into(),
+                    "texlab.findDuplicateEntries".into(),
+                    "texlab.mergeEntries".into(),
                 ],
                 ..Default::default()
             }),
             inlay_hint_provider: Some(OneOf::Left(true)),
+            diagnostic_provider: self
+                .client_capabilities
+                .has_pull_diagnostics_support()
+                .then(|| {
+                    DiagnosticServerCapabilities::Options(DiagnosticOptions {
+                        identifier: Some("texlab".into()),
+                        inter_file_dependencies: true,
+                        workspace_diagnostics: true,
+                        work_done_progress_options: WorkDoneProgressOptions::default(),
+                    })
+                }),
             ..ServerCapabilities::default()
         }
     }
@@ -183,7 +233,7 @@ impl Server {
         self.client_capabilities = Arc::new(params.capabilities);
         self.client_info = params.client_info.map(Arc::new);
 
-        let workspace_folders = params
+        let workspace_folders: Vec<_> = params
             .workspace_folders
             .unwrap_or_default()
             .into_iter()
@@ -191,6 +241,13 @@ impl Server {
             .flat_map(|folder| folder.uri.to_file_path())
             .collect();
 
+        let sender = self.internal_tx.clone();
+        self.ipc_listener = IpcListener::bind(workspace_folders.clone(), move |params| {
+            let _ = sender.send(InternalMessage::InverseSearch(params));
+        })
+        .map_err(|why| log::warn!("Unable to listen for inverse search requests: {why}"))
+        .ok();
+
         self.workspace.write().set_folders(workspace_folders);
 
         let result = InitializeResult {
@@ -207,10 +264,21 @@ impl Server {
             serde_json::from_value(params.initialization_options.unwrap_or_default())
                 .unwrap_or_default();
 
+        // Clients that skip the distribution (e.g. the tests) do not use the on-disk caches either.
         if !skip_distro {
+            if let Some(cache_dir) = dirs::cache_dir() {
+                let mut workspace = self.workspace.write();
+                workspace.load_semantics_cache(&cache_dir.join("texlab"));
+            }
+
             let sender = self.internal_tx.clone();
             self.pool.execute(move || {
-                let distro = Distro::detect().unwrap_or_else(|why| {
+                let distro = match dirs::cache_dir() {
+                    Some(cache_dir) => Distro::detect_cached(&cache_dir.join("texlab")),
+                    None => Distro::detect(),
+                };
+
+                let distro = distro.unwrap_or_else(|why| {
                     log::warn!("Unable to load distro files: {}", why);
                     Distro::default()
                 });
@@ -258,9 +326,11 @@ impl Server {
         self.publish_diagnostics_with_delay();
     }
 
-    fn publish_diagnostics(&mut self) -> Result<()> {
+    /// Updates the diagnostics and returns the documents whose diagnostics have changed.
+    fn update_diagnostics(&mut self) -> Vec<Url> {
         let workspace = self.workspace.read();
-        let mut all_diagnostics = util::diagnostics::collect(&workspace);
+        let mut all_diagnostics =
+            util::diagnostics::collect(&workspace, &self.build_logs, &mut self.diagnostics);
 
         for (uri, diagnostics) in &self.chktex_diagnostics {
             let Some(document) = workspace.lookup(uri) else { continue };
@@ -270,9 +340,42 @@ impl Server {
 
         util::diagnostics::filter(&mut all_diagnostics, &workspace);
 
-        for (document, diagnostics) in all_diagnostics {
-            let uri = document.uri.clone();
-            let version = None;
+        let all_diagnostics = all_diagnostics
+            .into_iter()
+            .map(|(document, diagnostics)| (document.uri.clone(), diagnostics))
+            .collect();
+
+        drop(workspace);
+        self.diagnostics.update(all_diagnostics)
+    }
+
+    fn publish_diagnostics(&mut self) -> Result<()> {
+        let changed = self.update_diagnostics();
+        if changed.is_empty() {
+            return Ok(());
+        }
+
+        if self.client_capabilities.has_pull_diagnostics_support() {
+            if self.client_capabilities.has_diagnostic_refresh_support() {
+                let client = self.client.clone();
+                self.pool.execute(move || {
+                    if let Err(why) = client.send_request::<WorkspaceDiagnosticRefresh>(()) {
+                        log::error!("Failed to refresh diagnostics: {why}");
+                    }
+                });
+            }
+
+            return Ok(());
+        }
+
+        for uri in changed {
+            let diagnostics = self
+                .diagnostics
+                .get(&uri)
+                .map(|entry| entry.diagnostics.clone())
+                .unwrap_or_default();
+
+            let version = self.versions.get(&uri).copied();
             let params = PublishDiagnosticsParams {
                 uri,
                 diagnostics,
@@ -286,6 +389,93 @@ impl Server {
         Ok(())
     }
 
+    fn document_diagnostic(
+        &mut self,
+        id: RequestId,
+        params: DocumentDiagnosticParams,
+    ) -> Result<()> {
+        let mut uri = params.text_document.uri;
+        normalize_uri(&mut uri);
+
+        self.update_diagnostics();
+        let report = match self.diagnostics.get(&uri) {
+            Some(entry) if params.previous_result_id.as_ref() == Some(&entry.result_id) => {
+                DocumentDiagnosticReport::Unchanged(RelatedUnchangedDocumentDiagnosticReport {
+                    related_documents: None,
+                    unchanged_document_diagnostic_report: UnchangedDocumentDiagnosticReport {
+                        result_id: entry.result_id.clone(),
+                    },
+                })
+            }
+            entry => DocumentDiagnosticReport::Full(RelatedFullDocumentDiagnosticReport {
+                related_documents: None,
+                full_document_diagnostic_report: FullDocumentDiagnosticReport {
+                    result_id: entry.map(|entry| entry.result_id.clone()),
+                    items: entry
+                        .map(|entry| entry.diagnostics.clone())
+                        .unwrap_or_default(),
+                },
+            }),
+        };
+
+        let result = DocumentDiagnosticReportResult::Report(report);
+        self.client
+            .send_response(lsp_server::Response::new_ok(id, result))?;
+        Ok(())
+    }
+
+    fn workspace_diagnostic(
+        &mut self,
+        id: RequestId,
+        params: WorkspaceDiagnosticParams,
+    ) -> Result<()> {
+        self.update_diagnostics();
+
+        let previous_result_ids: FxHashMap<_, _> = params
+            .previous_result_ids
+            .into_iter()
+            .map(|mut previous| {
+                normalize_uri(&mut previous.uri);
+                (previous.uri, previous.value)
+            })
+            .collect();
+
+        let items = self
+            .diagnostics
+            .iter()
+            .map(|(uri, entry)| {
+                let uri = uri.clone();
+                let version = self.versions.get(&uri).map(|&version| version.into());
+                if previous_result_ids.get(&uri) == Some(&entry.result_id) {
+                    WorkspaceDocumentDiagnosticReport::Unchanged(
+                        WorkspaceUnchangedDocumentDiagnosticReport {
+                            uri,
+                            version,
+                            unchanged_document_diagnostic_report:
+                                UnchangedDocumentDiagnosticReport {
+                                    result_id: entry.result_id.clone(),
+                                },
+                        },
+                    )
+                } else {
+                    WorkspaceDocumentDiagnosticReport::Full(WorkspaceFullDocumentDiagnosticReport {
+                        uri,
+                        version,
+                        full_document_diagnostic_report: FullDocumentDiagnosticReport {
+                            result_id: Some(entry.result_id.clone()),
+                            items: entry.diagnostics.clone(),
+                        },
+                    })
+                }
+            })
+            .collect();
+
+        let result = WorkspaceDiagnosticReportResult::Report(WorkspaceDiagnosticReport { items });
+        self.client
+            .send_response(lsp_server::Response::new_ok(id, result))?;
+        Ok(())
+    }
+
     fn publish_diagnostics_with_delay(&mut self) {
         let sender = self.internal_tx.clone();
         let delay = self.workspace.read().config().diagnostics.delay;
@@ -327,8 +517,68 @@ impl Server {
 
     fn update_options(&mut self, options: Options) {
         let mut workspace = self.workspace.write();
+        let old_indexing = workspace.config().indexing.clone();
         workspace.set_config(Config::from(options));
         self.watcher.watch(&mut workspace);
+
+        let indexing = &workspace.config().indexing;
+        if indexing.enabled && *indexing != old_indexing {
+            drop(workspace);
+            self.index_workspace();
+        }
+    }
+
+    fn index_workspace(&self) {
+        let workspace = self.workspace.read();
+        let config = workspace.config().indexing.clone();
+        let folders = workspace.folders().to_vec();
+        drop(workspace);
+
+        let workspace = Arc::clone(&self.workspace);
+        let client = self.client.clone();
+        let sender = self.internal_tx.clone();
+        let progress = self.client_capabilities.has_work_done_progress_support();
+        self.pool.execute(move || {
+            let progress_reporter =
+                progress.then(|| ProgressReporter::new(client, "Indexing", None));
+
+            let known_paths: FxHashSet<_> = workspace
+                .read()
+                .iter()
+                .filter_map(|document| document.path.clone())
+                .collect();
+
+            let files: Vec<_> = util::indexing::scan(&folders, &config)
+                .into_iter()
+                .filter(|(path, _)| !known_paths.contains(path))
+                .collect();
+
+            // The files are parsed while holding a read lock so that requests can still be answered.
+            // Only the insertion of a batch blocks the workspace.
+            log::info!("Indexing {} files in the workspace folders", files.len());
+            for (i, batch) in files.chunks(INDEXING_BATCH_SIZE).enumerate() {
+                if let Some(reporter) = &progress_reporter {
+                    let count = i * INDEXING_BATCH_SIZE + batch.len();
+                    let percentage = (count * 100 / files.len()) as u32;
+                    reporter.report(format!("{}/{}", count, files.len()), percentage);
+                }
+
+                let documents: Vec<_> = {
+                    let workspace = workspace.read();
+                    batch
+                        .iter()
+                        .filter_map(|(path, language)| {
+                            workspace.parse_file(path, *language, Owner::Server).ok()
+                        })
+                        .collect()
+                };
+
+                workspace.write().extend(documents);
+            }
+
+            drop(progress_reporter);
+            let _ = sender.send(InternalMessage::IndexingFinished);
+        });
     }
 
     fn cancel(&self, _params: CancelParams) -> Result<()> {
@@ -354,9 +604,18 @@ impl Server {
         let mut uri = params.text_document.uri;
         normalize_uri(&mut uri);
 
+        self.versions
+            .insert(uri.clone(), params.text_document.version);
+
+        let package_links = self.package_links(&uri);
         let language_id = &params.text_document.language_id;
-        let language = Language::from_id(language_id).unwrap_or(Language::Tex);
-        self.workspace.write().open(
+        let mut workspace = self.workspace.write();
+        let language = Language::from_id(language_id)
+            .or_else(|| workspace.lookup(&uri).map(|document| document.language))
+            .or_else(|| workspace.bibliography_language(&uri))
+            .unwrap_or(Language::Tex);
+
+        workspace.open(
             uri.clone(),
             params.text_document.text,
             language,
@@ -364,9 +623,18 @@ impl Server {
             LineCol { line: 0, col: 0 },
         );
 
+        drop(workspace);
         self.update_workspace();
-
-        if self.workspace.read().config().diagnostics.chktex.on_open {
+        self.warm_package_index(&uri, package_links);
+
+        if self
+            .workspace
+            .read()
+            .config_for(&uri)
+            .diagnostics
+            .chktex
+            .on_open
+        {
             self.run_chktex(&uri);
         }
 
@@ -376,7 +644,10 @@ impl Server {
     fn did_change(&mut self, params: DidChangeTextDocumentParams) -> Result<()> {
         let mut uri = params.text_document.uri;
         normalize_uri(&mut uri);
+        self.versions
+            .insert(uri.clone(), params.text_document.version);
 
+        let package_links = self.package_links(&uri);
         let mut workspace = self.workspace.write();
 
         for change in params.content_changes {
@@ -405,8 +676,16 @@ impl Server {
 
         drop(workspace);
         self.update_workspace();
-
-        if self.workspace.read().config().diagnostics.chktex.on_edit {
+        self.warm_package_index(&uri, package_links);
+
+        if self
+            .workspace
+            .read()
+            .config_for(&uri)
+            .diagnostics
+            .chktex
+            .on_edit
+        {
             self.run_chktex(&uri);
         }
 
@@ -417,7 +696,7 @@ impl Server {
         let mut uri = params.text_document.uri;
         normalize_uri(&mut uri);
 
-        if self.workspace.read().config().build.on_save {
+        if self.workspace.read().config_for(&uri).build.on_save {
             let text_document = TextDocumentIdentifier::new(uri.clone());
             let params = BuildParams {
                 text_document,
@@ -429,7 +708,14 @@ impl Server {
 
         self.publish_diagnostics_with_delay();
 
-        if self.workspace.read().config().diagnostics.chktex.on_save {
+        if self
+            .workspace
+            .read()
+            .config_for(&uri)
+            .diagnostics
+            .chktex
+            .on_save
+        {
             self.run_chktex(&uri);
         }
 
@@ -439,11 +725,32 @@ impl Server {
     fn did_close(&mut self, params: DidCloseTextDocumentParams) -> Result<()> {
         let mut uri = params.text_document.uri;
         normalize_uri(&mut uri);
+        self.versions.remove(&uri);
         self.workspace.write().close(&uri);
         self.publish_diagnostics_with_delay();
         Ok(())
     }
 
+    fn package_links(&self, uri: &Url) -> Vec<SmolStr> {
+        let workspace = self.workspace.read();
+        workspace.lookup(uri).map_or_else(Vec::new, |document| {
+            packages::package_links(document).collect()
+        })
+    }
+
+    /// Analyzes the packages of the project in the background if the packages
+    /// that are loaded by the document at `uri` have changed.
+    fn warm_package_index(&self, uri: &Url, old_links: Vec<SmolStr>) {
+        if self.package_links(uri) == old_links {
+            return;
+        }
+
+        let workspace = Arc::clone(&self.workspace);
+        let uri = uri.clone();
+        self.pool
+            .execute(move || PACKAGE_INDEX.warm_up(&workspace, &uri));
+    }
+
     fn run_chktex(&mut self, uri: &Url) {
         let workspace = self.workspace.read();
         let Some(document) = workspace.lookup(uri) else { return };
@@ -536,19 +843,31 @@ impl Server {
                         .map(Documentation::MarkupContent);
                 }
                 Some(CompletionItemData::Citation { uri, key }) => {
-                    if let Some(data) = workspace
-                        .lookup(&uri)
-                        .and_then(|document| document.data.as_bib())
-                    {
-                        item.documentation = bibtex::Root::cast(data.root_node())
-                            .and_then(|root| root.find_entry(&key))
-                            .and_then(|entry| citeproc::render(&entry))
-                            .map(|value| {
-                                Documentation::MarkupContent(MarkupContent {
-                                    kind: MarkupKind::Markdown,
-                                    value,
+                    if let Some(document) = workspace.lookup(&uri) {
+                        let project = workspace.project(document);
+                        let value = if let Some(data) = document.data.as_csl() {
+                            data.bibliography
+                                .items
+                                .iter()
+                                .find(|item| item.key == key)
+                                .and_then(|item| CITATION_STYLES.render_item(workspace, &uri, item))
+                        } else {
+                            document
+                                .data
+                                .as_bib()
+                                .and_then(|data| bibtex::Root::cast(data.root_node()))
+                                .and_then(|root| root.find_entry(&key))
+                                .and_then(|entry| {
+                                    CITATION_STYLES.render(workspace, &project, &uri, &entry)
                                 })
-                            });
+                        };
+
+                        item.documentation = value.map(|value| {
+                            Documentation::MarkupContent(MarkupContent {
+                                kind: MarkupKind::Markdown,
+                                value,
+                            })
+                        });
                     }
                 }
                 None => {}
@@ -660,6 +979,42 @@ impl Server {
                     client.send_request::<ApplyWorkspaceEdit>(params?)
                 });
             }
+            "texlab.pinRoot" => {
+                let result = self.pin_root(params);
+                self.run_fallible(id, move || result);
+            }
+            "texlab.importEntry" => {
+                let client = self.client.clone();
+                let task = self.prepare_import_entry(params);
+                self.run_fallible(id, move || {
+                    let (params, key) = task?.run()?;
+                    let response = client.send_request::<ApplyWorkspaceEdit>(params)?;
+                    if !response.applied {
+                        let reason = response.failure_reason.unwrap_or_default();
+                        anyhow::bail!("Unable to insert the entry {key}: {reason}");
+                    }
+
+                    Ok(ImportEntryResult { key })
+                });
+            }
+            "texlab.sortEntries" => {
+                let client = self.client.clone();
+                let params = self.sort_entries(params);
+                self.run_fallible(id, move || {
+                    client.send_request::<ApplyWorkspaceEdit>(params?)
+                });
+            }
+            "texlab.findDuplicateEntries" => {
+                let result = self.find_duplicate_entries(params);
+                self.run_fallible(id, move || result);
+            }
+            "texlab.mergeEntries" => {
+                let client = self.client.clone();
+                let params = self.merge_entries(params);
+                self.run_fallible(id, move || {
+                    client.send_request::<ApplyWorkspaceEdit>(params?)
+                });
+            }
             "texlab.showDependencyGraph" => {
                 let workspace = self.workspace.read();
                 let dot = commands::show_dependency_graph(&workspace).unwrap();
@@ -715,7 +1070,6 @@ impl Server {
 
     fn build(&self, id: Option<RequestId>, params: BuildParams) -> Result<()> {
         static LOCK: Mutex<()> = Mutex::new(());
-        static NEXT_TOKEN: AtomicI32 = AtomicI32::new(1);
 
         let mut uri = params.text_document.uri;
         normalize_uri(&mut uri);
@@ -724,10 +1078,19 @@ impl Server {
 
         let client = self.client.clone();
 
-        let fwd_search_after = workspace.config().build.forward_search_after;
+        let fwd_search_after = workspace.config_for(&uri).build.forward_search_after;
+
+        let root_document = workspace.lookup(&uri).map(|document| {
+            workspace
+                .parents(document)
+                .into_iter()
+                .next()
+                .unwrap_or(document)
+        });
 
         let (sender, receiver) = crossbeam_channel::unbounded();
-        self.redirect_build_log(receiver);
+        let (status_sender, status_receiver) = crossbeam_channel::bounded(1);
+        self.redirect_build_log(uri.clone(), root_document, receiver, status_receiver);
 
         let command = BuildCommand::new(&workspace, &uri);
         let internal = self.internal_tx.clone();
@@ -738,8 +1101,8 @@ impl Server {
             let guard = LOCK.lock();
 
             let progress_reporter = if progress {
-                let token = NEXT_TOKEN.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
-                Some(ProgressReporter::new(client.clone(), token, &uri))
+                let message = Some(String::from(uri.as_str()));
+                Some(ProgressReporter::new(client.clone(), "Building", message))
             } else {
                 None
             };
@@ -771,6 +1134,8 @@ impl Server {
             drop(progress_reporter);
             drop(guard);
 
+            let _ = status_sender.send(status);
+
             if let Some(id) = id {
                 let result = BuildResult { status };
                 let _ = client.send_response(lsp_server::Response::new_ok(id, result));
@@ -784,18 +1149,100 @@ impl Server {
         Ok(())
     }
 
-    fn redirect_build_log(&self, receiver: Receiver<String>) {
+    fn redirect_build_log(
+        &self,
+        uri: Url,
+        root_document: Option<&Document>,
+        receiver: Receiver<String>,
+        status_receiver: Receiver<BuildStatus>,
+    ) {
         let client = self.client.clone();
+        let internal = self.internal_tx.clone();
+        let root_uri = root_document.map(|document| document.uri.clone());
+        let base_dir = root_document
+            .and_then(|document| document.path.as_deref())
+            .and_then(Path::parent)
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+
         self.pool.execute(move || {
             let typ = MessageType::LOG;
+            let mut parser = BuildLogParser::new();
+            let mut errors = Vec::new();
+            let mut report = |events: Vec<BuildEvent>, force: bool| {
+                let mut changed = force;
+                for event in events {
+                    if let BuildEvent::Diagnostic(error) = &event {
+                        if !errors.contains(error) {
+                            errors.push(error.clone());
+                            changed = true;
+                        }
+                    }
+
+                    let event = BuildLogEvent::new(event, &base_dir);
+                    let params = BuildLogParams {
+                        uri: uri.clone(),
+                        event,
+                    };
+
+                    client
+                        .send_notification::<BuildLogNotification>(params)
+                        .unwrap();
+                }
+
+                if let Some(root_uri) = root_uri.as_ref().filter(|_| changed) {
+                    let _ =
+                        internal.send(InternalMessage::BuildLog(root_uri.clone(), errors.clone()));
+                }
+            };
+
+            let mut started = false;
             for message in receiver {
+                let events = parser.push(&message);
                 client
                     .send_notification::<LogMessage>(LogMessageParams { message, typ })
                     .unwrap();
+
+                report(events, !started);
+                started = true;
+            }
+
+            report(parser.finish(), false);
+
+            let status = status_receiver.recv().unwrap_or(BuildStatus::FAILURE);
+            let event = BuildLogEvent::Finished { status };
+            client
+                .send_notification::<BuildLogNotification>(BuildLogParams { uri, event })
+                .unwrap();
+
+            if let Some(root_uri) = root_uri {
+                let _ = internal.send(InternalMessage::BuildFinished(root_uri));
             }
         });
     }
 
+    fn finish_build(&mut self, root_uri: &Url) {
+        self.build_logs.remove(root_uri);
+
+        let mut workspace = self.workspace.write();
+        let paths = workspace
+            .lookup(root_uri)
+            .map(|document| workspace.project(document).documents)
+            .into_iter()
+            .flatten()
+            .filter(|document| document.language == Language::Log)
+            .filter(|document| document.owner == Owner::Server)
+            .filter_map(|document| document.path.clone())
+            .collect::<Vec<_>>();
+
+        for path in paths {
+            let _ = workspace.load(&path, Language::Log, Owner::Server);
+        }
+
+        drop(workspace);
+        self.update_workspace();
+    }
+
     fn forward_search(
         &self,
         id: Option<RequestId>,
@@ -831,15 +1278,95 @@ impl Server {
         Ok(())
     }
 
-    fn code_actions(&self, id: RequestId, _params: CodeActionParams) -> Result<()> {
-        self.client
-            .send_response(lsp_server::Response::new_ok(id, Vec::<CodeAction>::new()))?;
+    fn synctex_forward(&self, id: RequestId, params: TextDocumentPositionParams) -> Result<()> {
+        let mut uri = params.text_document.uri;
+        normalize_uri(&mut uri);
+
+        let workspace = Arc::clone(&self.workspace);
+        self.run_fallible(id, move || {
+            let line = params.position.line;
+            let result = commands::synctex_forward(&workspace.read(), &uri, line)?;
+            Ok(result.and_then(|(pdf_path, rect)| {
+                Some(SynctexForwardResult {
+                    uri: Url::from_file_path(pdf_path).ok()?,
+                    page: rect.page,
+                    x: rect.x,
+                    y: rect.y,
+                    width: rect.width,
+                    height: rect.height,
+                })
+            }))
+        });
+
+        Ok(())
+    }
+
+    fn synctex_inverse(&self, id: RequestId, params: SynctexInverseParams) -> Result<()> {
+        let Ok(pdf_path) = params.uri.to_file_path() else {
+            let message = format!("Document \"{}\" is not a local file", params.uri);
+            self.client.send_error(id, ErrorCode::InvalidParams, message)?;
+            return Ok(());
+        };
+
+        self.run_fallible(id, move || {
+            let result = commands::synctex_inverse(&pdf_path, params.page, params.x, params.y)?;
+            Ok(result.and_then(|source| {
+                let position = Position::new(source.line, 0);
+                Some(Location::new(
+                    Url::from_file_path(source.path).ok()?,
+                    Range::new(position, position),
+                ))
+            }))
+        });
+
+        Ok(())
+    }
+
+    fn inverse_search(&self, params: InverseSearchParams) {
+        if !self.client_capabilities.has_show_document_support() {
+            log::error!(
+                "Unable to perform inverse search: client does not support window/showDocument"
+            );
+            return;
+        }
+
+        let Ok(uri) = Url::from_file_path(&params.path) else {
+            log::error!(
+                "Unable to perform inverse search: invalid path {}",
+                params.path.display()
+            );
+            return;
+        };
+
+        let position = Position::new(params.line, 0);
+        let params = ShowDocumentParams {
+            uri,
+            external: Some(false),
+            take_focus: Some(true),
+            selection: Some(Range::new(position, position)),
+        };
+
+        let client = self.client.clone();
+        self.pool.execute(move || {
+            if let Err(why) = client.send_request::<ShowDocument>(params) {
+                log::error!("Failed to show document during inverse search: {why}");
+            }
+        });
+    }
+
+    fn code_actions(&self, id: RequestId, params: CodeActionParams) -> Result<()> {
+        let mut uri = params.text_document.uri;
+        normalize_uri(&mut uri);
+        let client_capabilities = Arc::clone(&self.client_capabilities);
+        self.run_query(id, move |db| {
+            code_action::find_all(db, &uri, params.range, &client_capabilities).unwrap_or_default()
+        });
+
         Ok(())
     }
 
     fn code_action_resolve(&self, id: RequestId, action: CodeAction) -> Result<()> {
-        self.client
-            .send_response(lsp_server::Response::new_ok(id, action))?;
+        self.run_query(id, move |db| code_action::resolve(db, action));
         Ok(())
     }
 
@@ -854,9 +1381,23 @@ impl Server {
                         .lookup_path(&path)
                         .map_or(true, |document| document.owner == Owner::Server)
                     {
-                        if let Some(language) = Language::from_path(&path) {
-                            changed |= workspace.load(&path, language, Owner::Server).is_ok();
+                        let language = match workspace.lookup_path(&path) {
+                            Some(document) => document.language,
+                            None => {
+                                let Some(language) = Language::from_path(&path) else { continue };
+                                language
+                            }
+                        };
+
+                        let indexing = &workspace.config().indexing;
+                        if indexing.enabled
+                            && workspace.lookup_path(&path).is_none()
+                            && util::indexing::is_excluded(workspace.folders(), indexing, &path)
+                        {
+                            continue;
                         }
+
+                        changed |= workspace.load(&path, language, Owner::Server).is_ok();
                     }
                 }
             }
@@ -908,7 +1449,8 @@ impl Server {
         let line_index = &document.line_index;
         let position = line_index.offset_lsp(params.text_document_position.position);
 
-        let Some(result) = commands::change_environment(document, position, &params.new_name) else {
+        let Some(result) = commands::change_environment(document, position, &params.new_name)
+        else {
             anyhow::bail!("No environment found at the current position");
         };
 
@@ -938,6 +1480,121 @@ impl Server {
         Ok(ApplyWorkspaceEditParams { label, edit })
     }
 
+    fn prepare_import_entry(&self, params: ExecuteCommandParams) -> Result<ImportEntryTask> {
+        let workspace = self.workspace.read();
+        let mut params = self.parse_command_params::<ImportEntryParams>(params.arguments)?;
+        normalize_uri(&mut params.text_document.uri);
+        let version = self.versions.get(&params.text_document.uri).copied();
+        ImportEntryTask::new(&workspace, params, version)
+    }
+
+    fn sort_entries(&self, params: ExecuteCommandParams) -> Result<ApplyWorkspaceEditParams> {
+        let workspace = self.workspace.read();
+        let params = self.parse_command_params::<SortEntriesParams>(params.arguments)?;
+        let mut uri = params.text_document.uri;
+        normalize_uri(&mut uri);
+
+        let Some(document) = workspace.lookup(&uri) else {
+            anyhow::bail!("Document {} is not opened!", uri)
+        };
+
+        let order = match params.sort_by {
+            SortEntriesOrder::Key => commands::SortOrder::Key,
+            SortEntriesOrder::Type => commands::SortOrder::Type,
+            SortEntriesOrder::Year => commands::SortOrder::Year,
+            SortEntriesOrder::Author => commands::SortOrder::Author,
+        };
+
+        let Some(edits) = commands::sort_entries(document, order) else {
+            anyhow::bail!("Document {} is not a BibTeX file", uri)
+        };
+
+        Ok(ApplyWorkspaceEditParams {
+            label: Some("sort entries".into()),
+            edit: create_workspace_edit(edits),
+        })
+    }
+
+    fn find_duplicate_entries(
+        &self,
+        params: ExecuteCommandParams,
+    ) -> Result<Vec<Vec<DuplicateEntry>>> {
+        let workspace = self.workspace.read();
+        let params = self.parse_command_params::<FindDuplicateEntriesParams>(params.arguments)?;
+        let mut uri = params.text_document.uri;
+        normalize_uri(&mut uri);
+
+        let Some(document) = workspace.lookup(&uri) else {
+            anyhow::bail!("Document {} is not opened!", uri)
+        };
+
+        let mut documents: Vec<_> = workspace.project(document).documents.into_iter().collect();
+        documents.sort_by(|a, b| a.uri.cmp(&b.uri));
+
+        let groups = commands::find_duplicates(documents)
+            .into_iter()
+            .map(|group| {
+                group
+                    .into_iter()
+                    .map(|entry| DuplicateEntry {
+                        key: entry.key,
+                        location: Location::new(
+                            entry.document.uri.clone(),
+                            entry.document.line_index.line_col_lsp_range(entry.range),
+                        ),
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Ok(groups)
+    }
+
+    fn merge_entries(&self, params: ExecuteCommandParams) -> Result<ApplyWorkspaceEditParams> {
+        let workspace = self.workspace.read();
+        let params = self.parse_command_params::<MergeEntriesParams>(params.arguments)?;
+        let mut uri = params.text_document.uri;
+        normalize_uri(&mut uri);
+
+        let Some(document) = workspace.lookup(&uri) else {
+            anyhow::bail!("Document {} is not opened!", uri)
+        };
+
+        let documents: Vec<_> = workspace.project(document).documents.into_iter().collect();
+        let edits = commands::merge_entries(&documents, &params.keep, &params.remove)?;
+
+        Ok(ApplyWorkspaceEditParams {
+            label: Some(format!(
+                "merge entries: {} -> {}",
+                params.remove, params.keep
+            )),
+            edit: create_workspace_edit(edits),
+        })
+    }
+
+    fn pin_root(&self, params: ExecuteCommandParams) -> Result<()> {
+        let params = self.parse_command_params::<PinRootParams>(params.arguments)?;
+        let mut uri = params.text_document.uri;
+        normalize_uri(&mut uri);
+
+        let mut root = params.root;
+        if let Some(root) = &mut root {
+            normalize_uri(root);
+        }
+
+        let mut workspace = self.workspace.write();
+        if workspace.lookup(&uri).is_none() {
+            anyhow::bail!("Document {} is not opened!", uri);
+        }
+
+        workspace.pin_root(uri, root);
+        workspace.discover();
+        drop(workspace);
+
+        self.internal_tx.send(InternalMessage::Diagnostics)?;
+        Ok(())
+    }
+
     fn parse_command_params<T: DeserializeOwned>(
         &self,
         params: Vec<serde_json::Value>,
@@ -958,6 +1615,7 @@ impl Server {
                     match msg? {
                         Message::Request(request) => {
                             if self.connection.handle_shutdown(&request)? {
+                                self.workspace.write().store_semantics_cache();
                                 return Ok(());
                             }
 
@@ -987,10 +1645,22 @@ impl Server {
                                     self.document_highlight(id, params)
                                 })?
                                 .on::<Formatting, _>(|id, params| self.formatting(id, params))?
+                                .on::<DocumentDiagnosticRequest, _>(|id, params| {
+                                    self.document_diagnostic(id, params)
+                                })?
+                                .on::<WorkspaceDiagnosticRequest, _>(|id, params| {
+                                    self.workspace_diagnostic(id, params)
+                                })?
                                 .on::<BuildRequest, _>(|id, params| self.build(Some(id), params))?
                                 .on::<ForwardSearchRequest, _>(|id, params| {
                                     self.forward_search(Some(id), params.text_document.uri, Some(params.position))
                                 })?
+                                .on::<SynctexForwardRequest, _>(|id, params| {
+                                    self.synctex_forward(id, params)
+                                })?
+                                .on::<SynctexInverseRequest, _>(|id, params| {
+                                    self.synctex_inverse(id, params)
+                                })?
                                 .on::<ExecuteCommand,_>(|id, params| self.execute_command(id, params))?
                                 .on::<SemanticTokensRangeRequest, _>(|id, params| {
                                     self.semantic_tokens_range(id, params)
@@ -1053,6 +1723,20 @@ impl Server {
                         InternalMessage::ForwardSearch(uri, position) => {
                             self.forward_search(None, uri, position)?;
                         }
+                        InternalMessage::BuildLog(uri, errors) => {
+                            self.build_logs.insert(uri, errors);
+                            self.publish_diagnostics()?;
+                        }
+                        InternalMessage::BuildFinished(uri) => {
+                            self.finish_build(&uri);
+                        }
+                        InternalMessage::InverseSearch(params) => {
+                            self.inverse_search(params);
+                        }
+                        InternalMessage::IndexingFinished => {
+                            self.update_workspace();
+                            self.workspace.write().store_semantics_cache();
+                        }
                     };
                 }
             };
@@ -1067,9 +1751,23 @@ impl Server {
     }
 }
 
+fn create_workspace_edit(edits: Vec<commands::EntryEdit>) -> WorkspaceEdit {
+    let mut changes: HashMap<Url, Vec<TextEdit>> = HashMap::new();
+    for edit in edits {
+        let range = edit.document.line_index.line_col_lsp_range(edit.range);
+        changes
+            .entry(edit.document.uri.clone())
+            .or_default()
+            .push(TextEdit::new(range, edit.new_text));
+    }
+
+    WorkspaceEdit::new(changes)
+}
+
 struct FileWatcher {
     watcher: notify::RecommendedWatcher,
     watched_dirs: FxHashSet<PathBuf>,
+    watched_folders: FxHashSet<PathBuf>,
 }
 
 impl FileWatcher {
@@ -1083,10 +1781,21 @@ impl FileWatcher {
         Ok(Self {
             watcher: notify::recommended_watcher(handle)?,
             watched_dirs: FxHashSet::default(),
+            watched_folders: FxHashSet::default(),
         })
     }
 
     pub fn watch(&mut self, workspace: &mut Workspace) {
         workspace.watch(&mut self.watcher, &mut self.watched_dirs);
+
+        // New files anywhere in the workspace folders need to be picked up by the index.
+        if workspace.config().indexing.enabled {
+            for folder in workspace.folders() {
+                if self.watched_folders.insert(folder.clone()) {
+                    let mode = notify::RecursiveMode::Recursive;
+                    let _ = notify::Watcher::watch(&mut self.watcher, folder, mode);
+                }
+            }
+        }
     }
 }
@@ -1,7 +1,12 @@
+use std::path::{Path, PathBuf};
+
 use commands::ForwardSearchError;
-use lsp_types::{Position, TextDocumentIdentifier, TextDocumentPositionParams};
+use lsp_types::{
+    FormattingOptions, Location, Position, TextDocumentIdentifier, TextDocumentPositionParams, Url,
+};
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
+use syntax::{BuildErrorLevel, BuildEvent};
 
 pub struct BuildRequest;
 
@@ -37,6 +42,78 @@ pub enum BuildStatus {
     CANCELLED = 3,
 }
 
+pub struct BuildLogNotification;
+
+impl lsp_types::notification::Notification for BuildLogNotification {
+    type Params = BuildLogParams;
+
+    const METHOD: &'static str = "texlab/buildLog";
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BuildLogParams {
+    pub uri: Url,
+    pub event: BuildLogEvent,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum BuildLogEvent {
+    FileOpened {
+        path: PathBuf,
+    },
+    Error {
+        path: PathBuf,
+        line: Option<u32>,
+        message: String,
+        hint: Option<String>,
+    },
+    Warning {
+        path: PathBuf,
+        line: Option<u32>,
+        message: String,
+        hint: Option<String>,
+    },
+    PageShipped {
+        page: u32,
+    },
+    Finished {
+        status: BuildStatus,
+    },
+}
+
+impl BuildLogEvent {
+    pub fn new(event: BuildEvent, base_dir: &Path) -> Self {
+        match event {
+            BuildEvent::FileOpened(path) => Self::FileOpened {
+                path: base_dir.join(path),
+            },
+            BuildEvent::Diagnostic(error) => {
+                let path = base_dir.join(error.relative_path);
+                let line = error.line;
+                let message = error.message;
+                let hint = error.hint;
+                match error.level {
+                    BuildErrorLevel::Error => Self::Error {
+                        path,
+                        line,
+                        message,
+                        hint,
+                    },
+                    BuildErrorLevel::Warning => Self::Warning {
+                        path,
+                        line,
+                        message,
+                        hint,
+                    },
+                }
+            }
+            BuildEvent::PageShipped(page) => Self::PageShipped { page },
+        }
+    }
+}
+
 pub struct ForwardSearchRequest;
 
 impl lsp_types::request::Request for ForwardSearchRequest {
@@ -73,3 +150,122 @@ impl From<ForwardSearchError> for ForwardSearchStatus {
 pub struct ForwardSearchResult {
     pub status: ForwardSearchStatus,
 }
+
+pub struct SynctexForwardRequest;
+
+impl lsp_types::request::Request for SynctexForwardRequest {
+    type Params = TextDocumentPositionParams;
+
+    type Result = Option<SynctexForwardResult>;
+
+    const METHOD: &'static str = "texlab/synctexForward";
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SynctexForwardResult {
+    pub uri: Url,
+    pub page: u32,
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+pub struct SynctexInverseRequest;
+
+impl lsp_types::request::Request for SynctexInverseRequest {
+    type Params = SynctexInverseParams;
+
+    type Result = Option<Location>;
+
+    const METHOD: &'static str = "texlab/synctexInverse";
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SynctexInverseParams {
+    pub uri: Url,
+    pub page: u32,
+    pub x: f64,
+    pub y: f64,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PinRootParams {
+    pub text_document: TextDocumentIdentifier,
+
+    /// The root document to use for `text_document` or `None` to remove the pin.
+    #[serde(default)]
+    pub root: Option<Url>,
+}
+
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportEntryParams {
+    /// The BibTeX document that receives the entry.
+    pub text_document: TextDocumentIdentifier,
+
+    /// A DOI, arXiv identifier or ISBN.
+    pub id: String,
+
+    /// The options used to format the entry.
+    #[serde(default)]
+    pub options: Option<FormattingOptions>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportEntryResult {
+    /// The key of the imported entry.
+    pub key: String,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SortEntriesOrder {
+    #[default]
+    Key,
+    Type,
+    Year,
+    Author,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SortEntriesParams {
+    /// The BibTeX document whose entries are sorted.
+    pub text_document: TextDocumentIdentifier,
+
+    /// The order of the entries, which is the citation key by default.
+    #[serde(default)]
+    pub sort_by: SortEntriesOrder,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FindDuplicateEntriesParams {
+    /// A document of the project whose BibTeX entries are compared.
+    pub text_document: TextDocumentIdentifier,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateEntry {
+    pub key: String,
+    pub location: Location,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeEntriesParams {
+    /// A document of the project that contains the entries.
+    pub text_document: TextDocumentIdentifier,
+
+    /// The key of the entry that receives the fields of the other entry.
+    pub keep: String,
+
+    /// The key of the entry that is removed. Citations of this key are replaced.
+    pub remove: String,
+}
@@ -0,0 +1,121 @@
+use anyhow::Result;
+use base_db::Workspace;
+use commands::{EntryId, EntryResolver};
+use lsp_types::{
+    ApplyWorkspaceEditParams, DocumentChanges, FormattingOptions, OneOf,
+    OptionalVersionedTextDocumentIdentifier, Position, TextDocumentEdit, TextEdit, Url,
+    WorkspaceEdit,
+};
+use rowan::{ast::AstNode, TextSize};
+use rustc_hash::FxHashSet;
+use syntax::bibtex::{self, HasName};
+
+use crate::{features::formatting::format_bibtex_entry, util::line_index_ext::LineIndexExt};
+
+use super::extensions::ImportEntryParams;
+
+/// Imports an entry into a BibTeX document. The entry is resolved when running the task
+/// because it may require a request to a web service.
+pub struct ImportEntryTask {
+    id: EntryId,
+    resolver: Box<dyn EntryResolver>,
+    keys: FxHashSet<String>,
+    uri: Url,
+    version: Option<i32>,
+    position: Position,
+    separator: &'static str,
+    options: FormattingOptions,
+    line_length: usize,
+}
+
+impl ImportEntryTask {
+    pub fn new(
+        workspace: &Workspace,
+        params: ImportEntryParams,
+        version: Option<i32>,
+    ) -> Result<Self> {
+        let uri = params.text_document.uri;
+        let Some(document) = workspace.lookup(&uri) else {
+            anyhow::bail!("Document {} is not opened!", uri)
+        };
+
+        if document.data.as_bib().is_none() {
+            anyhow::bail!("Document {} is not a BibTeX file", uri);
+        }
+
+        let id = EntryId::parse(&params.id)?;
+
+        let config = workspace.config_for(&uri);
+        let resolver = commands::entry_resolver(
+            config.import.database.as_deref(),
+            config.import.endpoint.as_deref(),
+        );
+
+        let keys = workspace
+            .iter()
+            .filter_map(|document| document.data.as_bib())
+            .flat_map(|data| data.root_node().descendants())
+            .filter_map(bibtex::Entry::cast)
+            .filter_map(|entry| entry.name_token())
+            .map(|token| token.text().to_string())
+            .collect();
+
+        let text = &document.text;
+        let position = document
+            .line_index
+            .line_col_lsp(TextSize::of(text.as_str()));
+
+        let separator = if text.trim().is_empty() {
+            ""
+        } else if text.ends_with('\n') {
+            "\n"
+        } else {
+            "\n\n"
+        };
+
+        Ok(Self {
+            id,
+            resolver,
+            keys,
+            uri,
+            version,
+            position,
+            separator,
+            options: params.options.unwrap_or(FormattingOptions {
+                tab_size: 2,
+                insert_spaces: true,
+                ..FormattingOptions::default()
+            }),
+            line_length: config.formatting.line_length,
+        })
+    }
+
+    /// Resolves the entry and returns the edit that inserts it together with its key.
+    pub fn run(self) -> Result<(ApplyWorkspaceEditParams, String)> {
+        let entry = commands::import_entry(&self.id, self.resolver.as_ref(), &self.keys)?;
+        let text =
+            format_bibtex_entry(&entry.text, &self.options, self.line_length).unwrap_or(entry.text);
+
+        let range = lsp_types::Range::new(self.position, self.position);
+        let edit = TextEdit::new(range, format!("{}{}\n", self.separator, text));
+
+        let text_document = OptionalVersionedTextDocumentIdentifier {
+            uri: self.uri,
+            version: self.version,
+        };
+
+        let params = ApplyWorkspaceEditParams {
+            label: Some(format!("import entry: {}", entry.key)),
+            edit: WorkspaceEdit {
+                changes: None,
+                document_changes: Some(DocumentChanges::Edits(vec![TextDocumentEdit {
+                    text_document,
+                    edits: vec![OneOf::Left(edit)],
+                }])),
+                change_annotations: None,
+            },
+        };
+
+        Ok((params, entry.key))
+    }
+}
@@ -1,4 +1,4 @@
-use std::time::Duration;
+use std::{path::PathBuf, time::Duration};
 
 use base_db::{Config, Formatter, SynctexConfig};
 use regex::Regex;
@@ -13,6 +13,7 @@ pub struct Options {
     pub bibtex_formatter: BibtexFormatter,
     pub latex_formatter: LatexFormatter,
     pub formatter_line_length: Option<i32>,
+    pub default_encoding: Option<String>,
     pub diagnostics: DiagnosticsOptions,
     pub diagnostics_delay: Option<u64>,
     pub build: BuildOptions,
@@ -21,6 +22,9 @@ pub struct Options {
     pub latexindent: LatexindentOptions,
     pub forward_search: ForwardSearchOptions,
     pub completion: CompletionOptions,
+    pub indexing: IndexingOptions,
+    pub citation: CitationOptions,
+    pub import_entry: ImportEntryOptions,
     pub experimental: ExperimentalOptions,
 }
 
@@ -129,6 +133,31 @@ pub struct CompletionOptions {
     pub matcher: CompletionMatcher,
 }
 
+#[derive(Debug, PartialEq, Eq, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(default)]
+pub struct IndexingOptions {
+    pub enabled: bool,
+    pub exclude: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(default)]
+pub struct CitationOptions {
+    pub style: Option<PathBuf>,
+    pub locales: Option<PathBuf>,
+    pub key_pattern: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(default)]
+pub struct ImportEntryOptions {
+    pub endpoint: Option<String>,
+    pub database: Option<PathBuf>,
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum CompletionMatcher {
@@ -194,6 +223,13 @@ impl From<Options> for Config {
                 .formatter_line_length
                 .map_or(80, |len| if len < 0 { usize::MAX } else { len as usize });
 
+        if let Some(label) = value.default_encoding {
+            match encoding_rs::Encoding::for_label(label.as_bytes()) {
+                Some(encoding) => config.default_encoding = encoding,
+                None => log::warn!("Unknown default encoding: {label}"),
+            };
+        }
+
         config.formatting.latex_indent.local = value.latexindent.local;
         config.formatting.latex_indent.modify_line_breaks = value.latexindent.modify_line_breaks;
 
@@ -224,6 +260,16 @@ impl From<Options> for Config {
             CompletionMatcher::PrefixIgnoreCase => base_db::MatchingAlgo::PrefixIgnoreCase,
         };
 
+        config.indexing.enabled = value.indexing.enabled;
+        config.indexing.exclude = value.indexing.exclude;
+
+        config.citation.style = value.citation.style;
+        config.citation.locales = value.citation.locales;
+        config.citation.key_pattern = value.citation.key_pattern;
+
+        config.import.endpoint = value.import_entry.endpoint;
+        config.import.database = value.import_entry.database;
+
         config
             .syntax
             .math_environments
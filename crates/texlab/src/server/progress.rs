@@ -1,11 +1,15 @@
+use std::sync::atomic::{AtomicI32, Ordering};
+
 use lsp_types::{
     notification::Progress, request::WorkDoneProgressCreate, NumberOrString, ProgressParams,
-    ProgressParamsValue, Url, WorkDoneProgress, WorkDoneProgressBegin,
-    WorkDoneProgressCreateParams, WorkDoneProgressEnd,
+    ProgressParamsValue, WorkDoneProgress, WorkDoneProgressBegin, WorkDoneProgressCreateParams,
+    WorkDoneProgressEnd, WorkDoneProgressReport,
 };
 
 use crate::LspClient;
 
+static NEXT_TOKEN: AtomicI32 = AtomicI32::new(1);
+
 #[derive(Debug)]
 pub struct ProgressReporter {
     client: LspClient,
@@ -24,7 +28,8 @@ impl Drop for ProgressReporter {
 }
 
 impl ProgressReporter {
-    pub fn new(client: LspClient, token: i32, uri: &Url) -> Self {
+    pub fn new(client: LspClient, title: &str, message: Option<String>) -> Self {
+        let token = NEXT_TOKEN.fetch_add(1, Ordering::SeqCst);
         let _ = client.send_request::<WorkDoneProgressCreate>(WorkDoneProgressCreateParams {
             token: NumberOrString::Number(token),
         });
@@ -32,8 +37,8 @@ impl ProgressReporter {
         let _ = client.send_notification::<Progress>(ProgressParams {
             token: NumberOrString::Number(token),
             value: ProgressParamsValue::WorkDone(WorkDoneProgress::Begin(WorkDoneProgressBegin {
-                title: "Building".into(),
-                message: Some(String::from(uri.as_str())),
+                title: title.into(),
+                message,
                 cancellable: Some(false),
                 percentage: None,
             })),
@@ -41,4 +46,17 @@ impl ProgressReporter {
 
         Self { client, token }
     }
+
+    pub fn report(&self, message: String, percentage: u32) {
+        let _ = self.client.send_notification::<Progress>(ProgressParams {
+            token: NumberOrString::Number(self.token),
+            value: ProgressParamsValue::WorkDone(WorkDoneProgress::Report(
+                WorkDoneProgressReport {
+                    message: Some(message),
+                    cancellable: Some(false),
+                    percentage: Some(percentage),
+                },
+            )),
+        });
+    }
 }
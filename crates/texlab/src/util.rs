@@ -1,10 +1,14 @@
 pub mod capabilities;
 pub mod chktex;
+pub mod citations;
 pub mod components;
 pub mod cursor;
 pub mod diagnostics;
+pub mod indexing;
+pub mod ipc;
 pub mod line_index_ext;
 pub mod lsp_enums;
+pub mod packages;
 
 use std::path::PathBuf;
 
@@ -18,6 +18,14 @@ pub trait ClientCapabilitiesExt {
     fn has_file_watching_support(&self) -> bool;
 
     fn has_snippet_support(&self) -> bool;
+
+    fn has_show_document_support(&self) -> bool;
+
+    fn has_pull_diagnostics_support(&self) -> bool;
+
+    fn has_diagnostic_refresh_support(&self) -> bool;
+
+    fn has_code_action_resolve_support(&self) -> bool;
 }
 
 impl ClientCapabilitiesExt for ClientCapabilities {
@@ -41,6 +49,39 @@ impl ClientCapabilitiesExt for ClientCapabilities {
         self.window.as_ref().and_then(|cap| cap.work_done_progress) == Some(true)
     }
 
+    fn has_show_document_support(&self) -> bool {
+        self.window
+            .as_ref()
+            .and_then(|cap| cap.show_document.as_ref())
+            .map_or(false, |cap| cap.support)
+    }
+
+    fn has_pull_diagnostics_support(&self) -> bool {
+        self.text_document
+            .as_ref()
+            .map_or(false, |cap| cap.diagnostic.is_some())
+    }
+
+    fn has_diagnostic_refresh_support(&self) -> bool {
+        self.workspace
+            .as_ref()
+            .and_then(|cap| cap.diagnostic.as_ref())
+            .and_then(|cap| cap.refresh_support)
+            == Some(true)
+    }
+
+    fn has_code_action_resolve_support(&self) -> bool {
+        let Some(cap) = self
+            .text_document
+            .as_ref()
+            .and_then(|cap| cap.code_action.as_ref()) else { return false };
+
+        cap.data_support == Some(true)
+            && cap.resolve_support.as_ref().map_or(false, |support| {
+                support.properties.iter().any(|name| name == "edit")
+            })
+    }
+
     fn has_completion_markdown_support(&self) -> bool {
         self.text_document
             .as_ref()
@@ -92,6 +133,7 @@ impl ClientCapabilitiesExt for ClientCapabilities {
 mod tests {
     use super::*;
     use lsp_types::{
+        CodeActionCapabilityResolveSupport, CodeActionClientCapabilities,
         DocumentSymbolClientCapabilities, GotoCapability, HoverClientCapabilities,
         TextDocumentClientCapabilities, WindowClientCapabilities,
     };
@@ -176,4 +218,28 @@ mod tests {
         let capabilities = ClientCapabilities::default();
         assert!(!capabilities.has_hover_markdown_support());
     }
+
+    #[test]
+    fn test_has_code_action_resolve_support_true() {
+        let capabilities = ClientCapabilities {
+            text_document: Some(TextDocumentClientCapabilities {
+                code_action: Some(CodeActionClientCapabilities {
+                    data_support: Some(true),
+                    resolve_support: Some(CodeActionCapabilityResolveSupport {
+                        properties: vec!["edit".into()],
+                    }),
+                    ..CodeActionClientCapabilities::default()
+                }),
+                ..TextDocumentClientCapabilities::default()
+            }),
+            ..ClientCapabilities::default()
+        };
+        assert!(capabilities.has_code_action_resolve_support());
+    }
+
+    #[test]
+    fn test_has_code_action_resolve_support_false() {
+        let capabilities = ClientCapabilities::default();
+        assert!(!capabilities.has_code_action_resolve_support());
+    }
 }
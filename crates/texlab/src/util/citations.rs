@@ -0,0 +1,303 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::SystemTime,
+};
+
+use base_db::{Project, Workspace};
+use citeproc::csl::{Locale, Style};
+use lsp_types::Url;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use rowan::ast::AstNode;
+use rustc_hash::FxHashMap;
+use syntax::{bibtex, csl};
+
+/// CSL styles and locales loaded from disk. Files are parsed on demand and kept in memory
+/// until they are modified on disk.
+#[derive(Debug, Default)]
+pub struct CitationStyles {
+    styles: FileCache<Style>,
+    locales: FileCache<Locale>,
+}
+
+#[derive(Debug)]
+struct FileCache<T> {
+    entries: Mutex<FxHashMap<PathBuf, CacheEntry<T>>>,
+}
+
+impl<T> Default for FileCache<T> {
+    fn default() -> Self {
+        Self {
+            entries: Mutex::default(),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct CacheEntry<T> {
+    modified: Option<SystemTime>,
+    value: Option<Arc<T>>,
+}
+
+impl<T> FileCache<T> {
+    fn get(&self, path: &Path, parse: impl FnOnce(&str) -> Option<T>) -> Option<Arc<T>> {
+        let modified = std::fs::metadata(path).ok()?.modified().ok();
+        if let Some(entry) = self.entries.lock().get(path) {
+            if entry.modified == modified {
+                return entry.value.clone();
+            }
+        }
+
+        let value = std::fs::read_to_string(path)
+            .ok()
+            .and_then(|text| parse(&text))
+            .map(Arc::new);
+
+        if value.is_none() {
+            log::warn!("Unable to parse {}", path.display());
+        }
+
+        self.entries.lock().insert(
+            path.to_path_buf(),
+            CacheEntry {
+                modified,
+                value: value.clone(),
+            },
+        );
+
+        value
+    }
+}
+
+impl CitationStyles {
+    /// Renders a BibTeX entry for hover and completion documentation.
+    /// Uses the CSL style configured for the document at `uri` and falls back to the
+    /// built-in citation style if there is none. Cross-references are resolved
+    /// using the BibTeX documents of the `project`.
+    pub fn render(
+        &self,
+        workspace: &Workspace,
+        project: &Project,
+        uri: &Url,
+        entry: &bibtex::Entry,
+    ) -> Option<String> {
+        let bibliography: Vec<_> = project
+            .documents
+            .iter()
+            .filter_map(|document| document.data.as_bib())
+            .filter_map(|data| bibtex::Root::cast(data.root_node()))
+            .collect();
+
+        let csl = self.find_style(workspace, uri).and_then(|(style, locale)| {
+            citeproc::csl::render(entry, &bibliography, &style, locale.as_deref())
+        });
+
+        csl.or_else(|| citeproc::render(entry, &bibliography))
+    }
+
+    /// Renders an item of a CSL-JSON or Hayagriva bibliography like [`Self::render`].
+    pub fn render_item(
+        &self,
+        workspace: &Workspace,
+        uri: &Url,
+        item: &csl::Item,
+    ) -> Option<String> {
+        let csl = self.find_style(workspace, uri).and_then(|(style, locale)| {
+            citeproc::csl::render_item(item, &style, locale.as_deref())
+        });
+
+        csl.or_else(|| citeproc::render_item(item))
+    }
+
+    /// Loads the CSL style configured for the document at `uri` together with its locale.
+    /// Dependent styles are replaced by their independent parent, which is expected to be
+    /// located next to the dependent style. Without a default locale, `en-US` is used.
+    fn find_style(
+        &self,
+        workspace: &Workspace,
+        uri: &Url,
+    ) -> Option<(Arc<Style>, Option<Arc<Locale>>)> {
+        let config = &workspace.config_for(uri).citation;
+        let path = config.style.as_deref()?;
+        let style = self.styles.get(path, Style::parse)?;
+        let (style, default_locale) = match style.independent_parent() {
+            Some(parent) => {
+                let parent_style = self.find_parent(path, parent)?;
+                let default_locale = style
+                    .default_locale()
+                    .or_else(|| parent_style.default_locale())
+                    .map(String::from);
+
+                (parent_style, default_locale)
+            }
+            None => {
+                let default_locale = style.default_locale().map(String::from);
+                (style, default_locale)
+            }
+        };
+
+        let locales = config.locales.as_deref();
+        let locale = default_locale
+            .as_deref()
+            .and_then(|lang| self.find_locale(path, locales, lang))
+            .or_else(|| self.find_locale(path, locales, FALLBACK_LOCALE));
+
+        Some((style, locale))
+    }
+
+    /// Loads the independent parent of a dependent style. The parent is identified by the
+    /// last segment of its URI, e.g. `http://www.zotero.org/styles/apa` refers to `apa.csl`.
+    fn find_parent(&self, style: &Path, parent: &str) -> Option<Arc<Style>> {
+        let name = parent.trim_end_matches('/').rsplit('/').next()?;
+        let path = style.parent()?.join(format!("{name}.csl"));
+        if !path.is_file() {
+            log::warn!(
+                "Unable to find the parent style {} of {}",
+                path.display(),
+                style.display()
+            );
+
+            return None;
+        }
+
+        self.styles
+            .get(&path, Style::parse)
+            .filter(|style| style.independent_parent().is_none())
+    }
+
+    /// Looks for `locales-<lang>.xml` in the configured locale directory,
+    /// next to the style and in the `locales` directory next to the style.
+    fn find_locale(&self, style: &Path, locales: Option<&Path>, lang: &str) -> Option<Arc<Locale>> {
+        let file_name = format!("locales-{lang}.xml");
+        let style_dir = style.parent()?;
+        locales
+            .into_iter()
+            .chain([style_dir, &style_dir.join("locales")])
+            .map(|dir| dir.join(&file_name))
+            .find(|path| path.is_file())
+            .and_then(|path| self.locales.get(&path, Locale::parse))
+    }
+}
+
+/// The locale that is used if the style does not specify one.
+const FALLBACK_LOCALE: &str = "en-US";
+
+pub static CITATION_STYLES: Lazy<CitationStyles> = Lazy::new(CitationStyles::default);
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use base_db::{Config, Workspace};
+    use lsp_types::Url;
+
+    use super::CitationStyles;
+
+    const LOCALE: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<locale xmlns="http://purl.org/net/xbiblio/csl" version="1.0" xml:lang="de-DE">
+  <terms>
+    <term name="and">und</term>
+  </terms>
+</locale>
+"#;
+
+    const PARENT: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<style xmlns="http://purl.org/net/xbiblio/csl" class="in-text" version="1.0">
+  <info><title>Parent</title></info>
+</style>
+"#;
+
+    const DEPENDENT: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<style xmlns="http://purl.org/net/xbiblio/csl" class="in-text" version="1.0" default-locale="de-DE">
+  <info>
+    <title>Dependent</title>
+    <link href="http://www.zotero.org/styles/parent" rel="independent-parent"/>
+  </info>
+</style>
+"#;
+
+    fn workspace(style: &Path) -> Workspace {
+        let mut config = Config::default();
+        config.citation.style = Some(style.to_path_buf());
+
+        let mut workspace = Workspace::default();
+        workspace.set_config(config);
+        workspace
+    }
+
+    #[test]
+    fn test_find_locale() {
+        let dir = tempfile::tempdir().unwrap();
+        let style = dir.path().join("style.csl");
+        std::fs::create_dir(dir.path().join("locales")).unwrap();
+        std::fs::write(dir.path().join("locales/locales-de-DE.xml"), LOCALE).unwrap();
+
+        let styles = CitationStyles::default();
+        let locale = styles.find_locale(&style, None, "de-DE").unwrap();
+        assert_eq!(locale.lang(), Some("de-DE"));
+        assert!(styles.find_locale(&style, None, "fr-FR").is_none());
+    }
+
+    #[test]
+    fn test_find_locale_configured_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let locales = dir.path().join("csl-locales");
+        std::fs::create_dir(&locales).unwrap();
+        std::fs::write(locales.join("locales-de-DE.xml"), LOCALE).unwrap();
+
+        let styles = CitationStyles::default();
+        let style = dir.path().join("styles/style.csl");
+        assert!(styles
+            .find_locale(&style, Some(&locales), "de-DE")
+            .is_some());
+    }
+
+    #[test]
+    fn test_find_style_dependent() {
+        let dir = tempfile::tempdir().unwrap();
+        let style = dir.path().join("dependent.csl");
+        std::fs::write(&style, DEPENDENT).unwrap();
+        std::fs::write(dir.path().join("parent.csl"), PARENT).unwrap();
+        std::fs::write(dir.path().join("locales-de-DE.xml"), LOCALE).unwrap();
+
+        let uri = Url::parse("file:///main.tex").unwrap();
+        let (style, locale) = CitationStyles::default()
+            .find_style(&workspace(&style), &uri)
+            .unwrap();
+
+        assert_eq!(style.title(), Some("Parent"));
+        assert_eq!(locale.unwrap().lang(), Some("de-DE"));
+    }
+
+    #[test]
+    fn test_find_style_missing_parent() {
+        let dir = tempfile::tempdir().unwrap();
+        let style = dir.path().join("dependent.csl");
+        std::fs::write(&style, DEPENDENT).unwrap();
+
+        let uri = Url::parse("file:///main.tex").unwrap();
+        let styles = CitationStyles::default();
+        assert!(styles.find_style(&workspace(&style), &uri).is_none());
+    }
+
+    #[test]
+    fn test_find_style_fallback_locale() {
+        let dir = tempfile::tempdir().unwrap();
+        let style = dir.path().join("parent.csl");
+        std::fs::write(&style, PARENT).unwrap();
+        std::fs::write(
+            dir.path().join("locales-en-US.xml"),
+            LOCALE.replace("de-DE", "en-US"),
+        )
+        .unwrap();
+
+        let uri = Url::parse("file:///main.tex").unwrap();
+        let (style, locale) = CitationStyles::default()
+            .find_style(&workspace(&style), &uri)
+            .unwrap();
+
+        assert_eq!(style.title(), Some("Parent"));
+        assert_eq!(locale.unwrap().lang(), Some("en-US"));
+    }
+}
@@ -144,10 +144,12 @@ impl<'a, T> CursorContext<'a, T> {
                 let right = root.token_at_offset(offset).right_biased();
                 Cursor::new_bib(left, right)
             }
-            DocumentData::Aux(_)
+            DocumentData::Csl(_)
+            | DocumentData::Aux(_)
             | DocumentData::Log(_)
-            | DocumentData::Root
-            | DocumentData::Tectonic => None,
+            | DocumentData::Fls(_)
+            | DocumentData::Root(_)
+            | DocumentData::Tectonic(_) => None,
         };
 
         Some(Self {
@@ -195,6 +197,20 @@ impl<'a, T> CursorContext<'a, T> {
         Some((key.to_string(), key.text_range()))
     }
 
+    pub fn find_string_name(&self) -> Option<(String, TextRange)> {
+        let name = self
+            .cursor
+            .as_bib()
+            .filter(|token| token.kind() == bibtex::NAME)?;
+
+        let parent = name.parent()?;
+        if bibtex::Value::can_cast(parent.kind()) || bibtex::StringDef::can_cast(parent.kind()) {
+            Some((name.to_string(), name.text_range()))
+        } else {
+            None
+        }
+    }
+
     pub fn find_label_name_key(&self) -> Option<(String, TextRange)> {
         let name = self
             .cursor
@@ -1,42 +1,261 @@
-use base_db::{diagnostics::ErrorCode, util::filter_regex_patterns, Document, Workspace};
+use std::hash::{Hash, Hasher};
+
+use base_db::{
+    diagnostics::{
+        bib::{self, BibDefinitions},
+        tex, ErrorCode,
+    },
+    semantics::SemanticsCache,
+    util::filter_regex_patterns,
+    Document, Workspace,
+};
 use distro::Language;
-use lsp_types::{DiagnosticSeverity, NumberOrString};
-use rustc_hash::FxHashMap;
-use syntax::BuildErrorLevel;
+use lsp_types::{DiagnosticSeverity, NumberOrString, Url};
+use rustc_hash::{FxHashMap, FxHashSet, FxHasher};
+use syntax::{BuildError, BuildErrorLevel};
 
 use super::line_index_ext::LineIndexExt;
 
-pub fn collect(workspace: &Workspace) -> FxHashMap<&Document, Vec<lsp_types::Diagnostic>> {
+/// Remembers the diagnostics that were last reported to the client for each document.
+#[derive(Debug, Default)]
+pub struct DiagnosticState {
+    entries: FxHashMap<Url, DiagnosticEntry>,
+    next_result_id: u64,
+    analyses: FxHashMap<Url, Analysis>,
+}
+
+#[derive(Debug)]
+pub struct DiagnosticEntry {
+    pub diagnostics: Vec<lsp_types::Diagnostic>,
+
+    /// Identifies the diagnostics in pull requests.
+    pub result_id: String,
+}
+
+/// The diagnostics of a TeX or BibTeX document that do not depend on a build.
+/// They are only computed again if the fingerprint of the document has changed.
+#[derive(Debug)]
+struct Analysis {
+    fingerprint: u64,
+    diagnostics: Vec<lsp_types::Diagnostic>,
+    undefined_citations: Vec<String>,
+}
+
+impl DiagnosticState {
+    /// Replaces the stored diagnostics and returns the documents whose diagnostics have changed.
+    /// Documents that are missing from `all_diagnostics` are forgotten.
+    pub fn update(
+        &mut self,
+        mut all_diagnostics: FxHashMap<Url, Vec<lsp_types::Diagnostic>>,
+    ) -> Vec<Url> {
+        let mut changed = Vec::new();
+        self.entries.retain(|uri, entry| {
+            let Some(diagnostics) = all_diagnostics.remove(uri) else {
+                changed.push(uri.clone());
+                return false;
+            };
+
+            if entry.diagnostics != diagnostics {
+                entry.diagnostics = diagnostics;
+                entry.result_id = self.next_result_id.to_string();
+                self.next_result_id += 1;
+                changed.push(uri.clone());
+            }
+
+            true
+        });
+
+        for (uri, diagnostics) in all_diagnostics {
+            let result_id = self.next_result_id.to_string();
+            self.next_result_id += 1;
+            self.entries.insert(
+                uri.clone(),
+                DiagnosticEntry {
+                    diagnostics,
+                    result_id,
+                },
+            );
+
+            changed.push(uri);
+        }
+
+        changed
+    }
+
+    pub fn get(&self, uri: &Url) -> Option<&DiagnosticEntry> {
+        self.entries.get(uri)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&Url, &DiagnosticEntry)> {
+        self.entries.iter()
+    }
+}
+
+/// Collects the diagnostics of all documents. The diagnostics of a TeX or BibTeX document
+/// are reused from the previous pass if neither the document nor the bibliographies
+/// of its project have changed.
+pub fn collect<'a>(
+    workspace: &'a Workspace,
+    build_logs: &FxHashMap<Url, Vec<BuildError>>,
+    state: &mut DiagnosticState,
+) -> FxHashMap<&'a Document, Vec<lsp_types::Diagnostic>> {
     let mut results = FxHashMap::default();
 
+    // The definitions are shared by all documents of a project with the same bibliographies.
+    let mut definitions = FxHashMap::default();
+    let mut undefined_citations = FxHashSet::default();
+    let mut analyses = FxHashMap::default();
     for document in workspace.iter() {
-        let lsp_diagnostics = document
-            .diagnostics
+        if !matches!(document.language, Language::Tex | Language::Bib) {
+            let lsp_diagnostics = document
+                .diagnostics
+                .iter()
+                .map(|diagnostic| create_diagnostic(document, diagnostic))
+                .collect::<Vec<_>>();
+
+            results.insert(document, lsp_diagnostics);
+            continue;
+        }
+
+        let project = workspace.project(document);
+        let mut bib_documents: Vec<_> = project
+            .documents
             .iter()
-            .map(|diagnostic| create_diagnostic(document, diagnostic))
-            .collect::<Vec<_>>();
+            .filter(|document| {
+                matches!(
+                    document.language,
+                    Language::Bib | Language::CslJson | Language::Hayagriva
+                )
+            })
+            .copied()
+            .collect();
+
+        bib_documents.sort_by(|a, b| a.uri.cmp(&b.uri));
+        let fingerprint = fingerprint(workspace, document, &bib_documents);
+        let analysis = match state.analyses.remove(&document.uri) {
+            Some(analysis) if analysis.fingerprint == fingerprint => analysis,
+            _ => {
+                let bib_uris: Vec<_> = bib_documents.iter().map(|document| &document.uri).collect();
+                let definitions = definitions
+                    .entry(bib_uris)
+                    .or_insert_with(|| BibDefinitions::new(project.documents.iter().copied()));
+
+                analyze(document, definitions, fingerprint)
+            }
+        };
+
+        for key in &analysis.undefined_citations {
+            undefined_citations.insert((&document.uri, key.clone()));
+        }
 
-        results.insert(document, lsp_diagnostics);
+        results.insert(document, analysis.diagnostics.clone());
+        analyses.insert(document.uri.clone(), analysis);
     }
 
+    state.analyses = analyses;
+
     for document in workspace
         .iter()
         .filter(|document| document.language == Language::Log)
     {
-        for (document, diagnostics) in base_db::diagnostics::log::analyze(workspace, document) {
-            let lsp_diagnostics = diagnostics
-                .iter()
-                .map(|diagnostic| create_diagnostic(document, diagnostic))
-                .collect::<Vec<_>>();
-
-            results.get_mut(document).unwrap().extend(lsp_diagnostics);
+        // The output of a running build supersedes the log file on disk.
+        if workspace
+            .parents(document)
+            .iter()
+            .any(|parent| build_logs.contains_key(&parent.uri))
+        {
+            continue;
         }
+
+        let diagnostics = base_db::diagnostics::log::analyze(workspace, document);
+        extend_build_diagnostics(&mut results, diagnostics, &undefined_citations);
+    }
+
+    for (uri, errors) in build_logs {
+        let Some(root_document) = workspace.lookup(uri) else { continue };
+        let diagnostics =
+            base_db::diagnostics::log::analyze_errors(workspace, root_document, errors);
+
+        extend_build_diagnostics(&mut results, diagnostics, &undefined_citations);
     }
 
     results
 }
 
-fn create_diagnostic(
+fn analyze(document: &Document, definitions: &BibDefinitions, fingerprint: u64) -> Analysis {
+    let mut diagnostics = document.diagnostics.clone();
+    if document.language == Language::Tex {
+        diagnostics.extend(tex::analyze_citations(document, definitions));
+    } else {
+        diagnostics.extend(bib::analyze_crossrefs(document, definitions));
+        diagnostics.extend(bib::analyze_strings(document, definitions));
+    }
+
+    let undefined_citations = diagnostics
+        .iter()
+        .filter_map(|diagnostic| match &diagnostic.code {
+            ErrorCode::UndefinedCitation(key) => Some(key.clone()),
+            _ => None,
+        })
+        .collect();
+
+    let diagnostics = diagnostics
+        .iter()
+        .map(|diagnostic| create_diagnostic(document, diagnostic))
+        .collect();
+
+    Analysis {
+        fingerprint,
+        diagnostics,
+        undefined_citations,
+    }
+}
+
+/// Identifies the inputs of the analysis of `document`: its own text and syntax configuration
+/// and the texts of the bibliographies of its project.
+fn fingerprint(workspace: &Workspace, document: &Document, bib_documents: &[&Document]) -> u64 {
+    let config = &workspace.config_for(&document.uri).syntax;
+    let mut hasher = FxHasher::default();
+    SemanticsCache::key(&document.text, config).hash(&mut hasher);
+    for bib_document in bib_documents {
+        bib_document.uri.hash(&mut hasher);
+        bib_document.text.hash(&mut hasher);
+    }
+
+    hasher.finish()
+}
+
+/// Adds the diagnostics of a build to `results`. The warnings of LaTeX about undefined citations
+/// are skipped if the same citation has already been reported by the server.
+fn extend_build_diagnostics<'a>(
+    results: &mut FxHashMap<&'a Document, Vec<lsp_types::Diagnostic>>,
+    diagnostics: FxHashMap<&'a Document, Vec<base_db::diagnostics::Diagnostic>>,
+    undefined_citations: &FxHashSet<(&Url, String)>,
+) {
+    for (document, diagnostics) in diagnostics {
+        let lsp_diagnostics = diagnostics
+            .iter()
+            .filter(|diagnostic| {
+                let ErrorCode::Build(error) = &diagnostic.code else { return true };
+                undefined_citation_key(error).map_or(true, |key| {
+                    !undefined_citations.contains(&(&document.uri, key.to_string()))
+                })
+            })
+            .map(|diagnostic| create_diagnostic(document, diagnostic))
+            .collect::<Vec<_>>();
+
+        results.get_mut(document).unwrap().extend(lsp_diagnostics);
+    }
+}
+
+/// Returns the key of a LaTeX warning like "Citation `foo' on page 1 undefined".
+fn undefined_citation_key(error: &BuildError) -> Option<&str> {
+    let (_, rest) = error.message.split_once("Citation `")?;
+    let (key, rest) = rest.split_once('\'')?;
+    rest.contains("undefined").then_some(key)
+}
+
+pub fn create_diagnostic(
     document: &Document,
     diagnostic: &base_db::diagnostics::Diagnostic,
 ) -> lsp_types::Diagnostic {
@@ -51,6 +270,15 @@ fn create_diagnostic(
         | ErrorCode::ExpectingRCurly
         | ErrorCode::ExpectingEq
         | ErrorCode::ExpectingFieldValue => DiagnosticSeverity::ERROR,
+        ErrorCode::MissingCrossref(_)
+        | ErrorCode::UndefinedCitation(_)
+        | ErrorCode::InvalidDate
+        | ErrorCode::InvalidMonth(_)
+        | ErrorCode::InvalidPageRange(_)
+        | ErrorCode::InvalidIsbn
+        | ErrorCode::DoiWithPrefix(_)
+        | ErrorCode::InvalidUrl
+        | ErrorCode::UndefinedString(_) => DiagnosticSeverity::WARNING,
         ErrorCode::Build(error) => match error.level {
             BuildErrorLevel::Error => DiagnosticSeverity::ERROR,
             BuildErrorLevel::Warning => DiagnosticSeverity::WARNING,
@@ -66,6 +294,15 @@ fn create_diagnostic(
         ErrorCode::ExpectingRCurly => Some(6),
         ErrorCode::ExpectingEq => Some(7),
         ErrorCode::ExpectingFieldValue => Some(8),
+        ErrorCode::MissingCrossref(_) => Some(9),
+        ErrorCode::UndefinedCitation(_) => Some(10),
+        ErrorCode::InvalidDate => Some(11),
+        ErrorCode::InvalidMonth(_) => Some(12),
+        ErrorCode::InvalidPageRange(_) => Some(13),
+        ErrorCode::InvalidIsbn => Some(14),
+        ErrorCode::DoiWithPrefix(_) => Some(15),
+        ErrorCode::InvalidUrl => Some(16),
+        ErrorCode::UndefinedString(_) => Some(17),
         ErrorCode::Build(_) => None,
     };
 
@@ -77,21 +314,39 @@ fn create_diagnostic(
         | ErrorCode::ExpectingKey
         | ErrorCode::ExpectingRCurly
         | ErrorCode::ExpectingEq
-        | ErrorCode::ExpectingFieldValue => "texlab",
+        | ErrorCode::ExpectingFieldValue
+        | ErrorCode::MissingCrossref(_)
+        | ErrorCode::UndefinedCitation(_)
+        | ErrorCode::InvalidDate
+        | ErrorCode::InvalidMonth(_)
+        | ErrorCode::InvalidPageRange(_)
+        | ErrorCode::InvalidIsbn
+        | ErrorCode::DoiWithPrefix(_)
+        | ErrorCode::InvalidUrl
+        | ErrorCode::UndefinedString(_) => "texlab",
         ErrorCode::Build(_) => "latex",
     };
 
-    let message = String::from(match &diagnostic.code {
-        ErrorCode::UnexpectedRCurly => "Unexpected \"}\"",
-        ErrorCode::RCurlyInserted => "Missing \"}\" inserted",
-        ErrorCode::MismatchedEnvironment => "Mismatched environment",
-        ErrorCode::ExpectingLCurly => "Expecting a curly bracket: \"{\"",
-        ErrorCode::ExpectingKey => "Expecting a key",
-        ErrorCode::ExpectingRCurly => "Expecting a curly bracket: \"}\"",
-        ErrorCode::ExpectingEq => "Expecting an equality sign: \"=\"",
-        ErrorCode::ExpectingFieldValue => "Expecting a field value",
-        ErrorCode::Build(error) => &error.message,
-    });
+    let message = match &diagnostic.code {
+        ErrorCode::UnexpectedRCurly => "Unexpected \"}\"".into(),
+        ErrorCode::RCurlyInserted => "Missing \"}\" inserted".into(),
+        ErrorCode::MismatchedEnvironment => "Mismatched environment".into(),
+        ErrorCode::ExpectingLCurly => "Expecting a curly bracket: \"{\"".into(),
+        ErrorCode::ExpectingKey => "Expecting a key".into(),
+        ErrorCode::ExpectingRCurly => "Expecting a curly bracket: \"}\"".into(),
+        ErrorCode::ExpectingEq => "Expecting an equality sign: \"=\"".into(),
+        ErrorCode::ExpectingFieldValue => "Expecting a field value".into(),
+        ErrorCode::MissingCrossref(key) => format!("Unknown entry \"{key}\""),
+        ErrorCode::UndefinedCitation(key) => format!("Undefined citation \"{key}\""),
+        ErrorCode::InvalidDate => "Expecting a date in the EDTF format: \"YYYY-MM-DD\"".into(),
+        ErrorCode::InvalidMonth(_) => "Expecting the month as an integer".into(),
+        ErrorCode::InvalidPageRange(_) => "Expecting a page range like \"12--15\"".into(),
+        ErrorCode::InvalidIsbn => "Invalid ISBN".into(),
+        ErrorCode::DoiWithPrefix(_) => "Expecting a DOI without URL prefix".into(),
+        ErrorCode::InvalidUrl => "Invalid URL".into(),
+        ErrorCode::UndefinedString(name) => format!("Undefined string \"{name}\""),
+        ErrorCode::Build(error) => error.message.clone(),
+    };
 
     lsp_types::Diagnostic {
         severity: Some(severity),
@@ -105,8 +360,8 @@ pub fn filter(
     all_diagnostics: &mut FxHashMap<&Document, Vec<lsp_types::Diagnostic>>,
     workspace: &Workspace,
 ) {
-    let config = &workspace.config().diagnostics;
-    for diagnostics in all_diagnostics.values_mut() {
+    for (document, diagnostics) in all_diagnostics.iter_mut() {
+        let config = &workspace.config_for(&document.uri).diagnostics;
         diagnostics.retain(|diagnostic| {
             filter_regex_patterns(
                 &diagnostic.message,
@@ -116,3 +371,94 @@ pub fn filter(
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use base_db::{util::LineCol, Owner, Workspace};
+    use distro::Language;
+    use lsp_types::{Diagnostic, Range, Url};
+    use rustc_hash::FxHashMap;
+
+    use super::DiagnosticState;
+
+    fn diagnostics(uri: &Url, messages: &[&str]) -> FxHashMap<Url, Vec<Diagnostic>> {
+        let diagnostics = messages
+            .iter()
+            .map(|message| Diagnostic::new_simple(Range::default(), message.to_string()))
+            .collect();
+
+        FxHashMap::from_iter([(uri.clone(), diagnostics)])
+    }
+
+    #[test]
+    fn test_update_unchanged() {
+        let uri = Url::parse("http://example.com/main.tex").unwrap();
+        let mut state = DiagnosticState::default();
+        assert_eq!(state.update(diagnostics(&uri, &["foo"])), vec![uri.clone()]);
+
+        let result_id = state.get(&uri).unwrap().result_id.clone();
+        assert!(state.update(diagnostics(&uri, &["foo"])).is_empty());
+        assert_eq!(state.get(&uri).unwrap().result_id, result_id);
+    }
+
+    #[test]
+    fn test_update_changed() {
+        let uri = Url::parse("http://example.com/main.tex").unwrap();
+        let mut state = DiagnosticState::default();
+        state.update(diagnostics(&uri, &["foo"]));
+
+        let result_id = state.get(&uri).unwrap().result_id.clone();
+        assert_eq!(state.update(diagnostics(&uri, &["bar"])), vec![uri.clone()]);
+        assert_ne!(state.get(&uri).unwrap().result_id, result_id);
+    }
+
+    #[test]
+    fn test_update_removed() {
+        let uri = Url::parse("http://example.com/main.tex").unwrap();
+        let mut state = DiagnosticState::default();
+        state.update(diagnostics(&uri, &[]));
+        assert_eq!(state.update(FxHashMap::default()), vec![uri.clone()]);
+        assert!(state.get(&uri).is_none());
+    }
+
+    #[test]
+    fn test_collect_bibliography_changed() {
+        let tex_uri = Url::parse("http://example.com/main.tex").unwrap();
+        let bib_uri = Url::parse("http://example.com/main.bib").unwrap();
+        let open = |workspace: &mut Workspace, uri: &Url, text: &str, language| {
+            let cursor = LineCol { line: 0, col: 0 };
+            workspace.open(uri.clone(), text.into(), language, Owner::Client, cursor);
+        };
+
+        let mut workspace = Workspace::default();
+        open(
+            &mut workspace,
+            &tex_uri,
+            "\\addbibresource{main.bib}\n\\cite{foo}",
+            Language::Tex,
+        );
+        open(&mut workspace, &bib_uri, "@article{bar,}", Language::Bib);
+
+        let messages = |workspace: &Workspace, state: &mut DiagnosticState| {
+            let results = super::collect(workspace, &FxHashMap::default(), state);
+            let document = workspace.lookup(&tex_uri).unwrap();
+            results[document]
+                .iter()
+                .map(|diagnostic| diagnostic.message.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let mut state = DiagnosticState::default();
+        assert_eq!(
+            messages(&workspace, &mut state),
+            vec!["Undefined citation \"foo\""]
+        );
+        assert_eq!(
+            messages(&workspace, &mut state),
+            vec!["Undefined citation \"foo\""]
+        );
+
+        open(&mut workspace, &bib_uri, "@article{foo,}", Language::Bib);
+        assert!(messages(&workspace, &mut state).is_empty());
+    }
+}
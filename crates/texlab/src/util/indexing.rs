@@ -0,0 +1,148 @@
+use std::path::{Path, PathBuf};
+
+use base_db::IndexingConfig;
+use distro::Language;
+use ignore::{
+    gitignore::GitignoreBuilder,
+    overrides::{Override, OverrideBuilder},
+    WalkBuilder,
+};
+
+/// Finds the TeX and BibTeX files in the workspace folders.
+/// Files that are hidden, ignored by a `.gitignore` file or matched by an exclusion glob are skipped.
+pub fn scan(folders: &[PathBuf], config: &IndexingConfig) -> Vec<(PathBuf, Language)> {
+    let mut files = Vec::new();
+    for folder in folders {
+        let walker = WalkBuilder::new(folder)
+            .require_git(false)
+            .overrides(build_overrides(folder, &config.exclude))
+            .build();
+
+        for entry in walker.flatten() {
+            if !entry.file_type().map_or(false, |type_| type_.is_file()) {
+                continue;
+            }
+
+            let path = entry.into_path();
+            if let Some(language @ (Language::Tex | Language::Bib)) = Language::from_path(&path) {
+                files.push((path, language));
+            }
+        }
+    }
+
+    files
+}
+
+/// Checks whether a file that has been created after the initial scan should stay out of the index.
+/// This applies the same rules as [`scan`] to a single path.
+pub fn is_excluded(folders: &[PathBuf], config: &IndexingConfig, path: &Path) -> bool {
+    let Some(folder) = folders.iter().find(|folder| path.starts_with(folder)) else { return true };
+
+    let overrides = build_overrides(folder, &config.exclude);
+    for ancestor in path.ancestors().take_while(|dir| *dir != folder) {
+        let is_hidden = ancestor
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map_or(false, |name| name.starts_with('.'));
+
+        if is_hidden || overrides.matched(ancestor, ancestor != path).is_ignore() {
+            return true;
+        }
+    }
+
+    path.ancestors()
+        .skip(1)
+        .take_while(|dir| dir.starts_with(folder))
+        .filter(|dir| dir.join(".gitignore").is_file())
+        .any(|dir| {
+            let mut builder = GitignoreBuilder::new(dir);
+            builder.add(dir.join(".gitignore"));
+            builder.build().map_or(false, |gitignore| {
+                gitignore
+                    .matched_path_or_any_parents(path, false)
+                    .is_ignore()
+            })
+        })
+}
+
+fn build_overrides(folder: &Path, exclude: &[String]) -> Override {
+    let mut builder = OverrideBuilder::new(folder);
+    for glob in exclude {
+        if let Err(why) = builder.add(&format!("!{glob}")) {
+            log::warn!("Invalid exclusion glob \"{glob}\": {why}");
+        }
+    }
+
+    builder.build().unwrap_or_else(|_| Override::empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use base_db::IndexingConfig;
+    use distro::Language;
+
+    use super::{is_excluded, scan};
+
+    fn setup() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        for path in [
+            "main.tex",
+            "chapters/intro.tex",
+            "references.bib",
+            "figure.png",
+            "build/main.tex",
+            "drafts/old.tex",
+            ".hidden/secret.tex",
+        ] {
+            let path = dir.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "").unwrap();
+        }
+
+        std::fs::write(dir.path().join(".gitignore"), "build/\n").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_scan() {
+        let dir = setup();
+        let config = IndexingConfig {
+            enabled: true,
+            exclude: vec!["drafts/**".into()],
+        };
+
+        let mut files: Vec<_> = scan(&[dir.path().to_path_buf()], &config)
+            .into_iter()
+            .map(|(path, language)| (path.strip_prefix(&dir).unwrap().to_path_buf(), language))
+            .collect();
+
+        files.sort();
+        assert_eq!(
+            files,
+            vec![
+                (PathBuf::from("chapters/intro.tex"), Language::Tex),
+                (PathBuf::from("main.tex"), Language::Tex),
+                (PathBuf::from("references.bib"), Language::Bib),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_is_excluded() {
+        let dir = setup();
+        let folders = [dir.path().to_path_buf()];
+        let config = IndexingConfig {
+            enabled: true,
+            exclude: vec!["drafts/**".into()],
+        };
+
+        let check = |path: &str| is_excluded(&folders, &config, &dir.path().join(path));
+        assert!(!check("main.tex"));
+        assert!(!check("chapters/intro.tex"));
+        assert!(check("build/main.tex"));
+        assert!(check("drafts/old.tex"));
+        assert!(check(".hidden/secret.tex"));
+    }
+}
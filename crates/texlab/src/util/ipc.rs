@@ -0,0 +1,398 @@
+use std::{
+    fs::OpenOptions,
+    io::{BufRead, BufReader, Read, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+/// The maximum length of a line that is read from a client.
+const MAX_LINE_LENGTH: u64 = 64 * 1024;
+
+/// The time after which a client that does not send a complete request is disconnected.
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A request sent by `texlab inverse-search` to a running server.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct InverseSearchParams {
+    pub path: PathBuf,
+
+    /// The zero-based line number.
+    pub line: u32,
+}
+
+/// The address that a server listens on.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum Endpoint {
+    Tcp(u16),
+    Unix(PathBuf),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Instance {
+    endpoint: Endpoint,
+    token: String,
+    folders: Vec<PathBuf>,
+}
+
+/// Makes a running server reachable for `texlab inverse-search`.
+///
+/// Every server listens on a Unix domain socket (or a local TCP port on other platforms)
+/// and registers itself in a file in a per-user directory that is removed on drop.
+/// The registry file can only be read by the current user and contains a random token
+/// that clients have to send on the first line of every connection.
+#[derive(Debug)]
+pub struct IpcListener {
+    registry_path: PathBuf,
+    socket_path: Option<PathBuf>,
+}
+
+impl IpcListener {
+    pub fn bind<F>(folders: Vec<PathBuf>, handler: F) -> Result<Self>
+    where
+        F: Fn(InverseSearchParams) + Send + Sync + 'static,
+    {
+        Self::bind_in(&registry_dir()?, folders, handler)
+    }
+
+    fn bind_in<F>(registry_dir: &Path, folders: Vec<PathBuf>, handler: F) -> Result<Self>
+    where
+        F: Fn(InverseSearchParams) + Send + Sync + 'static,
+    {
+        create_private_dir(registry_dir)?;
+        let name = std::process::id().to_string();
+        let token = generate_token()?;
+        let handler = Arc::new(handler);
+
+        let (endpoint, socket_path) = listen(registry_dir, &name, &token, handler)?;
+        let instance = Instance {
+            endpoint,
+            token,
+            folders,
+        };
+
+        let registry_path = registry_dir.join(format!("{name}.json"));
+        write_private_file(&registry_path, &serde_json::to_vec(&instance)?)?;
+        Ok(Self {
+            registry_path,
+            socket_path,
+        })
+    }
+}
+
+impl Drop for IpcListener {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.registry_path);
+        if let Some(socket_path) = &self.socket_path {
+            let _ = std::fs::remove_file(socket_path);
+        }
+    }
+}
+
+#[cfg(unix)]
+fn listen<F>(
+    registry_dir: &Path,
+    name: &str,
+    token: &str,
+    handler: Arc<F>,
+) -> Result<(Endpoint, Option<PathBuf>)>
+where
+    F: Fn(InverseSearchParams) + Send + Sync + 'static,
+{
+    use std::os::unix::net::UnixListener;
+
+    let socket_path = registry_dir.join(format!("{name}.sock"));
+    let _ = std::fs::remove_file(&socket_path);
+    let listener = UnixListener::bind(&socket_path)?;
+
+    let token = token.to_string();
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            if stream.set_read_timeout(Some(READ_TIMEOUT)).is_ok() {
+                serve(stream, token.clone(), Arc::clone(&handler));
+            }
+        }
+    });
+
+    Ok((Endpoint::Unix(socket_path.clone()), Some(socket_path)))
+}
+
+#[cfg(not(unix))]
+fn listen<F>(
+    _registry_dir: &Path,
+    _name: &str,
+    token: &str,
+    handler: Arc<F>,
+) -> Result<(Endpoint, Option<PathBuf>)>
+where
+    F: Fn(InverseSearchParams) + Send + Sync + 'static,
+{
+    use std::net::{Ipv4Addr, TcpListener};
+
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+    let port = listener.local_addr()?.port();
+
+    let token = token.to_string();
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            if stream.set_read_timeout(Some(READ_TIMEOUT)).is_ok() {
+                serve(stream, token.clone(), Arc::clone(&handler));
+            }
+        }
+    });
+
+    Ok((Endpoint::Tcp(port), None))
+}
+
+/// Handles a connection on a separate thread so that a slow client cannot block other clients.
+fn serve<S, F>(stream: S, token: String, handler: Arc<F>)
+where
+    S: Read + Send + 'static,
+    F: Fn(InverseSearchParams) + Send + Sync + 'static,
+{
+    std::thread::spawn(move || {
+        let mut reader = BufReader::new(stream.take(2 * MAX_LINE_LENGTH));
+        let Some(client_token) = read_line(&mut reader) else { return };
+        if client_token != token {
+            log::warn!("Rejected inverse search request with an invalid token");
+            return;
+        }
+
+        let Some(line) = read_line(&mut reader) else { return };
+        match serde_json::from_str(&line) {
+            Ok(params) => handler(params),
+            Err(why) => log::warn!("Received invalid inverse search request: {why}"),
+        };
+    });
+}
+
+fn read_line(reader: &mut impl BufRead) -> Option<String> {
+    let mut line = String::new();
+    reader
+        .take(MAX_LINE_LENGTH)
+        .read_line(&mut line)
+        .ok()
+        .filter(|_| line.ends_with('\n'))?;
+
+    line.pop();
+    Some(line)
+}
+
+/// Forwards an inverse search request to the server that is responsible for the given file.
+/// Servers whose workspace folders contain the file are preferred.
+pub fn send_inverse_search(params: &InverseSearchParams) -> Result<()> {
+    send_inverse_search_in(&registry_dir()?, params)
+}
+
+fn send_inverse_search_in(registry_dir: &Path, params: &InverseSearchParams) -> Result<()> {
+    let mut instances = Vec::new();
+    for entry in std::fs::read_dir(registry_dir)?.flatten() {
+        let path = entry.path();
+        if path.extension().map_or(true, |ext| ext != "json") {
+            continue;
+        }
+
+        let Ok(data) = std::fs::read(&path) else { continue };
+        let Ok(instance) = serde_json::from_slice::<Instance>(&data) else { continue };
+        let Some(rank) = rank_instance(&instance, &params.path) else { continue };
+        instances.push((rank, path, instance));
+    }
+
+    instances.sort_by(|(a, _, _), (b, _, _)| b.cmp(a));
+
+    let message = format!("{}\n", serde_json::to_string(params)?);
+    for (_, registry_path, instance) in instances {
+        match connect(&instance.endpoint) {
+            Ok(mut stream) => {
+                stream.write_all(format!("{}\n", instance.token).as_bytes())?;
+                stream.write_all(message.as_bytes())?;
+                return Ok(());
+            }
+            Err(_) => {
+                // The server exited without unregistering itself.
+                let _ = std::fs::remove_file(registry_path);
+            }
+        };
+    }
+
+    bail!("No running server found for \"{}\"", params.path.display())
+}
+
+fn connect(endpoint: &Endpoint) -> std::io::Result<Box<dyn Write>> {
+    match endpoint {
+        Endpoint::Tcp(port) => {
+            let stream = std::net::TcpStream::connect((std::net::Ipv4Addr::LOCALHOST, *port))?;
+            Ok(Box::new(stream))
+        }
+        #[cfg(unix)]
+        Endpoint::Unix(path) => Ok(Box::new(std::os::unix::net::UnixStream::connect(path)?)),
+        #[cfg(not(unix))]
+        Endpoint::Unix(_) => Err(std::io::ErrorKind::Unsupported.into()),
+    }
+}
+
+fn rank_instance(instance: &Instance, path: &Path) -> Option<usize> {
+    if instance.folders.is_empty() {
+        return Some(0);
+    }
+
+    instance
+        .folders
+        .iter()
+        .filter(|folder| path.starts_with(folder))
+        .map(|folder| folder.components().count())
+        .max()
+}
+
+/// Returns a directory that belongs to the current user.
+fn registry_dir() -> Result<PathBuf> {
+    let Some(dir) = dirs::runtime_dir().or_else(dirs::cache_dir) else {
+        bail!("Unable to determine the runtime directory of the current user");
+    };
+
+    Ok(dir.join("texlab").join("instances"))
+}
+
+fn generate_token() -> Result<String> {
+    let mut bytes = [0; 16];
+    getrandom::getrandom(&mut bytes)?;
+    Ok(bytes.iter().map(|byte| format!("{byte:02x}")).collect())
+}
+
+fn create_private_dir(path: &Path) -> Result<()> {
+    std::fs::create_dir_all(path)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o700))?;
+    }
+
+    Ok(())
+}
+
+fn write_private_file(path: &Path, data: &[u8]) -> Result<()> {
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    options.open(path)?.write_all(data)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::Write,
+        path::{Path, PathBuf},
+        time::Duration,
+    };
+
+    use crossbeam_channel::Receiver;
+
+    use super::{
+        connect, rank_instance, send_inverse_search_in, Endpoint, Instance, InverseSearchParams,
+        IpcListener,
+    };
+
+    fn instance(folders: &[&str]) -> Instance {
+        Instance {
+            endpoint: Endpoint::Tcp(0),
+            token: String::new(),
+            folders: folders.iter().map(PathBuf::from).collect(),
+        }
+    }
+
+    fn bind(dir: &Path, folders: &[&str]) -> (IpcListener, Receiver<InverseSearchParams>) {
+        let (sender, receiver) = crossbeam_channel::unbounded();
+        let folders = folders.iter().map(PathBuf::from).collect();
+        let listener = IpcListener::bind_in(dir, folders, move |params| {
+            let _ = sender.send(params);
+        })
+        .unwrap();
+
+        (listener, receiver)
+    }
+
+    #[test]
+    fn test_rank_instance() {
+        let path = Path::new("/home/user/thesis/chapters/intro.tex");
+        assert_eq!(rank_instance(&instance(&[]), path), Some(0));
+        assert_eq!(rank_instance(&instance(&["/home/other"]), path), None);
+        assert_eq!(
+            rank_instance(&instance(&["/home/user", "/home/user/thesis"]), path),
+            Some(4)
+        );
+    }
+
+    #[test]
+    fn test_send_inverse_search() {
+        let dir = tempfile::tempdir().unwrap();
+        let (_listener, receiver) = bind(dir.path(), &[]);
+
+        let params = InverseSearchParams {
+            path: PathBuf::from("/home/user/thesis/main.tex"),
+            line: 42,
+        };
+
+        send_inverse_search_in(dir.path(), &params).unwrap();
+        let received = receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(received, params);
+    }
+
+    #[test]
+    fn test_send_inverse_search_no_server() {
+        let dir = tempfile::tempdir().unwrap();
+        let params = InverseSearchParams {
+            path: PathBuf::from("/home/user/thesis/main.tex"),
+            line: 0,
+        };
+
+        assert!(send_inverse_search_in(dir.path(), &params).is_err());
+    }
+
+    #[test]
+    fn test_invalid_token() {
+        let dir = tempfile::tempdir().unwrap();
+        let (listener, receiver) = bind(dir.path(), &[]);
+
+        let data = std::fs::read(&listener.registry_path).unwrap();
+        let instance: Instance = serde_json::from_slice(&data).unwrap();
+        let mut stream = connect(&instance.endpoint).unwrap();
+        stream
+            .write_all(b"invalid\n{\"path\":\"/main.tex\",\"line\":0}\n")
+            .unwrap();
+        drop(stream);
+
+        assert!(receiver.recv_timeout(Duration::from_millis(500)).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_registry_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let (listener, _) = bind(dir.path(), &[]);
+        let metadata = std::fs::metadata(&listener.registry_path).unwrap();
+        assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+    }
+
+    #[test]
+    fn test_remove_on_drop() {
+        let dir = tempfile::tempdir().unwrap();
+        let (listener, _) = bind(dir.path(), &[]);
+        let registry_path = listener.registry_path.clone();
+        drop(listener);
+        assert!(!registry_path.exists());
+    }
+}
@@ -0,0 +1,397 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::SystemTime,
+};
+
+use base_db::{semantics::tex::LinkKind, Document, DocumentData, Project, Workspace};
+use lsp_types::Url;
+use once_cell::sync::Lazy;
+use parking_lot::{Mutex, RwLock};
+use parser::SyntaxConfig;
+use rowan::ast::AstNode;
+use rustc_hash::{FxHashMap, FxHashSet};
+use smol_str::SmolStr;
+use syntax::latex;
+
+use super::components::{Component, ComponentCommand, COMPONENT_DATABASE};
+
+/// Commands and environments defined by the `.sty` and `.cls` files of the installed
+/// TeX distribution. Packages are analyzed on demand and kept in memory until they
+/// are modified on disk.
+#[derive(Debug, Default)]
+pub struct PackageIndex {
+    entries: Mutex<FxHashMap<PathBuf, PackageEntry>>,
+}
+
+#[derive(Debug)]
+struct PackageEntry {
+    modified: Option<SystemTime>,
+    config: SyntaxConfig,
+    component: Arc<Component>,
+}
+
+impl PackageIndex {
+    /// Returns the analyzed packages that are (transitively) loaded by the project.
+    /// Packages that were recorded by the engine in an `.fls` file are included as well.
+    /// Definitions that are already part of the bundled component database are omitted.
+    /// The packages are parsed with the configuration of the document at `uri`.
+    pub fn linked_components(
+        &self,
+        workspace: &Workspace,
+        uri: &Url,
+        project: &Project,
+    ) -> Vec<Arc<Component>> {
+        let config = &workspace.config_for(uri).syntax;
+        let mut stack = project_packages(project);
+
+        let mut visited = FxHashSet::default();
+        let mut components = Vec::new();
+        while let Some(name) = stack.pop() {
+            if !visited.insert(name.clone()) {
+                continue;
+            }
+
+            let known = COMPONENT_DATABASE.find(&name);
+            if let Some(known) = known {
+                stack.extend(known.references.iter().cloned());
+            }
+
+            let Some(path) = workspace.distro().file_name_db.get(&name) else { continue };
+            let Some(component) = self.analyze(path, config) else { continue };
+            stack.extend(component.references.iter().cloned());
+
+            let component = match known {
+                Some(known) => Arc::new(subtract(&component, known)),
+                None => component,
+            };
+
+            if !component.commands.is_empty() || !component.environments.is_empty() {
+                components.push(component);
+            }
+        }
+
+        components
+    }
+
+    /// Analyzes the packages that are loaded by the project of the document at `uri`
+    /// so that subsequent calls to [`Self::linked_components`] can use the cached results.
+    /// The workspace is only locked while looking up the files of the packages.
+    pub fn warm_up(&self, workspace: &RwLock<Workspace>, uri: &Url) {
+        let (mut stack, config) = {
+            let workspace = workspace.read();
+            let Some(document) = workspace.lookup(uri) else { return };
+            let project = workspace.project(document);
+            let config = workspace.config_for(uri).syntax.clone();
+            (project_packages(&project), config)
+        };
+
+        let mut visited = FxHashSet::default();
+        while !stack.is_empty() {
+            let mut paths = Vec::new();
+            let workspace = workspace.read();
+            for name in std::mem::take(&mut stack) {
+                if !visited.insert(name.clone()) {
+                    continue;
+                }
+
+                if let Some(known) = COMPONENT_DATABASE.find(&name) {
+                    stack.extend(known.references.iter().cloned());
+                }
+
+                let path = workspace.distro().file_name_db.get(&name);
+                paths.extend(path.map(Path::to_path_buf));
+            }
+
+            drop(workspace);
+            for path in paths {
+                let Some(component) = self.analyze(&path, &config) else { continue };
+                stack.extend(component.references.iter().cloned());
+            }
+        }
+    }
+
+    fn analyze(&self, path: &Path, config: &SyntaxConfig) -> Option<Arc<Component>> {
+        let modified = std::fs::metadata(path)
+            .and_then(|meta| meta.modified())
+            .ok();
+        if let Some(entry) = self.entries.lock().get(path) {
+            if entry.modified == modified && entry.config == *config {
+                return Some(Arc::clone(&entry.component));
+            }
+        }
+
+        let data = std::fs::read(path).ok()?;
+        let text = String::from_utf8_lossy(&data);
+        let file_name = path.file_name()?.to_str()?;
+        let component = Arc::new(analyze(file_name, &text, config));
+        log::debug!("Analyzed package {}", path.display());
+
+        self.entries.lock().insert(
+            path.to_path_buf(),
+            PackageEntry {
+                modified,
+                config: config.clone(),
+                component: Arc::clone(&component),
+            },
+        );
+
+        Some(component)
+    }
+}
+
+pub static PACKAGE_INDEX: Lazy<PackageIndex> = Lazy::new(PackageIndex::default);
+
+/// Extracts the public commands and environments defined by a package or class.
+pub fn analyze(file_name: &str, text: &str, config: &SyntaxConfig) -> Component {
+    let root = latex::SyntaxNode::new_root(parser::parse_latex(text, config));
+
+    let mut references = Vec::new();
+    let mut commands = Vec::new();
+    let mut environments = Vec::new();
+    for node in root.descendants() {
+        if let Some(include) = latex::Include::cast(node.clone()) {
+            let extension = match include.syntax().kind() {
+                latex::PACKAGE_INCLUDE => "sty",
+                latex::CLASS_INCLUDE => "cls",
+                _ => continue,
+            };
+
+            for path in include.path_list().iter().flat_map(|list| list.keys()) {
+                references.push(format!("{}.{extension}", path.to_string()).into());
+            }
+        } else if let Some(definition) = latex::EnvironmentDefinition::cast(node.clone()) {
+            let name = definition.name().and_then(|name| name.key());
+            environments.extend(name.map(|name| SmolStr::from(name.to_string())));
+        } else if let Some(definition) = latex::CommandDefinition::cast(node.clone()) {
+            commands.extend(definition.command().and_then(defined_command));
+        } else if let Some(command) = latex::GenericCommand::cast(node) {
+            let Some(token) = command.name() else { continue };
+            match &token.text()[1..] {
+                "NewDocumentCommand"
+                | "RenewDocumentCommand"
+                | "ProvideDocumentCommand"
+                | "DeclareDocumentCommand"
+                | "NewExpandableDocumentCommand"
+                | "DeclareExpandableDocumentCommand" => {
+                    commands.extend(defined_command(token));
+                }
+                "NewDocumentEnvironment"
+                | "RenewDocumentEnvironment"
+                | "ProvideDocumentEnvironment"
+                | "DeclareDocumentEnvironment" => {
+                    environments.extend(defined_environment(token));
+                }
+                _ => {}
+            };
+        }
+    }
+
+    commands.sort();
+    commands.dedup();
+    environments.sort();
+    environments.dedup();
+
+    Component {
+        file_names: vec![file_name.into()],
+        references,
+        commands: commands
+            .into_iter()
+            .map(|name| ComponentCommand {
+                name,
+                image: None,
+                glyph: None,
+                parameters: Vec::new(),
+            })
+            .collect(),
+        environments,
+    }
+}
+
+/// Returns the file names of the packages and classes that are loaded by the project.
+fn project_packages(project: &Project) -> Vec<SmolStr> {
+    project
+        .documents
+        .iter()
+        .flat_map(|document| package_links(document))
+        .chain(recorded_packages(project))
+        .collect()
+}
+
+/// Returns the file names of the packages and classes that are loaded by a document.
+pub fn package_links(document: &Document) -> impl Iterator<Item = SmolStr> + '_ {
+    document
+        .data
+        .as_tex()
+        .into_iter()
+        .flat_map(|data| data.semantics.links.iter())
+        .filter_map(|link| match link.kind {
+            LinkKind::Sty => Some(format!("{}.sty", link.path.text).into()),
+            LinkKind::Cls => Some(format!("{}.cls", link.path.text).into()),
+            _ => None,
+        })
+}
+
+/// Returns the file names of the packages and classes that were recorded by the engine.
+fn recorded_packages<'a>(project: &'a Project<'a>) -> impl Iterator<Item = SmolStr> + 'a {
+    project
+        .documents
+        .iter()
+        .filter_map(|document| match &document.data {
+            DocumentData::Fls(data) => Some(data),
+            _ => None,
+        })
+        .flat_map(|data| data.distro_inputs())
+        .filter(|path| {
+            matches!(
+                path.extension().and_then(|ext| ext.to_str()),
+                Some("sty" | "cls")
+            )
+        })
+        .filter_map(|path| path.file_name()?.to_str().map(SmolStr::from))
+}
+
+/// Finds the command that follows a definition like `\newcommand{\foo}` or `\newcommand\foo`.
+fn defined_command(definition: latex::SyntaxToken) -> Option<SmolStr> {
+    let token = next_argument(definition)?;
+    if token.kind() != latex::COMMAND_NAME {
+        return None;
+    }
+
+    let name = &token.text()[1..];
+    let is_public = !name.is_empty() && !name.contains(['@', ':', '_']);
+    is_public.then(|| name.into())
+}
+
+fn defined_environment(definition: latex::SyntaxToken) -> Option<SmolStr> {
+    let token = next_argument(definition)?;
+    (token.kind() == latex::WORD).then(|| token.text().into())
+}
+
+fn next_argument(definition: latex::SyntaxToken) -> Option<latex::SyntaxToken> {
+    std::iter::successors(definition.next_token(), |token| token.next_token()).find(|token| {
+        !matches!(
+            token.kind(),
+            latex::WHITESPACE | latex::LINE_BREAK | latex::COMMENT | latex::L_CURLY
+        )
+    })
+}
+
+fn subtract(component: &Component, known: &Component) -> Component {
+    let commands = component
+        .commands
+        .iter()
+        .filter(|command| {
+            known
+                .commands
+                .iter()
+                .all(|other| other.name != command.name)
+        })
+        .cloned()
+        .collect();
+
+    let environments = component
+        .environments
+        .iter()
+        .filter(|name| !known.environments.contains(name))
+        .cloned()
+        .collect();
+
+    Component {
+        file_names: component.file_names.clone(),
+        references: component.references.clone(),
+        commands,
+        environments,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base_db::{util::LineCol, Owner, Workspace};
+    use distro::Language;
+    use lsp_types::Url;
+    use parser::SyntaxConfig;
+
+    use super::{analyze, recorded_packages};
+
+    #[test]
+    fn test_commands() {
+        let text = r#"
+\ProvidesPackage{foo}
+\newcommand{\foo}[1]{#1}
+\newcommand*\bar{bar}
+\DeclareRobustCommand{\baz}{baz}
+\NewDocumentCommand{\qux}{m}{#1}
+\newcommand{\foo@internal}{}
+"#;
+
+        let component = analyze("foo.sty", text, &SyntaxConfig::default());
+        let names: Vec<_> = component
+            .commands
+            .iter()
+            .map(|command| command.name.as_str())
+            .collect();
+
+        assert_eq!(component.file_names, vec!["foo.sty"]);
+        assert_eq!(names, vec!["bar", "baz", "foo", "qux"]);
+    }
+
+    #[test]
+    fn test_environments() {
+        let text = r#"
+\newenvironment{foo}{\begin{center}}{\end{center}}
+\NewDocumentEnvironment{bar}{m}{}{}
+"#;
+
+        let component = analyze("foo.sty", text, &SyntaxConfig::default());
+        assert_eq!(component.environments, vec!["bar", "foo"]);
+    }
+
+    #[test]
+    fn test_references() {
+        let text = r#"
+\RequirePackage{amsmath,graphicx}
+\documentclass{article}
+"#;
+
+        let component = analyze("foo.sty", text, &SyntaxConfig::default());
+        assert_eq!(
+            component.references,
+            vec!["amsmath.sty", "graphicx.sty", "article.cls"]
+        );
+    }
+
+    #[test]
+    fn test_recorded_packages() {
+        let mut workspace = Workspace::default();
+        let main = Url::parse("file:///home/user/project/main.tex").unwrap();
+        let fls = Url::parse("file:///home/user/project/main.fls").unwrap();
+        let text = "PWD /home/user/project\n\
+                    INPUT /home/user/project/main.tex\n\
+                    INPUT /home/user/project/style.sty\n\
+                    INPUT /usr/share/texmf/tex/latex/amsmath/amsmath.sty\n\
+                    INPUT /usr/share/texmf/tex/latex/base/article.cls\n\
+                    INPUT /usr/share/texmf/tex/latex/base/size10.clo\n";
+
+        workspace.open(
+            main.clone(),
+            String::new(),
+            Language::Tex,
+            Owner::Client,
+            LineCol { line: 0, col: 0 },
+        );
+        workspace.open(
+            fls,
+            text.into(),
+            Language::Fls,
+            Owner::Server,
+            LineCol { line: 0, col: 0 },
+        );
+
+        let document = workspace.lookup(&main).unwrap();
+        let project = workspace.project(document);
+        let mut names: Vec<_> = recorded_packages(&project).collect();
+        names.sort();
+        assert_eq!(names, vec!["amsmath.sty", "article.cls"]);
+    }
+}
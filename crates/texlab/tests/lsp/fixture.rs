@@ -1,6 +1,6 @@
 use std::{
     path::{Path, PathBuf},
-    sync::Once,
+    sync::{Arc, Mutex, Once},
     thread::JoinHandle,
 };
 
@@ -9,9 +9,10 @@ use distro::Language;
 use lsp_server::Connection;
 use lsp_types::{
     notification::{DidOpenTextDocument, Exit, Initialized},
-    request::{Initialize, Shutdown},
-    ClientCapabilities, DidOpenTextDocumentParams, InitializeParams, InitializedParams, Location,
-    Position, Range, TextDocumentIdentifier, TextDocumentItem, TextDocumentPositionParams, Url,
+    request::{ApplyWorkspaceEdit, Initialize, Request, Shutdown},
+    ApplyWorkspaceEditParams, ApplyWorkspaceEditResponse, ClientCapabilities,
+    DidOpenTextDocumentParams, InitializeParams, InitializedParams, Location, Position, Range,
+    TextDocumentIdentifier, TextDocumentItem, TextDocumentPositionParams, Url,
 };
 use tempfile::{tempdir, TempDir};
 use texlab::{LspClient, Server};
@@ -49,11 +50,17 @@ impl Fixture {
             std::fs::write(&path, &text)?;
 
             let uri = Url::from_file_path(&path).unwrap();
-            let language = Language::from_path(&path).unwrap_or(Language::Tex);
+            let language = Language::from_bibliography_path(&path).unwrap_or(Language::Tex);
             let language_id = String::from(match language {
                 Language::Tex => "latex",
                 Language::Bib => "bibtex",
-                Language::Aux | Language::Log | Language::Root | Language::Tectonic => continue,
+                Language::CslJson => "json",
+                Language::Hayagriva => "yaml",
+                Language::Aux
+                | Language::Log
+                | Language::Fls
+                | Language::Root
+                | Language::Tectonic => continue,
             });
 
             client.send_notification::<DidOpenTextDocument>(DidOpenTextDocumentParams {
@@ -130,6 +137,7 @@ pub struct TestBed {
     _temp_dir: TempDir,
     temp_dir_path: PathBuf,
     client: LspClient,
+    applied_edits: Arc<Mutex<Vec<ApplyWorkspaceEditParams>>>,
     client_thread: Option<JoinHandle<()>>,
     server_thread: Option<JoinHandle<()>>,
 }
@@ -165,11 +173,29 @@ impl TestBed {
         let client = LspClient::new(client_conn.sender);
 
         let server_thread = std::thread::spawn(move || Server::new(server_conn).run().unwrap());
+        let applied_edits: Arc<Mutex<Vec<_>>> = Arc::default();
         let client_thread = {
             let client = client.clone();
+            let applied_edits = Arc::clone(&applied_edits);
             std::thread::spawn(move || {
                 for message in &client_conn.receiver {
                     match message {
+                        lsp_server::Message::Request(request)
+                            if request.method == ApplyWorkspaceEdit::METHOD =>
+                        {
+                            let params = serde_json::from_value(request.params).unwrap();
+                            applied_edits.lock().unwrap().push(params);
+
+                            let result = ApplyWorkspaceEditResponse {
+                                applied: true,
+                                failure_reason: None,
+                                failed_change: None,
+                            };
+
+                            client
+                                .send_response(lsp_server::Response::new_ok(request.id, result))
+                                .unwrap();
+                        }
                         lsp_server::Message::Request(request) => {
                             client
                                 .send_error(
@@ -209,6 +235,7 @@ impl TestBed {
             _temp_dir: temp_dir,
             temp_dir_path,
             client,
+            applied_edits,
             client_thread: Some(client_thread),
             server_thread: Some(server_thread),
         })
@@ -232,6 +259,11 @@ impl TestBed {
         &self.client
     }
 
+    /// Returns the workspace edits that were applied on behalf of the server.
+    pub fn applied_edits(&self) -> Vec<ApplyWorkspaceEditParams> {
+        self.applied_edits.lock().unwrap().clone()
+    }
+
     pub fn cursor(&self) -> Option<TextDocumentPositionParams> {
         let (document, cursor) = self
             .fixture
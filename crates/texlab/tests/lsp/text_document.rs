@@ -1,5 +1,7 @@
+mod code_action;
 mod completion;
 mod definition;
+mod diagnostic;
 mod document_highlight;
 mod document_link;
 mod document_symbol;
@@ -0,0 +1,219 @@
+use lsp_types::{
+    request::{CodeActionRequest, CodeActionResolveRequest},
+    ClientCapabilities, CodeAction, CodeActionCapabilityResolveSupport,
+    CodeActionClientCapabilities, CodeActionContext, CodeActionOrCommand, CodeActionParams, Range,
+    TextDocumentClientCapabilities,
+};
+
+use crate::fixture::TestBed;
+
+/// Returns the title of each code action together with the new texts of its edits.
+fn check(fixture: &str) -> Vec<(String, Vec<(String, String)>)> {
+    check_with_capabilities(fixture, ClientCapabilities::default())
+}
+
+/// Same as `check` but resolves the edits of the code actions lazily.
+fn check_with_resolve(fixture: &str) -> Vec<(String, Vec<(String, String)>)> {
+    let capabilities = ClientCapabilities {
+        text_document: Some(TextDocumentClientCapabilities {
+            code_action: Some(CodeActionClientCapabilities {
+                data_support: Some(true),
+                resolve_support: Some(CodeActionCapabilityResolveSupport {
+                    properties: vec!["edit".into()],
+                }),
+                ..CodeActionClientCapabilities::default()
+            }),
+            ..TextDocumentClientCapabilities::default()
+        }),
+        ..ClientCapabilities::default()
+    };
+
+    check_with_capabilities(fixture, capabilities)
+}
+
+fn check_with_capabilities(
+    fixture: &str,
+    capabilities: ClientCapabilities,
+) -> Vec<(String, Vec<(String, String)>)> {
+    let test_bed = TestBed::new(fixture).unwrap();
+    test_bed.initialize(capabilities).unwrap();
+
+    let position = test_bed.cursor().unwrap();
+    let actions = test_bed
+        .client()
+        .send_request::<CodeActionRequest>(CodeActionParams {
+            text_document: position.text_document,
+            range: Range::new(position.position, position.position),
+            context: CodeActionContext::default(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+        .unwrap()
+        .unwrap_or_default();
+
+    actions
+        .into_iter()
+        .map(|action| {
+            let CodeActionOrCommand::CodeAction(action) = action else { unreachable!() };
+            let action = resolve(&test_bed, action);
+            let mut edits: Vec<_> = action
+                .edit
+                .and_then(|edit| edit.changes)
+                .unwrap_or_default()
+                .into_iter()
+                .flat_map(|(uri, edits)| {
+                    let uri = test_bed.redact(&uri).to_string();
+                    edits
+                        .into_iter()
+                        .map(move |edit| (uri.clone(), edit.new_text))
+                })
+                .collect();
+
+            edits.sort();
+            (action.title, edits)
+        })
+        .collect()
+}
+
+fn resolve(test_bed: &TestBed, action: CodeAction) -> CodeAction {
+    if action.data.is_none() {
+        return action;
+    }
+
+    assert!(action.edit.is_none());
+    test_bed
+        .client()
+        .send_request::<CodeActionResolveRequest>(action)
+        .unwrap()
+}
+
+#[test]
+fn regenerate_key() {
+    let fixture = r#"
+%! main.bib
+@book{foo, author = {Knuth, Donald}, title = {The {\TeX}book}, year = 1984}
+     |
+@book{bar, author = {Knuth, Donald}, title = {The {\TeX}book}, year = 1984}
+
+%! main.tex
+\addbibresource{main.bib}
+\cite{foo} \cite{bar, foo}
+"#;
+
+    let actions = check(fixture);
+
+    let redacted = |name: &str| format!("redacted://{name}");
+    assert_eq!(
+        actions,
+        vec![
+            (
+                "Regenerate citation key: knuth1984TeXbook".to_string(),
+                vec![
+                    (redacted("main.bib"), "knuth1984TeXbook".to_string()),
+                    (redacted("main.tex"), "knuth1984TeXbook".to_string()),
+                    (redacted("main.tex"), "knuth1984TeXbook".to_string()),
+                ]
+            ),
+            (
+                "Regenerate all citation keys".to_string(),
+                vec![
+                    (redacted("main.bib"), "knuth1984TeXbook".to_string()),
+                    (redacted("main.bib"), "knuth1984TeXbooka".to_string()),
+                    (redacted("main.tex"), "knuth1984TeXbook".to_string()),
+                    (redacted("main.tex"), "knuth1984TeXbook".to_string()),
+                    (redacted("main.tex"), "knuth1984TeXbooka".to_string()),
+                ]
+            ),
+        ]
+    );
+
+    assert_eq!(check_with_resolve(fixture), actions);
+}
+
+#[test]
+fn unchanged_key() {
+    let actions = check(
+        r#"
+%! main.bib
+@book{knuth1984TeXbook, author = {Knuth, Donald}, title = {The {\TeX}book}, year = 1984}
+      |
+"#,
+    );
+
+    assert_eq!(actions, Vec::new());
+}
+
+#[test]
+fn fix_month() {
+    let actions = check(
+        r#"
+%! main.bib
+@article{foo, month = {January}, title = {Foo}}
+                         |
+"#,
+    );
+
+    assert_eq!(
+        actions.first(),
+        Some(&(
+            "Replace with \"1\"".to_string(),
+            vec![("redacted://main.bib".to_string(), "1".to_string())]
+        ))
+    );
+}
+
+#[test]
+fn fix_page_range() {
+    let actions = check(
+        r#"
+%! main.bib
+@article{foo, pages = {12-15, 17 - 19}, title = {Foo}}
+                         |
+"#,
+    );
+
+    assert_eq!(
+        actions.first(),
+        Some(&(
+            "Replace with \"12--15, 17--19\"".to_string(),
+            vec![(
+                "redacted://main.bib".to_string(),
+                "12--15, 17--19".to_string()
+            )]
+        ))
+    );
+}
+
+#[test]
+fn remove_doi_prefix() {
+    let actions = check(
+        r#"
+%! main.bib
+@article{foo, doi = {https://doi.org/10.1000/182}, title = {Foo}}
+                        |
+"#,
+    );
+
+    assert_eq!(
+        actions.first(),
+        Some(&(
+            "Remove DOI prefix".to_string(),
+            vec![("redacted://main.bib".to_string(), "10.1000/182".to_string())]
+        ))
+    );
+}
+
+#[test]
+fn invalid_month_without_fix() {
+    let actions = check(
+        r#"
+%! main.bib
+@article{foo, month = {Spring}, title = {Foo}}
+                        |
+"#,
+    );
+
+    assert!(actions
+        .iter()
+        .all(|(title, _)| title.starts_with("Regenerate")));
+}
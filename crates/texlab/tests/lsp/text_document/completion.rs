@@ -241,6 +241,26 @@ fn citation() {
     ));
 }
 
+#[test]
+fn citation_latex_markup() {
+    assert_json_snapshot!(complete(
+        r#"
+%! main.tex
+\documentclass{article}
+\bibliography{main}
+\begin{document}
+\cite{
+      |
+\end{document}
+
+%! main.bib
+@article{foo:2019,
+    author = {M{\"u}ller, J{\"{\i}}rgen},
+    title = {Die {Gr\"o{\ss}e} des $\alpha$-Zerfalls},
+}"#
+    ));
+}
+
 #[test]
 fn citation_open_brace() {
     assert_json_snapshot!(complete(
@@ -255,6 +275,73 @@ fn citation_open_brace() {
     ));
 }
 
+#[test]
+fn citation_csl_json() {
+    assert_json_snapshot!(complete(
+        r#"
+%! main.tex
+\addbibresource{refs.json}
+\cite{
+      |
+
+%! refs.json
+[
+  {"id": "foo:2019", "type": "article-journal", "title": "Baz Qux"},
+  {"id": "bar:2005"}
+]"#
+    ));
+}
+
+#[test]
+fn citation_csl_json_not_referenced() {
+    assert_json_snapshot!(complete(
+        r#"
+%! main.tex
+\addbibresource{main.bib}
+\cite{
+      |
+
+%! package.json
+[
+  {"id": "foo:2019", "type": "article-journal", "title": "Baz Qux"}
+]"#
+    ));
+}
+
+#[test]
+fn citation_hayagriva() {
+    assert_json_snapshot!(complete(
+        r#"
+%! main.tex
+\addbibresource{refs.yaml}
+\cite{
+      |
+
+%! refs.yaml
+foo:2019:
+  type: article
+  title: Baz Qux"#
+    ));
+}
+
+#[test]
+fn citation_project_config() {
+    assert_json_snapshot!(complete(
+        r#"
+%! texlab.toml
+[syntax]
+citation_commands = ["mycite"]
+
+%! main.tex
+\addbibresource{main.bib}
+\mycite{
+        |
+
+%! main.bib
+@article{foo,}"#
+    ));
+}
+
 #[test]
 fn citation_open_brace_multiple() {
     assert_json_snapshot!(complete(
@@ -705,6 +792,29 @@ Lorem ipsum dolor sit amet.
     ));
 }
 
+#[test]
+fn include_magic_root() {
+    assert_json_snapshot!(complete(
+        r#"
+%! main.tex
+\documentclass{article}
+\begin{document}
+\input{chapters/a}
+\end{document}
+
+%! chapters/variant.tex
+\documentclass{article}
+\begin{document}
+\input{a}
+\end{document}
+
+%! chapters/a.tex
+% !TEX root = ../main.tex
+\input{}
+       |"#
+    ));
+}
+
 #[test]
 fn theorem_begin() {
     assert_json_snapshot!(complete(
@@ -912,3 +1022,21 @@ fn issue_885() {
 "#
     ));
 }
+
+#[test]
+fn string_ref() {
+    assert_json_snapshot!(complete(
+        r#"
+%! main.tex
+\bibliography{journals,refs}
+
+%! journals.bib
+@string{jcp = {J. Chem. Phys.}}
+@string{jacs = {J. Am. Chem. Soc.}}
+
+%! refs.bib
+@article{foo, journal = ja}
+                         |
+                        ^^"#
+    ));
+}
@@ -77,6 +77,32 @@ fn command_definition() {
     )
 }
 
+#[test]
+fn command_definition_tectonic() {
+    check(
+        r#"
+%! Tectonic.toml
+[doc]
+name = "foo"
+bundle = "bar"
+
+[[output]]
+name = "default"
+type = "pdf"
+
+%! src/_preamble.tex
+\documentclass{article}
+\newcommand{\foo}{foo}
+            ^^^^
+^^^^^^^^^^^^^^^^^^^^^^
+
+%! src/index.tex
+\foo
+  |
+^^^^"#,
+    )
+}
+
 #[test]
 fn document() {
     check(
@@ -116,6 +142,23 @@ fn entry() {
     )
 }
 
+#[test]
+fn entry_csl_json() {
+    check(
+        r#"
+%! foo.tex
+\addbibresource{refs.json}
+\cite{foo}
+      |
+      ^^^
+
+%! refs.json
+[{"id": "foo", "type": "book", "title": "Bar"}]
+         ^^^
+ ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^"#,
+    )
+}
+
 #[test]
 fn string_simple() {
     check(
@@ -144,6 +187,20 @@ fn string_join() {
     )
 }
 
+#[test]
+fn string_case_insensitive() {
+    check(
+        r#"
+%! main.bib
+@string{foo = {bar}}
+        ^^^
+^^^^^^^^^^^^^^^^^^^^
+@article{bar, author = FOO}
+                        |
+                       ^^^"#,
+    )
+}
+
 #[test]
 fn string_field() {
     check(
@@ -154,3 +211,22 @@ fn string_field() {
                 |"#,
     )
 }
+
+#[test]
+fn string_other_file() {
+    check(
+        r#"
+%! main.tex
+\bibliography{journals,refs}
+
+%! journals.bib
+@string{jcp = {J. Chem. Phys.}}
+        ^^^
+^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+
+%! refs.bib
+@article{foo, journal = jcp}
+                         |
+                        ^^^"#,
+    )
+}
@@ -0,0 +1,284 @@
+use lsp_types::{
+    request::DocumentDiagnosticRequest, ClientCapabilities, DiagnosticClientCapabilities,
+    DocumentDiagnosticParams, DocumentDiagnosticReport, DocumentDiagnosticReportResult, Position,
+    Range, TextDocumentClientCapabilities,
+};
+
+use crate::fixture::TestBed;
+
+fn pull_diagnostics(
+    test_bed: &TestBed,
+    previous_result_id: Option<String>,
+) -> DocumentDiagnosticReport {
+    let text_document = test_bed.cursor().unwrap().text_document;
+    let result = test_bed
+        .client()
+        .send_request::<DocumentDiagnosticRequest>(DocumentDiagnosticParams {
+            text_document,
+            identifier: None,
+            previous_result_id,
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        })
+        .unwrap();
+
+    match result {
+        DocumentDiagnosticReportResult::Report(report) => report,
+        DocumentDiagnosticReportResult::Partial(_) => panic!("unexpected partial result"),
+    }
+}
+
+fn initialize(fixture: &str) -> TestBed {
+    let test_bed = TestBed::new(fixture).unwrap();
+    test_bed
+        .initialize(ClientCapabilities {
+            text_document: Some(TextDocumentClientCapabilities {
+                diagnostic: Some(DiagnosticClientCapabilities::default()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+        .unwrap();
+
+    test_bed
+}
+
+#[test]
+fn full_report() {
+    let test_bed = initialize(
+        r#"
+%! main.tex
+\begin{foo}
+\end{bar}
+|"#,
+    );
+
+    let DocumentDiagnosticReport::Full(report) = pull_diagnostics(&test_bed, None) else {
+        panic!("expected a full report");
+    };
+
+    let report = report.full_document_diagnostic_report;
+    assert!(report.result_id.is_some());
+    assert_eq!(report.items.len(), 1);
+    assert_eq!(report.items[0].message, "Mismatched environment");
+}
+
+#[test]
+fn unchanged_report() {
+    let test_bed = initialize(
+        r#"
+%! main.tex
+\begin{foo}
+\end{foo}
+|"#,
+    );
+
+    let DocumentDiagnosticReport::Full(report) = pull_diagnostics(&test_bed, None) else {
+        panic!("expected a full report");
+    };
+
+    let result_id = report.full_document_diagnostic_report.result_id;
+    assert!(matches!(
+        pull_diagnostics(&test_bed, result_id),
+        DocumentDiagnosticReport::Unchanged(_)
+    ));
+}
+
+#[test]
+fn missing_crossref() {
+    let test_bed = initialize(
+        r#"
+%! main.bib
+@inproceedings{foo, title = {Foo}, crossref = {bar}, xdata = {baz, qux}}
+@proceedings{baz, title = {Baz}}
+|"#,
+    );
+
+    let DocumentDiagnosticReport::Full(report) = pull_diagnostics(&test_bed, None) else {
+        panic!("expected a full report");
+    };
+
+    let messages: Vec<_> = report
+        .full_document_diagnostic_report
+        .items
+        .into_iter()
+        .map(|diagnostic| (diagnostic.message, diagnostic.range))
+        .collect();
+
+    assert_eq!(
+        messages,
+        vec![
+            (
+                String::from("Unknown entry \"bar\""),
+                Range::new(Position::new(0, 47), Position::new(0, 50))
+            ),
+            (
+                String::from("Unknown entry \"qux\""),
+                Range::new(Position::new(0, 67), Position::new(0, 70))
+            ),
+        ]
+    );
+}
+
+#[test]
+fn undefined_citation() {
+    let test_bed = initialize(
+        r#"
+%! main.tex
+\addbibresource{main.bib}
+\addbibresource{refs.json}
+\cite{foo, bar, baz} \nocite{*}
+|
+
+%! main.bib
+@article{foo, title = {Foo}}
+
+%! refs.json
+[{"id": "bar", "title": "Bar"}]"#,
+    );
+
+    let DocumentDiagnosticReport::Full(report) = pull_diagnostics(&test_bed, None) else {
+        panic!("expected a full report");
+    };
+
+    let messages: Vec<_> = report
+        .full_document_diagnostic_report
+        .items
+        .into_iter()
+        .map(|diagnostic| diagnostic.message)
+        .collect();
+
+    assert_eq!(messages, vec!["Undefined citation \"baz\""]);
+}
+
+#[test]
+fn undefined_citation_build_log() {
+    let test_bed = initialize(
+        r#"
+%! main.tex
+\documentclass{article}
+\addbibresource{main.bib}
+\begin{document}
+\cite{foo, bar}
+|
+\end{document}
+
+%! main.bib
+@article{foo, title = {Foo}}
+
+%! main.log
+(./main.tex
+
+LaTeX Warning: Citation `bar' on page 1 undefined on input line 4.
+
+
+LaTeX Warning: Citation `baz' on page 1 undefined on input line 4.
+
+)"#,
+    );
+
+    let DocumentDiagnosticReport::Full(report) = pull_diagnostics(&test_bed, None) else {
+        panic!("expected a full report");
+    };
+
+    let messages: Vec<_> = report
+        .full_document_diagnostic_report
+        .items
+        .into_iter()
+        .map(|diagnostic| diagnostic.message)
+        .collect();
+
+    assert_eq!(
+        messages,
+        vec![
+            "Undefined citation \"bar\"",
+            "LaTeX Warning: Citation `baz' on page 1 undefined on input line 4."
+        ]
+    );
+}
+
+#[test]
+fn undefined_citation_without_bibliography() {
+    let test_bed = initialize(
+        r#"
+%! main.tex
+\cite{foo}
+|"#,
+    );
+
+    let DocumentDiagnosticReport::Full(report) = pull_diagnostics(&test_bed, None) else {
+        panic!("expected a full report");
+    };
+
+    assert!(report.full_document_diagnostic_report.items.is_empty());
+}
+
+#[test]
+fn invalid_field_values() {
+    let test_bed = initialize(
+        r#"
+%! main.bib
+@book{foo,
+    date = {04/05/2004},
+    urldate = {2004-04-05},
+    month = {January},
+    pages = {12-15},
+    isbn = {0-201-13447-1},
+    doi = {https://doi.org/10.1000/182},
+    url = {example.com},
+}
+|"#,
+    );
+
+    let DocumentDiagnosticReport::Full(report) = pull_diagnostics(&test_bed, None) else {
+        panic!("expected a full report");
+    };
+
+    let messages: Vec<_> = report
+        .full_document_diagnostic_report
+        .items
+        .into_iter()
+        .map(|diagnostic| diagnostic.message)
+        .collect();
+
+    assert_eq!(
+        messages,
+        vec![
+            "Expecting a date in the EDTF format: \"YYYY-MM-DD\"",
+            "Expecting the month as an integer",
+            "Expecting a page range like \"12--15\"",
+            "Invalid ISBN",
+            "Expecting a DOI without URL prefix",
+            "Invalid URL",
+        ]
+    );
+}
+
+#[test]
+fn undefined_string() {
+    let test_bed = initialize(
+        r#"
+%! main.tex
+\bibliography{journals,refs}
+
+%! journals.bib
+@string{jcp = {J. Chem. Phys.}}
+
+%! refs.bib
+@article{foo, journal = JCP # jcpl, month = jan}
+|"#,
+    );
+
+    let DocumentDiagnosticReport::Full(report) = pull_diagnostics(&test_bed, None) else {
+        panic!("expected a full report");
+    };
+
+    let messages: Vec<_> = report
+        .full_document_diagnostic_report
+        .items
+        .into_iter()
+        .map(|diagnostic| diagnostic.message)
+        .collect();
+
+    assert_eq!(messages, vec!["Undefined string \"jcpl\""]);
+}
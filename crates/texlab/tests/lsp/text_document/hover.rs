@@ -79,6 +79,40 @@ fn citation_inside_entry() {
     ));
 }
 
+#[test]
+fn citation_crossref() {
+    assert_json_snapshot!(find_hover(
+        r#"
+%! main.bib
+@inproceedings{foo, author = {Foo Bar}, title = {Baz Qux}, crossref = {proc}}
+
+%! proc.bib
+@proceedings{proc, title = {Proceedings}, publisher = {Publisher}, year = 2020}
+
+%! main.tex
+\addbibresource{main.bib}
+\addbibresource{proc.bib}
+\cite{foo}
+       |
+      ^^^"#
+    ));
+}
+
+#[test]
+fn citation_csl_json() {
+    assert_json_snapshot!(find_hover(
+        r#"
+%! refs.json
+[{"id": "foo", "type": "book", "author": [{"family": "Bar", "given": "Foo"}], "title": "Baz Qux", "issued": {"date-parts": [[1337]]}}]
+
+%! main.tex
+\addbibresource{refs.json}
+\cite{foo}
+       |
+      ^^^"#
+    ));
+}
+
 #[test]
 fn component_known_package() {
     assert_json_snapshot!(find_hover(
@@ -223,6 +257,24 @@ fn label_theorem_child_file() {
     ));
 }
 
+#[test]
+fn label_equation_project_config() {
+    assert_json_snapshot!(find_hover(
+        r#"
+%! texlab.toml
+[syntax]
+math_environments = ["mymath"]
+
+%! main.tex
+\begin{mymath}\label{eq:foo}
+    1 + 1 = 2
+\end{mymath}
+\ref{eq:foo}
+       |
+     ^^^^^^"#
+    ));
+}
+
 #[test]
 fn label_theorem_child_file_mumber() {
     assert_json_snapshot!(find_hover(
@@ -244,3 +296,31 @@ fn label_theorem_child_file_mumber() {
 \newlabel{thm:foo}{{1}{1}{Foo}{lemma.1}{}}"#
     ));
 }
+
+#[test]
+fn string_other_file() {
+    assert_json_snapshot!(find_hover(
+        r#"
+%! main.tex
+\bibliography{journals,refs}
+
+%! journals.bib
+@string{jcp = {J. Chem. Phys.}}
+
+%! refs.bib
+@article{foo, journal = jcp}
+                         |
+                        ^^^"#
+    ));
+}
+
+#[test]
+fn string_month_macro() {
+    assert_json_snapshot!(find_hover(
+        r#"
+%! main.bib
+@article{foo, month = mar}
+                       |
+                      ^^^"#
+    ));
+}
@@ -150,6 +150,29 @@ fn label_definition_include_decl() {
     )
 }
 
+#[test]
+fn label_definition_recorder() {
+    check(
+        r#"
+%! foo.tex
+\label{foo}
+        |
+
+%! bar.tex
+\ref{foo}
+     ^^^
+\loadchapter{foo}
+
+%! bar.fls
+INPUT bar.tex
+INPUT foo.tex
+"#,
+        ReferenceContext {
+            include_declaration: false,
+        },
+    )
+}
+
 #[test]
 fn label_reference() {
     check(
@@ -216,6 +239,24 @@ fn string_reference() {
     )
 }
 
+#[test]
+fn string_reference_case_insensitive() {
+    check(
+        r#"
+%! main.bib
+@string{foo = {Foo}}
+@article{bar, author = foo}
+                        |
+                       ^^^
+@article{baz, author = Foo}
+                       ^^^
+"#,
+        ReferenceContext {
+            include_declaration: false,
+        },
+    )
+}
+
 #[test]
 fn string_reference_include_decl() {
     check(
@@ -268,3 +309,27 @@ fn string_definition_include_decl() {
         },
     )
 }
+
+#[test]
+fn string_other_file() {
+    check(
+        r#"
+%! main.tex
+\bibliography{journals,refs}
+
+%! journals.bib
+@string{jcp = {J. Chem. Phys.}}
+        ^^^
+
+%! refs.bib
+@article{foo, journal = jcp}
+                         |
+                        ^^^
+@article{bar, journal = jcp # { Letters}}
+                        ^^^
+"#,
+        ReferenceContext {
+            include_declaration: true,
+        },
+    )
+}
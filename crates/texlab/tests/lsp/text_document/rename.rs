@@ -104,3 +104,25 @@ fn label() {
         "bar",
     )
 }
+
+#[test]
+fn string() {
+    check(
+        r#"
+%! main.tex
+\bibliography{journals,refs}
+
+%! journals.bib
+@string{jcp = {J. Chem. Phys.}}
+        ^^^
+
+%! refs.bib
+@article{foo, journal = jcp}
+                         |
+                        ^^^
+@article{bar, journal = jcp # { Letters}}
+                        ^^^
+"#,
+        "jchemphys",
+    )
+}
@@ -1 +1,2 @@
+mod execute_command;
 mod symbol;
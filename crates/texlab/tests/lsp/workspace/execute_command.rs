@@ -0,0 +1,129 @@
+use lsp_types::{
+    notification::DidChangeConfiguration, request::ExecuteCommand, ClientCapabilities,
+    DidChangeConfigurationParams, DocumentChanges, ExecuteCommandParams, Location, OneOf, Position,
+    Range, TextDocumentIdentifier,
+};
+
+use crate::fixture::TestBed;
+
+#[test]
+fn find_duplicate_entries() {
+    let test_bed = TestBed::new(
+        r#"
+%! main.bib
+@book{knuth, title = {The {\TeX}book}, year = 1984}
+      ^^^^^
+@book{knuth84, title = {The \TeX{}book}, year = {1984}}
+      ^^^^^^^
+@book{other, title = {The METAFONTbook}, year = 1986}
+|
+
+%! main.tex
+\addbibresource{main.bib}"#,
+    )
+    .unwrap();
+
+    test_bed.initialize(ClientCapabilities::default()).unwrap();
+
+    let text_document = TextDocumentIdentifier::new(test_bed.cursor().unwrap().text_document.uri);
+    let result = test_bed
+        .client()
+        .send_request::<ExecuteCommand>(ExecuteCommandParams {
+            command: "texlab.findDuplicateEntries".into(),
+            arguments: vec![serde_json::json!({ "textDocument": text_document })],
+            work_done_progress_params: Default::default(),
+        })
+        .unwrap()
+        .unwrap();
+
+    let groups: Vec<Vec<(String, Location)>> = result
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|group| {
+            group
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|entry| {
+                    let key = entry["key"].as_str().unwrap().to_string();
+                    let location = serde_json::from_value(entry["location"].clone()).unwrap();
+                    (key, location)
+                })
+                .collect()
+        })
+        .collect();
+
+    let locations = test_bed.locations();
+    assert_eq!(
+        groups,
+        vec![vec![
+            ("knuth".to_string(), locations[0].clone()),
+            ("knuth84".to_string(), locations[1].clone()),
+        ]]
+    );
+}
+
+#[test]
+fn import_entry() {
+    let test_bed = TestBed::new(
+        r#"
+%! main.bib
+@book{knuth, title = {The {\TeX}book}, year = 1984}
+|"#,
+    )
+    .unwrap();
+
+    test_bed.initialize(ClientCapabilities::default()).unwrap();
+
+    let database = test_bed.directory().join("database.txt");
+    std::fs::write(
+        &database,
+        "@article{knuth, doi = {10.1145/359340.359342}, title = {Literate Programming}}",
+    )
+    .unwrap();
+
+    test_bed
+        .client()
+        .send_notification::<DidChangeConfiguration>(DidChangeConfigurationParams {
+            settings: serde_json::json!({ "importEntry": { "database": database } }),
+        })
+        .unwrap();
+
+    let text_document = TextDocumentIdentifier::new(test_bed.cursor().unwrap().text_document.uri);
+    let result = test_bed
+        .client()
+        .send_request::<ExecuteCommand>(ExecuteCommandParams {
+            command: "texlab.importEntry".into(),
+            arguments: vec![serde_json::json!({
+                "textDocument": text_document,
+                "id": "doi:10.1145/359340.359342",
+            })],
+            work_done_progress_params: Default::default(),
+        })
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(result, serde_json::json!({ "key": "knutha" }));
+
+    let edits = test_bed.applied_edits();
+    assert_eq!(edits.len(), 1);
+
+    let Some(DocumentChanges::Edits(changes)) = &edits[0].edit.document_changes else {
+        panic!("expected versioned document changes");
+    };
+
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].text_document.uri, text_document.uri);
+    assert_eq!(changes[0].text_document.version, Some(0));
+
+    let OneOf::Left(edit) = &changes[0].edits[0] else {
+        panic!("expected a plain text edit");
+    };
+
+    assert_eq!(
+        edit.range,
+        Range::new(Position::new(0, 51), Position::new(0, 51))
+    );
+    insta::assert_snapshot!(edit.new_text);
+}